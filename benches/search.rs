@@ -0,0 +1,80 @@
+//! Times the path finders on large mazes.
+//!
+//! Run with `cargo bench --bench search`. The mazes are generated from fixed
+//! seeds, so that runs can be compared.
+
+use std::{fmt::Debug, thread, time::Duration};
+
+use maze::{maze::generator::WalkGenerator, prelude::*};
+
+/// Mazes per finder and size.
+const MAZES: u64 = 3;
+
+const DENSITY: u8 = 30;
+
+fn maze<const N: usize>(seed: u64) -> Maze<N> {
+    let mut generator_seed = [0; 32];
+    generator_seed[..8].copy_from_slice(&seed.to_le_bytes());
+    let last = N as i64 - 1;
+
+    Maze::with_generator(
+        Coordinates::new(0, 0),
+        Coordinates::new(last, last),
+        DENSITY,
+        seed,
+        WalkGenerator::new(Some(generator_seed)),
+    )
+}
+
+/// Prints the mean time to solve, number of expansions and path length.
+fn bench<const N: usize, F: FrontierManager + Debug>(name: &str) {
+    let mut elapsed = Duration::ZERO;
+    let (mut steps, mut cells) = (0, 0);
+
+    for seed in 0..MAZES {
+        let maze = maze::<N>(seed).with_frontier_trace(false);
+        let finder = PathFinder::<N, F>::new(&maze);
+        let mut simulator = Simulator::new(finder, Headless, maze);
+
+        let stats = simulator.run_episodes(1).unwrap()[0];
+        assert!(stats.terminated, "{name} did not solve maze {seed}");
+
+        elapsed += stats.elapsed;
+        steps += stats.steps;
+        cells += simulator.environment().current_path.len();
+    }
+
+    println!(
+        "{N:>5} {name:<8} {:>14?} {:>10} {:>8}",
+        elapsed / MAZES as u32,
+        steps / MAZES as usize,
+        cells / MAZES as usize
+    );
+}
+
+fn sizes<const N: usize>() {
+    bench::<N, AStarFinder>("astar");
+    bench::<N, GreedyFinder>("greedy");
+    bench::<N, DFSFinder>("dfs");
+    bench::<N, BFSFinder>("bfs");
+}
+
+fn main() {
+    // The simulator keeps the maze inline, a 1000x1000 maze doesn't fit the
+    // stack of the main thread
+    thread::Builder::new()
+        .stack_size(256 << 20)
+        .spawn(|| {
+            println!("{DENSITY}% blocked, mean of {MAZES} mazes");
+            println!(
+                "{:>5} {:<8} {:>14} {:>10} {:>8}",
+                "size", "finder", "time", "expanded", "path"
+            );
+
+            sizes::<200>();
+            sizes::<1000>();
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+}
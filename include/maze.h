@@ -0,0 +1,135 @@
+/*
+ * C API of the maze crate, implemented in src/ffi.rs, whose tests check
+ * that the declarations, constants and structs below match it.
+ *
+ * Mazes and solvers are opaque handles. Every fallible function returns a
+ * MazeStatus, MAZE_OK on success. Cells are given row after row, the cell
+ * (x, y) of a maze of side `size` being at index `x * size + y`.
+ *
+ * Supported sides: 8, 10, 16, 20, 30, 32, 40, 50, 64, 100.
+ */
+
+#ifndef MAZE_H
+#define MAZE_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef enum MazeStatus {
+    MAZE_OK = 0,
+    MAZE_NULL_POINTER = 1,
+    /* The side of the maze isn't supported. */
+    MAZE_INVALID_SIZE = 2,
+    /* An unknown generator or finder, or a density above 100. */
+    MAZE_INVALID_ARGUMENT = 3,
+    MAZE_OUT_OF_BOUNDS = 4,
+    /* The finder failed to act. */
+    MAZE_AGENT_ERROR = 5,
+    /* The buffer can't hold the result, whose length was written back. */
+    MAZE_BUFFER_TOO_SMALL = 6,
+    MAZE_PANIC = 7,
+} MazeStatus;
+
+enum {
+    MAZE_GENERATOR_BACKTRACKING = 0,
+    MAZE_GENERATOR_RANDOM_WALK = 1,
+};
+
+enum {
+    MAZE_FINDER_ASTAR = 0,
+    MAZE_FINDER_GREEDY = 1,
+    MAZE_FINDER_BFS = 2,
+    MAZE_FINDER_DFS = 3,
+    MAZE_FINDER_RANDOM = 4,
+};
+
+/* Cell values. Doors, keys and items are or'ed with their index, at most
+ * MAZE_CELL_INDEX. */
+enum {
+    MAZE_CELL_FREE = 0,
+    MAZE_CELL_BLOCKED = 1,
+    MAZE_CELL_DOOR = 0x40,
+    MAZE_CELL_KEY = 0x80,
+    MAZE_CELL_ITEM = 0xC0,
+    MAZE_CELL_INDEX = 0x3F,
+};
+
+typedef struct MazeHandle MazeHandle;
+typedef struct MazeSolver MazeSolver;
+
+/* Coordinates of a cell, x being the row. */
+typedef struct MazeCoordinates {
+    int64_t x;
+    int64_t y;
+} MazeCoordinates;
+
+typedef struct MazeStats {
+    /* Number of expansions. */
+    size_t steps;
+    /* Distinct cells expanded. */
+    size_t expanded;
+    size_t frontier;
+    /* Cells of the current path, start and end included. */
+    size_t path_length;
+    /* The finder stopped, reaching the goal or exhausting its frontier. */
+    bool finished;
+    bool reached_goal;
+} MazeStats;
+
+/* Generates a maze whose layout is determined by `seed`. */
+MazeStatus maze_generate(size_t size, uint8_t density, uint64_t seed,
+                         uint32_t generator, MazeCoordinates start,
+                         MazeCoordinates goal, MazeHandle **out);
+
+/* Creates a maze from `size * size` cells, MAZE_CELL_FREE being free, doors,
+ * keys and items or'ed with their index, and anything else blocked. Start
+ * and goal are freed. */
+MazeStatus maze_from_cells(size_t size, const uint8_t *cells,
+                           MazeCoordinates start, MazeCoordinates goal,
+                           MazeHandle **out);
+
+/* Side of the maze, or 0 for NULL. */
+size_t maze_size(const MazeHandle *maze);
+
+/* Copies the cell values, doors, keys and items past index 63 being written
+ * as walls and free cells. `len` must be at least `size * size`. */
+MazeStatus maze_get_cells(const MazeHandle *maze, uint8_t *cells, size_t len);
+
+/* Frees a maze that wasn't given to a solver. NULL is ignored. */
+void maze_free(MazeHandle *maze);
+
+/* Creates a solver running `finder`. On success the solver takes the maze,
+ * which must not be used nor freed anymore. */
+MazeStatus maze_solver_new(MazeHandle *maze, uint32_t finder, MazeSolver **out);
+
+/* Expands one cell. `finished`, if not NULL, tells whether the finder
+ * stopped. */
+MazeStatus maze_solver_step(MazeSolver *solver, bool *finished);
+
+/* Steps until the finder stops, or at most `max_steps` times unless it is
+ * 0. */
+MazeStatus maze_solver_run(MazeSolver *solver, size_t max_steps, bool *finished);
+
+MazeStatus maze_solver_stats(const MazeSolver *solver, MazeStats *out);
+
+/* Copies the current path, from the start. Its length is written to `len`,
+ * even when `capacity` is too small. */
+MazeStatus maze_solver_path(const MazeSolver *solver, MazeCoordinates *path,
+                            size_t capacity, size_t *len);
+
+/* Frees a solver and its maze. NULL is ignored. */
+void maze_solver_free(MazeSolver *solver);
+
+/* Static description of a status. */
+const char *maze_status_message(int32_t status);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* MAZE_H */
@@ -18,12 +18,12 @@ use crate::environment::Environment;
 /// An [`Agent`] is something acts in an environment. Typically, they have:
 /// - prior knowledge about  the environment
 /// - stimuli received from the environment, which can include observations
-///   about the environment
-///  (e.g., light, sound, keyboard commands, web requests) as well as actions
-/// that the environment  imposes on the agent (e.g., bumping the agent)
+///   about the environment (e.g., light, sound, keyboard commands, web
+///   requests) as well as actions that the environment imposes on the agent
+///   (e.g., bumping the agent)
 /// - past experiences, including history of interaction with the environment
-///   (its previous actions and stimuli)
-/// and other data, from which it can learn
+///   (its previous actions and stimuli) and other data, from which it can
+///   learn
 /// - goals that it must try to achieve or preferences over states of the world
 /// - abilities, the primitive actions the agent is capable of carrying out.
 pub trait Agent {
@@ -42,7 +42,9 @@ pub trait Agent {
     type Environment: Environment<Action = Self::Action, Stimuli = Self::Stimuli>;
 
     /// Initializes the Agent based on the environment.
-    fn new(environment: &Self::Environment) -> Self;
+    fn new(environment: &Self::Environment) -> Self
+    where
+        Self: Sized;
 
     /// Acts in the environment.
     ///
@@ -53,4 +55,15 @@ pub trait Agent {
 
     /// Checks if the action still was work to do.
     fn should_stop(&self) -> bool;
+
+    /// Prepares the agent for a new episode in a freshly reset environment.
+    ///
+    /// By default the agent is rebuilt from scratch; agents that learn across
+    /// episodes override it to keep their knowledge.
+    fn reset(&mut self, environment: &Self::Environment)
+    where
+        Self: Sized,
+    {
+        *self = Self::new(environment);
+    }
 }
@@ -1,35 +1,913 @@
-use std::io::stdout;
-
-use solver::agent::Agent;
-use solver::maze::agent::astar::AStarFinder;
-use solver::maze::agent::greedy::GreedyFinder;
-use solver::maze::agent::PathFinder;
-use solver::maze::coordinates::Coordinates;
-use solver::maze::render::DefaultRenderer;
-use solver::maze::Maze;
-use solver::simulator::Simulator;
-
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    const N: usize = 50;
-    
-    let density = 10;
-
-    let start = Coordinates::new(0, 0);
-    let goal = Coordinates::new(49, 49);
-
-    let maze = Maze::<N>::new(start, goal, density);
-    let path_finder = PathFinder::<N, GreedyFinder>::new(&maze);
-
-    let renderer  = DefaultRenderer::new(stdout());
-
-    let mut simulator = Simulator::new(
-        path_finder,
-        renderer,
-        maze,
+//! Command-line interface of the maze solvers
+//!
+//! ```text
+//! path_finder generate --size 32 --seed 7 -o maze.txt
+//! path_finder solve maze.txt --finder astar --heuristic manhattan --render search
+//! path_finder bench --sizes 16,32 --finders astar,greedy,dfs --format csv
+//! path_finder render maze.txt -o maze.png --finder bfs --heat-map
+//! path_finder route romania.txt --from Arad --to Bucharest --finder ucs
+//! ```
+//!
+//! Without a subcommand the terminal application opens.
+
+use std::{
+    error::Error,
+    fmt::Debug,
+    fs::{self, File},
+    io::{self, stdout, BufWriter, Write},
+    path::PathBuf,
+    time::Duration,
+};
+
+use clap::{error::ErrorKind, Args, CommandFactory, Parser, Subcommand, ValueEnum};
+
+use maze::{
+    graph::{Graph, RouteFinding},
+    maze::{
+        agent::heuristic::{Chebyshev, Distance, Euclidean, Manhattan},
+        file::MazeFile,
+        generator::{RbGenerator, WalkGenerator},
+        image::{ImageExporter, WallStyle},
+        tui::App,
+        with_size, SIZES,
+    },
+    prelude::*,
+    render::FrameWriter,
+    search::Planner,
+    simulator::EpisodeStats,
+};
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "path_finder",
+    version,
+    about = "Generates, solves and draws mazes"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Generates a maze and writes it as a maze file
+    Generate(GenerateArgs),
+    /// Solves a maze file
+    Solve(SolveArgs),
+    /// Runs finders on generated mazes and reports their statistics
+    Bench(BenchArgs),
+    /// Draws a maze file as a PNG or SVG image
+    Render(RenderArgs),
+    /// Finds a route on a graph file, an edge list or a DOT file
+    Route(RouteArgs),
+    /// Opens the terminal application, the default
+    Tui(TuiArgs),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum GeneratorArg {
+    Backtracking,
+    Walk,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum FinderArg {
+    Astar,
+    Greedy,
+    Bfs,
+    Dfs,
+    Random,
+    /// Uniform cost search
+    Ucs,
+}
+
+impl FinderArg {
+    fn is_informed(&self) -> bool {
+        matches!(self, Self::Astar | Self::Greedy)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+enum HeuristicArg {
+    #[default]
+    Euclidean,
+    Manhattan,
+    Chebyshev,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+enum ConnectivityArg {
+    #[default]
+    #[value(name = "4")]
+    Four,
+    #[value(name = "8")]
+    Eight,
+}
+
+impl From<ConnectivityArg> for Connectivity {
+    fn from(value: ConnectivityArg) -> Self {
+        match value {
+            ConnectivityArg::Four => Self::Four,
+            ConnectivityArg::Eight => Self::Eight,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum RenderMode {
+    /// Prints the path once solved
+    None,
+    /// Draws every step in a text style
+    Text,
+    /// Draws the explored cells with live counters
+    Search,
+    /// Draws every step, with keys to pause, step and abort
+    Interactive,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ReportFormat {
+    Table,
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum WallsArg {
+    Blocks,
+    Thin,
+}
+
+/// Finder and heuristic, shared by the subcommands running searches.
+#[derive(Debug, Args)]
+struct FinderArgs {
+    /// Distance to the goal estimated by astar and greedy [default: euclidean]
+    #[arg(long)]
+    heuristic: Option<HeuristicArg>,
+    /// Cells reachable in one move
+    #[arg(long, default_value = "4")]
+    connectivity: ConnectivityArg,
+}
+
+#[derive(Debug, Args)]
+struct GenerateArgs {
+    /// Side of the maze
+    #[arg(long, default_value_t = 16, value_parser = parse_size)]
+    size: usize,
+    /// Percentage of cells blocked before carving
+    #[arg(long, default_value_t = 30, value_parser = clap::value_parser!(u8).range(0..=100))]
+    density: u8,
+    #[arg(long, default_value = "backtracking")]
+    generator: GeneratorArg,
+    /// Seed of the layout [default: random]
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Start cell, as `x,y` [default: 0,0]
+    #[arg(long, value_parser = parse_coordinates)]
+    start: Option<Coordinates>,
+    /// Goal cell, as `x,y` [default: the opposite corner]
+    #[arg(long, value_parser = parse_coordinates)]
+    goal: Option<Coordinates>,
+    /// Maze file to write [default: stdout]
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+struct SolveArgs {
+    /// Maze file to solve
+    maze: PathBuf,
+    #[arg(long, default_value = "astar")]
+    finder: FinderArg,
+    #[command(flatten)]
+    search: FinderArgs,
+    #[arg(long, default_value = "none")]
+    render: RenderMode,
+    /// Text style of `--render text`: table, box, half-block or braille
+    #[arg(long)]
+    style: Option<TextStyle>,
+    /// Delay between two rendered steps, in milliseconds
+    #[arg(long)]
+    delay: Option<u64>,
+    /// Gives up after this many steps
+    #[arg(long)]
+    max_steps: Option<usize>,
+    /// File to write the path to, one `x,y` cell per line [default: stdout]
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+struct BenchArgs {
+    /// Sides of the mazes
+    #[arg(long, value_delimiter = ',', default_value = "16,32", value_parser = parse_size)]
+    sizes: Vec<usize>,
+    #[arg(
+        long,
+        value_delimiter = ',',
+        default_value = "astar,greedy,bfs,dfs,random"
+    )]
+    finders: Vec<FinderArg>,
+    #[command(flatten)]
+    search: FinderArgs,
+    /// Mazes generated for each size
+    #[arg(long, default_value_t = 5)]
+    mazes: u64,
+    /// Percentage of cells blocked before carving
+    #[arg(long, default_value_t = 30, value_parser = clap::value_parser!(u8).range(0..=100))]
+    density: u8,
+    #[arg(long, default_value = "backtracking")]
+    generator: GeneratorArg,
+    /// Seed of the first maze, the next ones counting up from it
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    /// Gives up on a maze after this many steps
+    #[arg(long, default_value_t = 10_000)]
+    max_steps: usize,
+    #[arg(long, default_value = "table")]
+    format: ReportFormat,
+}
+
+#[derive(Debug, Args)]
+struct RenderArgs {
+    /// Maze file to draw
+    maze: PathBuf,
+    /// Image to write, `.png` or `.svg`
+    #[arg(short, long)]
+    output: PathBuf,
+    /// Side of a cell, in pixels
+    #[arg(long, default_value_t = 16, value_parser = clap::value_parser!(u32).range(1..))]
+    cell_size: u32,
+    /// Blank border around the maze, in pixels
+    #[arg(long, default_value_t = 16)]
+    margin: u32,
+    #[arg(long, default_value = "blocks")]
+    walls: WallsArg,
+    /// Thickness of `--walls thin`, in pixels
+    #[arg(long, default_value_t = 2)]
+    wall_thickness: u32,
+    /// Draws the path found by this finder
+    #[arg(long)]
+    finder: Option<FinderArg>,
+    #[command(flatten)]
+    search: FinderArgs,
+    /// Colors the cells expanded by the finder by their order
+    #[arg(long, requires = "finder")]
+    heat_map: bool,
+}
+
+#[derive(Debug, Args)]
+struct RouteArgs {
+    /// Graph file to search
+    graph: PathBuf,
+    /// Start node
+    #[arg(long)]
+    from: String,
+    /// Goal node
+    #[arg(long)]
+    to: String,
+    #[arg(long, default_value = "astar")]
+    finder: FinderArg,
+    /// Prints the route after every move
+    #[arg(long)]
+    steps: bool,
+}
+
+#[derive(Debug, Args)]
+struct TuiArgs {
+    /// Side of the first maze
+    #[arg(long, value_parser = parse_size)]
+    size: Option<usize>,
+    /// Percentage of cells blocked before carving
+    #[arg(long, value_parser = clap::value_parser!(u8).range(0..=100))]
+    density: Option<u8>,
+    #[arg(long)]
+    seed: Option<u64>,
+}
+
+fn parse_size(value: &str) -> Result<usize, String> {
+    let size = value.parse().map_err(|error| format!("{error}"))?;
+
+    if SIZES.contains(&size) {
+        Ok(size)
+    } else {
+        Err(format!("unsupported size, expected one of {SIZES:?}"))
+    }
+}
+
+fn parse_coordinates(value: &str) -> Result<Coordinates, String> {
+    let (x, y) = value
+        .split_once(',')
+        .ok_or_else(|| "expected `x,y`".to_string())?;
+    let parse = |axis: &str| {
+        axis.trim()
+            .parse::<i64>()
+            .map_err(|error| format!("{error}"))
+    };
+
+    Ok(Coordinates::new(parse(x)?, parse(y)?))
+}
+
+/// Exits with a usage error of the subcommand, for flags that don't go
+/// together.
+fn conflict(subcommand: &str, message: impl std::fmt::Display) -> ! {
+    let mut command = Cli::command();
+    command.build();
+
+    command
+        .find_subcommand_mut(subcommand)
+        .expect("unknown subcommand")
+        .error(ErrorKind::ArgumentConflict, message)
+        .exit()
+}
+
+/// Reads a maze file whose side is one of [`SIZES`].
+fn read_maze(path: &PathBuf) -> Result<MazeFile, Box<dyn Error>> {
+    let file: MazeFile = fs::read_to_string(path)
+        .map_err(|error| format!("can't read {}: {error}", path.display()))?
+        .parse()
+        .map_err(|error| format!("invalid maze file {}: {error}", path.display()))?;
+
+    if !SIZES.contains(&file.size()) {
+        return Err(format!(
+            "{} has side {}, expected one of {SIZES:?}",
+            path.display(),
+            file.size()
+        )
+        .into());
+    }
+
+    Ok(file)
+}
+
+/// Writes to the file, or to stdout without one.
+fn output(path: &Option<PathBuf>) -> io::Result<Box<dyn Write>> {
+    Ok(match path {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(stdout()),
+    })
+}
+
+/// A search to run with the finder chosen at runtime.
+trait Search<const N: usize> {
+    type Output;
+
+    fn search<F: FrontierManager + Debug, D: Distance>(self, maze: Maze<N>) -> Self::Output;
+}
+
+/// Runs `search` with the [`PathFinder`] of `finder` and `heuristic`.
+fn with_finder<const N: usize, S: Search<N>>(
+    finder: FinderArg,
+    heuristic: HeuristicArg,
+    maze: Maze<N>,
+    search: S,
+) -> S::Output {
+    match (finder, heuristic) {
+        (FinderArg::Astar, HeuristicArg::Euclidean) => {
+            search.search::<AStarFinder, Euclidean>(maze)
+        }
+        (FinderArg::Astar, HeuristicArg::Manhattan) => {
+            search.search::<AStarFinder, Manhattan>(maze)
+        }
+        (FinderArg::Astar, HeuristicArg::Chebyshev) => {
+            search.search::<AStarFinder, Chebyshev>(maze)
+        }
+        (FinderArg::Greedy, HeuristicArg::Euclidean) => {
+            search.search::<GreedyFinder, Euclidean>(maze)
+        }
+        (FinderArg::Greedy, HeuristicArg::Manhattan) => {
+            search.search::<GreedyFinder, Manhattan>(maze)
+        }
+        (FinderArg::Greedy, HeuristicArg::Chebyshev) => {
+            search.search::<GreedyFinder, Chebyshev>(maze)
+        }
+        (FinderArg::Bfs, _) => search.search::<BFSFinder, Euclidean>(maze),
+        (FinderArg::Dfs, _) => search.search::<DFSFinder, Euclidean>(maze),
+        (FinderArg::Random, _) => search.search::<RandomFinder, Euclidean>(maze),
+        (FinderArg::Ucs, _) => search.search::<UniformCostFinder, Euclidean>(maze),
+    }
+}
+
+/// Simulator of a finder after its episode, and the episode.
+type Run<const N: usize, F, D, R> = (Simulator<PathFinder<N, F, D>, R>, EpisodeStats);
+
+/// Runs a single episode of the finder on the maze.
+fn run<const N: usize, F: FrontierManager + Debug, D: Distance, R: Renderer<Maze<N>>>(
+    maze: Maze<N>,
+    renderer: R,
+    max_steps: Option<usize>,
+) -> Result<Run<N, F, D, R>, Box<dyn Error>> {
+    let finder = PathFinder::<N, F, D>::new(&maze);
+    let mut simulator = Simulator::new(finder, renderer, maze);
+    if let Some(max_steps) = max_steps {
+        simulator = simulator.with_max_steps(max_steps);
+    }
+
+    let stats = simulator
+        .run_episodes(1)
+        .map_err(|()| "the finder failed to act")?;
+
+    Ok((simulator, stats[0]))
+}
+
+fn generate_maze<const N: usize>(
+    generator: GeneratorArg,
+    density: u8,
+    seed: u64,
+    start: Coordinates,
+    goal: Coordinates,
+) -> Maze<N> {
+    let mut generator_seed = [0; 32];
+    generator_seed[..8].copy_from_slice(&seed.to_le_bytes());
+
+    match generator {
+        GeneratorArg::Backtracking => Maze::with_generator(
+            start,
+            goal,
+            density,
+            seed,
+            RbGenerator::new(Some(generator_seed)),
+        ),
+        GeneratorArg::Walk => Maze::with_generator(
+            start,
+            goal,
+            density,
+            seed,
+            WalkGenerator::new(Some(generator_seed)),
+        ),
+    }
+}
+
+fn generate(args: GenerateArgs) -> Result<(), Box<dyn Error>> {
+    let last = args.size as i64 - 1;
+    let start = args.start.unwrap_or(Coordinates::new(0, 0));
+    let goal = args.goal.unwrap_or(Coordinates::new(last, last));
+
+    for (name, coords) in [("start", start), ("goal", goal)] {
+        if !(0..=last).contains(&coords.x) || !(0..=last).contains(&coords.y) {
+            conflict(
+                "generate",
+                format!(
+                    "the {name} {},{} is outside of a maze of size {}",
+                    coords.x, coords.y, args.size
+                ),
+            );
+        }
+    }
+    if start == goal {
+        conflict("generate", "the start and the goal must be different cells");
+    }
+
+    let seed = args.seed.unwrap_or_else(rand::random);
+    let file = with_size!(args.size, N => {
+        MazeFile::from_maze(&generate_maze::<N>(args.generator, args.density, seed, start, goal))
+    });
+
+    let mut writer = output(&args.output)?;
+    write!(writer, "{file}")?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+impl<const N: usize> Search<N> for &SolveArgs {
+    type Output = Result<(), Box<dyn Error>>;
+
+    fn search<F: FrontierManager + Debug, D: Distance>(self, maze: Maze<N>) -> Self::Output {
+        let delay = self.delay.map(Duration::from_millis);
+        let mut renderer: Vec<Box<dyn Renderer<Maze<N>>>> = Vec::new();
+
+        match self.render {
+            RenderMode::None => {}
+            RenderMode::Text => {
+                let text = TextRenderer::new(stdout(), self.style.unwrap_or_default());
+                renderer.push(Box::new(match delay {
+                    Some(delay) => text.with_delay(delay),
+                    None => text,
+                }));
+            }
+            RenderMode::Search => {
+                let search = SearchRenderer::new(stdout());
+                renderer.push(Box::new(match delay {
+                    Some(delay) => search.with_delay(delay),
+                    None => search,
+                }));
+            }
+            RenderMode::Interactive => {
+                let interactive = InteractiveRenderer::new(stdout());
+                renderer.push(Box::new(match delay {
+                    Some(delay) => interactive.with_delay(delay),
+                    None => interactive,
+                }));
+            }
+        }
+
+        let (simulator, stats) = run::<N, F, D, _>(maze, renderer, self.max_steps)?;
+        let maze = simulator.environment();
+
+        if simulator.aborted() {
+            return Err("the search was aborted".into());
+        }
+        if !stats.terminated {
+            return Err(format!(
+                "no path found after {} steps{}",
+                stats.steps,
+                if stats.truncated {
+                    ", the step limit was hit"
+                } else {
+                    ""
+                }
+            )
+            .into());
+        }
+
+        let path = maze.current_path.cells();
+        let mut writer = output(&self.output)?;
+        for coords in &path {
+            writeln!(writer, "{},{}", coords.x, coords.y)?;
+        }
+        writer.flush()?;
+
+        eprintln!(
+            "Found a path of {} cells in {} steps, expanding {} cells in {:?}",
+            path.len(),
+            stats.steps,
+            maze.trace().visited.len(),
+            stats.elapsed
+        );
+
+        Ok(())
+    }
+}
+
+/// Rejects `--heuristic` when no finder uses it.
+fn check_heuristic(subcommand: &str, search: &FinderArgs, finders: &[FinderArg]) {
+    if search.heuristic.is_some() && !finders.iter().any(FinderArg::is_informed) {
+        conflict(
+            subcommand,
+            "--heuristic only applies to the astar and greedy finders",
+        );
+    }
+}
+
+fn solve(args: SolveArgs) -> Result<(), Box<dyn Error>> {
+    check_heuristic("solve", &args.search, &[args.finder]);
+    if args.style.is_some() && args.render != RenderMode::Text {
+        conflict("solve", "--style only applies to --render text");
+    }
+    if args.delay.is_some() && args.render == RenderMode::None {
+        conflict("solve", "--delay needs a --render mode other than none");
+    }
+
+    let file = read_maze(&args.maze)?;
+
+    with_size!(file.size(), N => {
+        let maze = file
+            .to_maze::<N>()
+            .with_connectivity(args.search.connectivity.into())
+            .with_frontier_trace(args.render != RenderMode::None);
+        with_finder(args.finder, args.search.heuristic.unwrap_or_default(), maze, &args)
+    })
+}
+
+/// Statistics of a finder on a single maze.
+struct Sample {
+    solved: bool,
+    steps: usize,
+    expanded: usize,
+    path_length: usize,
+    elapsed: Duration,
+}
+
+struct Benchmark {
+    max_steps: usize,
+}
+
+impl<const N: usize> Search<N> for &Benchmark {
+    type Output = Result<Sample, Box<dyn Error>>;
+
+    fn search<F: FrontierManager + Debug, D: Distance>(self, maze: Maze<N>) -> Self::Output {
+        let renderer = Headless;
+        let (simulator, stats) = run::<N, F, D, _>(maze, renderer, Some(self.max_steps))?;
+        let maze = simulator.environment();
+
+        Ok(Sample {
+            solved: stats.terminated,
+            steps: stats.steps,
+            expanded: maze.trace().visited.len(),
+            path_length: maze.current_path.len(),
+            elapsed: stats.elapsed,
+        })
+    }
+}
+
+/// Averages of a finder on the mazes of a size.
+struct Row {
+    size: usize,
+    finder: String,
+    mazes: usize,
+    solved: usize,
+    steps: f64,
+    expanded: f64,
+    /// Over the solved mazes only.
+    path_length: f64,
+    millis: f64,
+}
+
+impl Row {
+    fn new(size: usize, finder: String, samples: &[Sample]) -> Self {
+        let mean = |value: &dyn Fn(&Sample) -> f64, samples: &[&Sample]| {
+            samples.iter().map(|sample| value(sample)).sum::<f64>() / samples.len().max(1) as f64
+        };
+        let all: Vec<_> = samples.iter().collect();
+        let solved: Vec<_> = samples.iter().filter(|sample| sample.solved).collect();
+
+        Self {
+            size,
+            finder,
+            mazes: samples.len(),
+            solved: solved.len(),
+            steps: mean(&|sample| sample.steps as f64, &all),
+            expanded: mean(&|sample| sample.expanded as f64, &all),
+            path_length: mean(&|sample| sample.path_length as f64, &solved),
+            millis: mean(&|sample| sample.elapsed.as_secs_f64() * 1000.0, &all),
+        }
+    }
+}
+
+fn report(rows: &[Row], format: ReportFormat, writer: &mut impl Write) -> io::Result<()> {
+    match format {
+        ReportFormat::Table => {
+            writeln!(
+                writer,
+                "{:>5}  {:<20} {:>7} {:>10} {:>10} {:>8} {:>10}",
+                "size", "finder", "solved", "steps", "expanded", "path", "ms"
+            )?;
+            for row in rows {
+                writeln!(
+                    writer,
+                    "{:>5}  {:<20} {:>7} {:>10.1} {:>10.1} {:>8.1} {:>10.3}",
+                    row.size,
+                    row.finder,
+                    format!("{}/{}", row.solved, row.mazes),
+                    row.steps,
+                    row.expanded,
+                    row.path_length,
+                    row.millis
+                )?;
+            }
+        }
+        ReportFormat::Csv => {
+            writeln!(
+                writer,
+                "size,finder,mazes,solved,steps,expanded,path_length,ms"
+            )?;
+            for row in rows {
+                writeln!(
+                    writer,
+                    "{},{},{},{},{:.1},{:.1},{:.1},{:.3}",
+                    row.size,
+                    row.finder,
+                    row.mazes,
+                    row.solved,
+                    row.steps,
+                    row.expanded,
+                    row.path_length,
+                    row.millis
+                )?;
+            }
+        }
+        ReportFormat::Json => {
+            let rows: Vec<_> = rows
+                .iter()
+                .map(|row| {
+                    format!(
+                        "  {{\"size\":{},\"finder\":\"{}\",\"mazes\":{},\"solved\":{},\"steps\":{:.1},\"expanded\":{:.1},\"path_length\":{:.1},\"ms\":{:.3}}}",
+                        row.size,
+                        row.finder,
+                        row.mazes,
+                        row.solved,
+                        row.steps,
+                        row.expanded,
+                        row.path_length,
+                        row.millis
+                    )
+                })
+                .collect();
+            writeln!(writer, "[\n{}\n]", rows.join(",\n"))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn bench(args: BenchArgs) -> Result<(), Box<dyn Error>> {
+    check_heuristic("bench", &args.search, &args.finders);
+    if args.mazes == 0 {
+        conflict("bench", "--mazes must be at least 1");
+    }
+
+    let heuristic = args.search.heuristic.unwrap_or_default();
+    let benchmark = Benchmark {
+        max_steps: args.max_steps,
+    };
+    let mut rows = Vec::new();
+
+    for size in &args.sizes {
+        for finder in &args.finders {
+            let mut samples = Vec::new();
+
+            for seed in (0..args.mazes).map(|maze| args.seed.wrapping_add(maze)) {
+                let sample = with_size!(*size, N => {
+                    let goal = Coordinates::new(N as i64 - 1, N as i64 - 1);
+                    let maze = generate_maze::<N>(
+                        args.generator,
+                        args.density,
+                        seed,
+                        Coordinates::new(0, 0),
+                        goal,
+                    )
+                    .with_connectivity(args.search.connectivity.into());
+
+                    with_finder(*finder, heuristic, maze, &benchmark)
+                })?;
+                samples.push(sample);
+            }
+
+            let name = match finder.is_informed() {
+                true => format!("{finder:?} ({heuristic:?})").to_lowercase(),
+                false => format!("{finder:?}").to_lowercase(),
+            };
+            rows.push(Row::new(*size, name, &samples));
+        }
+    }
+
+    report(&rows, args.format, &mut stdout())?;
+    Ok(())
+}
+
+struct Drawing<'a> {
+    args: &'a RenderArgs,
+    exporter: ImageExporter,
+    svg: bool,
+}
+
+impl<const N: usize> Search<N> for Drawing<'_> {
+    type Output = Result<(), Box<dyn Error>>;
+
+    fn search<F: FrontierManager + Debug, D: Distance>(self, maze: Maze<N>) -> Self::Output {
+        let export = |maze: &Maze<N>| -> Result<(), Box<dyn Error>> {
+            let writer = BufWriter::new(File::create(&self.args.output)?);
+            if self.svg {
+                self.exporter.write_svg(maze, writer)?;
+            } else {
+                self.exporter.write_png(maze, writer)?;
+            }
+            Ok(())
+        };
+
+        match self.args.finder {
+            Some(_) => {
+                let (simulator, _) = run::<N, F, D, _>(maze, Headless, None)?;
+                export(simulator.environment())
+            }
+            None => export(&maze),
+        }
+    }
+}
+
+fn render(args: RenderArgs) -> Result<(), Box<dyn Error>> {
+    if let Some(finder) = args.finder {
+        check_heuristic("render", &args.search, &[finder]);
+    } else if args.search.heuristic.is_some() {
+        conflict("render", "--heuristic needs a --finder");
+    }
+
+    let svg = match args
+        .output
+        .extension()
+        .and_then(|extension| extension.to_str())
+    {
+        Some("png") => false,
+        Some("svg") => true,
+        _ => conflict("render", "the output image must end with .png or .svg"),
+    };
+
+    let walls = match args.walls {
+        WallsArg::Blocks => WallStyle::Blocks,
+        WallsArg::Thin => WallStyle::Thin,
+    };
+    let exporter = ImageExporter::default()
+        .with_cell_size(args.cell_size)
+        .with_margin(args.margin)
+        .with_walls(walls, args.wall_thickness)
+        .with_solution(args.finder.is_some())
+        .with_heat_map(args.heat_map);
+
+    let file = read_maze(&args.maze)?;
+    let drawing = Drawing {
+        args: &args,
+        exporter,
+        svg,
+    };
+
+    with_size!(file.size(), N => {
+        let maze = file.to_maze::<N>().with_connectivity(args.search.connectivity.into());
+        let finder = args.finder.unwrap_or(FinderArg::Astar);
+        with_finder(finder, args.search.heuristic.unwrap_or_default(), maze, drawing)
+    })
+}
+
+/// Plays the route planned with `F`, writing its nodes one per line.
+fn find_route<F: FrontierManager>(route: RouteFinding, steps: bool) -> Result<(), Box<dyn Error>> {
+    let mut renderer: Vec<Box<dyn Renderer<RouteFinding>>> = Vec::new();
+    if steps {
+        renderer.push(Box::new(FrameWriter::new(stdout())));
+    }
+
+    let planner = Planner::<_, F>::new(&route);
+    let mut simulator = Simulator::new(planner, renderer, route);
+    let stats = simulator.run_episodes(1)?;
+    let route = simulator.environment();
+
+    let Some(solution) = simulator.agent().solution() else {
+        return Err(format!(
+            "no route from {} to {}",
+            route.graph().name(route.start()),
+            route.graph().name(route.goal())
+        )
+        .into());
+    };
+
+    let mut writer = stdout();
+    for node in route.route() {
+        writeln!(writer, "{}", route.graph().name(*node))?;
+    }
+    writer.flush()?;
+
+    eprintln!(
+        "Found a route of cost {} through {} nodes, expanding {} nodes in {:?}",
+        route.cost(),
+        route.route().len(),
+        solution.expanded,
+        stats[0].elapsed
     );
 
-    let _ = simulator.simulate();
+    Ok(())
+}
+
+fn route(args: RouteArgs) -> Result<(), Box<dyn Error>> {
+    let graph: Graph = fs::read_to_string(&args.graph)
+        .map_err(|error| format!("can't read {}: {error}", args.graph.display()))?
+        .parse()
+        .map_err(|error| format!("invalid graph file {}: {error}", args.graph.display()))?;
+
+    let node = |name: &str| {
+        graph
+            .node(name)
+            .ok_or_else(|| format!("{} has no node `{name}`", args.graph.display()))
+    };
+    let (start, goal) = (node(&args.from)?, node(&args.to)?);
+    let route = graph.route(start, goal);
 
+    match args.finder {
+        FinderArg::Astar => find_route::<AStarFinder>(route, args.steps),
+        FinderArg::Greedy => find_route::<GreedyFinder>(route, args.steps),
+        FinderArg::Bfs => find_route::<BFSFinder>(route, args.steps),
+        FinderArg::Dfs => find_route::<DFSFinder>(route, args.steps),
+        FinderArg::Random => find_route::<RandomFinder>(route, args.steps),
+        FinderArg::Ucs => find_route::<UniformCostFinder>(route, args.steps),
+    }
+}
+
+fn tui(args: TuiArgs) -> Result<(), Box<dyn Error>> {
+    let mut app = App::new(stdout());
+    if let Some(size) = args.size {
+        app = app.with_size(size);
+    }
+    if let Some(density) = args.density {
+        app = app.with_density(density);
+    }
+    if let Some(seed) = args.seed {
+        app = app.with_seed(seed);
+    }
 
+    app.run()?;
     Ok(())
 }
+
+fn main() -> Result<(), Box<dyn Error>> {
+    match Cli::parse().command {
+        Some(Command::Generate(args)) => generate(args),
+        Some(Command::Solve(args)) => solve(args),
+        Some(Command::Bench(args)) => bench(args),
+        Some(Command::Render(args)) => render(args),
+        Some(Command::Route(args)) => route(args),
+        Some(Command::Tui(args)) => tui(args),
+        None => tui(TuiArgs {
+            size: None,
+            density: None,
+            seed: None,
+        }),
+    }
+}
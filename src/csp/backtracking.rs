@@ -0,0 +1,233 @@
+//! Backtracking search
+
+use std::cmp::Reverse;
+
+use super::{
+    consistency::{arcs_to, forward_check, propagate},
+    Assignment, Csp, Variable,
+};
+
+/// Inference after each assignment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Inference {
+    None,
+    /// Prunes the domains of the variables sharing a constraint with the
+    /// assigned one.
+    #[default]
+    ForwardChecking,
+    /// Maintains arc consistency: forward checking, then AC-3 from the
+    /// neighbors of the assigned variable.
+    Mac,
+}
+
+/// Which variable to assign next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VariableOrder {
+    /// The first unassigned one.
+    Static,
+    /// Minimum remaining values: the one with the fewest values left.
+    Mrv,
+    /// MRV, ties going to the one constraining the most unassigned
+    /// variables.
+    #[default]
+    MrvDegree,
+}
+
+/// In which order to try the values of a variable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValueOrder {
+    /// The order of the domain.
+    #[default]
+    Static,
+    /// Least constraining value: the ones ruling out the fewest values of
+    /// the neighbors first.
+    Lcv,
+}
+
+/// Counters of a [`Backtracking`] search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CspStats {
+    /// Values given to variables.
+    pub assignments: usize,
+    /// Variables left without a value to try.
+    pub backtracks: usize,
+}
+
+/// Depth-first search assigning one variable at a time.
+#[derive(Debug, Clone, Default)]
+pub struct Backtracking {
+    inference: Inference,
+    variable_order: VariableOrder,
+    value_order: ValueOrder,
+    /// Whether to make the problem arc consistent before searching.
+    preprocess: bool,
+    stats: CspStats,
+}
+
+impl Backtracking {
+    /// Forward checking with the MRV and degree heuristics.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_inference(mut self, inference: Inference) -> Self {
+        self.inference = inference;
+        self
+    }
+
+    pub fn with_variable_order(mut self, variable_order: VariableOrder) -> Self {
+        self.variable_order = variable_order;
+        self
+    }
+
+    pub fn with_value_order(mut self, value_order: ValueOrder) -> Self {
+        self.value_order = value_order;
+        self
+    }
+
+    /// Runs [`ac3`](super::ac3) on the whole problem before searching.
+    pub fn with_arc_consistency(mut self, preprocess: bool) -> Self {
+        self.preprocess = preprocess;
+        self
+    }
+
+    /// Counters of the last search.
+    pub fn stats(&self) -> CspStats {
+        self.stats
+    }
+
+    /// A solution, with a value for each variable, `None` when there is
+    /// none.
+    pub fn solve<T: Clone>(&mut self, csp: &Csp<T>) -> Option<Vec<T>> {
+        self.stats = CspStats::default();
+
+        let mut domains = csp.domains().to_vec();
+        if self.preprocess && !super::ac3(csp, &mut domains) {
+            return None;
+        }
+
+        let mut assignment = vec![None; csp.len()];
+        if !self.backtrack(csp, &mut assignment, &domains) {
+            return None;
+        }
+
+        assignment.into_iter().collect()
+    }
+
+    fn backtrack<T: Clone>(
+        &mut self,
+        csp: &Csp<T>,
+        assignment: &mut Assignment<T>,
+        domains: &[Vec<T>],
+    ) -> bool {
+        let Some(variable) = self.select(csp, assignment, domains) else {
+            return true;
+        };
+
+        for value in self.order(csp, assignment, domains, variable) {
+            if !csp.is_consistent(assignment, variable, &value) {
+                continue;
+            }
+
+            self.stats.assignments += 1;
+            assignment[variable] = Some(value.clone());
+
+            let mut pruned = domains.to_vec();
+            pruned[variable] = vec![value];
+            let consistent = match self.inference {
+                Inference::None => true,
+                Inference::ForwardChecking => forward_check(csp, &mut pruned, assignment, variable),
+                Inference::Mac => {
+                    forward_check(csp, &mut pruned, assignment, variable)
+                        && propagate(csp, &mut pruned, arcs_to(csp, variable))
+                }
+            };
+
+            if consistent && self.backtrack(csp, assignment, &pruned) {
+                return true;
+            }
+            assignment[variable] = None;
+        }
+
+        self.stats.backtracks += 1;
+        false
+    }
+
+    fn select<T>(
+        &self,
+        csp: &Csp<T>,
+        assignment: &[Option<T>],
+        domains: &[Vec<T>],
+    ) -> Option<Variable> {
+        let mut unassigned = (0..csp.len()).filter(|variable| assignment[*variable].is_none());
+
+        // Constraints with another unassigned variable
+        let degree = |variable: Variable| {
+            csp.constraints_on(variable)
+                .filter(|constraint| {
+                    constraint
+                        .scope()
+                        .iter()
+                        .any(|other| *other != variable && assignment[*other].is_none())
+                })
+                .count()
+        };
+
+        match self.variable_order {
+            VariableOrder::Static => unassigned.next(),
+            VariableOrder::Mrv => unassigned.min_by_key(|variable| domains[*variable].len()),
+            VariableOrder::MrvDegree => unassigned
+                .min_by_key(|variable| (domains[*variable].len(), Reverse(degree(*variable)))),
+        }
+    }
+
+    fn order<T: Clone>(
+        &self,
+        csp: &Csp<T>,
+        assignment: &mut Assignment<T>,
+        domains: &[Vec<T>],
+        variable: Variable,
+    ) -> Vec<T> {
+        let mut values = domains[variable].clone();
+        if self.value_order == ValueOrder::Static {
+            return values;
+        }
+
+        // Values of the neighbors left alone unassigned in a constraint
+        // that the value rules out
+        let mut ruled_out = |value: &T| {
+            assignment[variable] = Some(value.clone());
+
+            let count = csp
+                .constraints_on(variable)
+                .map(|constraint| {
+                    let mut unassigned = constraint
+                        .scope()
+                        .iter()
+                        .filter(|other| assignment[**other].is_none());
+                    let (Some(&other), None) = (unassigned.next(), unassigned.next()) else {
+                        return 0;
+                    };
+
+                    domains[other]
+                        .iter()
+                        .filter(|support| {
+                            constraint.check_with(assignment, other, support) == Some(false)
+                        })
+                        .count()
+                })
+                .sum::<usize>();
+
+            assignment[variable] = None;
+            count
+        };
+
+        let mut keyed: Vec<_> = values
+            .drain(..)
+            .map(|value| (ruled_out(&value), value))
+            .collect();
+        keyed.sort_by_key(|(count, _)| *count);
+
+        keyed.into_iter().map(|(_, value)| value).collect()
+    }
+}
@@ -0,0 +1,143 @@
+//! Consistency
+//!
+//! Inference prunes the values that can't be part of a solution from the
+//! domains, before or while searching:
+//!
+//! - a variable is node consistent once every value of its domain satisfies
+//!   its unary constraints;
+//! - an arc, a variable and one of its binary constraints, is consistent
+//!   once every value of the variable has a supporting value in the domain
+//!   of the other variable;
+//! - forward checking only prunes the domains of the variables left alone
+//!   unassigned in a constraint.
+
+use std::collections::VecDeque;
+
+use super::{Csp, Variable};
+
+/// Makes every variable node consistent and every arc consistent, with the
+/// AC-3 algorithm. Constraints on three variables or more are left out.
+///
+/// Returns `false` when a domain is wiped out, the problem having no
+/// solution.
+pub fn ac3<T: Clone>(csp: &Csp<T>, domains: &mut [Vec<T>]) -> bool {
+    let mut arcs = VecDeque::new();
+
+    for (index, constraint) in csp.constraints().iter().enumerate() {
+        match constraint.scope() {
+            [variable] => {
+                domains[*variable].retain(|value| constraint.is_satisfied(&[value]));
+                if domains[*variable].is_empty() {
+                    return false;
+                }
+            }
+            [first, second] => {
+                arcs.push_back((*first, index));
+                arcs.push_back((*second, index));
+            }
+            _ => {}
+        }
+    }
+
+    propagate(csp, domains, arcs)
+}
+
+/// Runs AC-3 from the arcs in the queue, each a variable and one of its
+/// binary constraints.
+pub(crate) fn propagate<T: Clone>(
+    csp: &Csp<T>,
+    domains: &mut [Vec<T>],
+    mut arcs: VecDeque<(Variable, usize)>,
+) -> bool {
+    while let Some((variable, constraint)) = arcs.pop_front() {
+        if !revise(csp, domains, variable, constraint) {
+            continue;
+        }
+        if domains[variable].is_empty() {
+            return false;
+        }
+
+        // The neighbors may have lost the support of the removed values
+        for other in csp.involved(variable) {
+            if let [first, second] = csp.constraints()[*other].scope() {
+                if *other != constraint {
+                    let neighbor = if *first == variable { *second } else { *first };
+                    arcs.push_back((neighbor, *other));
+                }
+            }
+        }
+    }
+
+    true
+}
+
+/// Removes the values of `variable` without support in the domain of the
+/// other variable of the binary constraint. Whether any was removed.
+fn revise<T: Clone>(
+    csp: &Csp<T>,
+    domains: &mut [Vec<T>],
+    variable: Variable,
+    constraint: usize,
+) -> bool {
+    let constraint = &csp.constraints()[constraint];
+    let &[first, second] = constraint.scope() else {
+        return false;
+    };
+    let (other, variable_first) = match first == variable {
+        true => (second, true),
+        false => (first, false),
+    };
+
+    let supports = domains[other].clone();
+    let before = domains[variable].len();
+    domains[variable].retain(|value| {
+        supports.iter().any(|support| match variable_first {
+            true => constraint.is_satisfied(&[value, support]),
+            false => constraint.is_satisfied(&[support, value]),
+        })
+    });
+
+    domains[variable].len() != before
+}
+
+/// Prunes the domain of every variable left alone unassigned in a
+/// constraint on `variable`, which was just assigned.
+///
+/// Returns `false` when a domain is wiped out.
+pub fn forward_check<T: Clone>(
+    csp: &Csp<T>,
+    domains: &mut [Vec<T>],
+    assignment: &[Option<T>],
+    variable: Variable,
+) -> bool {
+    for constraint in csp.constraints_on(variable) {
+        let mut unassigned = constraint
+            .scope()
+            .iter()
+            .filter(|other| assignment[**other].is_none());
+        let (Some(&other), None) = (unassigned.next(), unassigned.next()) else {
+            continue;
+        };
+
+        domains[other]
+            .retain(|value| constraint.check_with(assignment, other, value) != Some(false));
+        if domains[other].is_empty() {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Arcs of the neighbors of `variable` towards it, to propagate its new
+/// domain with AC-3.
+pub(crate) fn arcs_to<T>(csp: &Csp<T>, variable: Variable) -> VecDeque<(Variable, usize)> {
+    csp.involved(variable)
+        .iter()
+        .filter_map(|constraint| match csp.constraints()[*constraint].scope() {
+            [first, second] if *first == variable => Some((*second, *constraint)),
+            [first, second] if *second == variable => Some((*first, *constraint)),
+            _ => None,
+        })
+        .collect()
+}
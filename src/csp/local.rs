@@ -0,0 +1,119 @@
+//! Local search
+
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaChaRng;
+
+use super::{Csp, Variable};
+
+/// Min-conflicts local search.
+///
+/// Starts from a greedy complete assignment, then repeatedly picks a
+/// variable violating a constraint at random and gives it the value
+/// violating the fewest constraints, ties broken at random.
+#[derive(Debug, Clone)]
+pub struct MinConflicts {
+    max_steps: usize,
+    seed: Option<u64>,
+    steps: usize,
+}
+
+impl Default for MinConflicts {
+    fn default() -> Self {
+        Self {
+            max_steps: 10_000,
+            seed: None,
+            steps: 0,
+        }
+    }
+}
+
+impl MinConflicts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Repairs made before giving up, `10_000` by default.
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// Seed of the random choices, random by default.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Repairs made by the last search.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// A solution, `None` when none was found within the steps, or when a
+    /// domain is empty.
+    pub fn solve<T: Clone>(&mut self, csp: &Csp<T>) -> Option<Vec<T>> {
+        self.steps = 0;
+        let mut rng = match self.seed {
+            None => ChaChaRng::from_entropy(),
+            Some(seed) => ChaChaRng::seed_from_u64(seed),
+        };
+
+        // Each variable takes the value conflicting the least with the
+        // ones before it
+        let mut assignment = vec![None; csp.len()];
+        for variable in 0..csp.len() {
+            let value = best(csp, &assignment, variable, &mut rng)?;
+            assignment[variable] = Some(value);
+        }
+
+        for step in 0..=self.max_steps {
+            let values: Vec<T> = assignment.iter().flatten().cloned().collect();
+            let conflicted: Vec<Variable> = (0..csp.len())
+                .filter(|variable| csp.conflicts(&values, *variable) > 0)
+                .collect();
+
+            let Some(variable) = conflicted.choose(&mut rng) else {
+                return Some(values);
+            };
+            if step == self.max_steps {
+                break;
+            }
+
+            self.steps += 1;
+            assignment[*variable] = None;
+            assignment[*variable] = best(csp, &assignment, *variable, &mut rng);
+        }
+
+        None
+    }
+}
+
+/// Value of `variable` violating the fewest constraints whose other
+/// variables are assigned, ties broken at random.
+fn best<T: Clone>(
+    csp: &Csp<T>,
+    assignment: &[Option<T>],
+    variable: Variable,
+    rng: &mut ChaChaRng,
+) -> Option<T> {
+    let conflicts: Vec<usize> = csp
+        .domain(variable)
+        .iter()
+        .map(|value| {
+            csp.constraints_on(variable)
+                .filter(|constraint| {
+                    constraint.check_with(assignment, variable, value) == Some(false)
+                })
+                .count()
+        })
+        .collect();
+
+    let fewest = *conflicts.iter().min()?;
+    let candidates: Vec<_> = (0..conflicts.len())
+        .filter(|index| conflicts[*index] == fewest)
+        .collect();
+
+    candidates
+        .choose(rng)
+        .map(|index| csp.domain(variable)[*index].clone())
+}
@@ -0,0 +1,415 @@
+//! Constraint satisfaction problems
+//!
+//! A [`Csp`] has variables, each with a finite domain of values, and
+//! constraints on the values of some of them. A solution assigns each
+//! variable a value of its domain satisfying every constraint.
+//!
+//! Two kinds of solvers are provided:
+//!
+//! - [`Backtracking`] assigns the variables one at a time and undoes the
+//!   last assignment once no value is left, pruning the domains with
+//!   forward checking or [`ac3`] as it goes. Which variable and which value
+//!   come first is up to the MRV, degree and LCV heuristics.
+//! - [`MinConflicts`] starts from a complete assignment and repairs it one
+//!   variable at a time, which scales to large problems but can't tell
+//!   when there is no solution.
+//!
+//! ```
+//! use maze::csp::{Backtracking, Csp};
+//!
+//! let mut csp = Csp::new();
+//! let colors = ["red", "green", "blue"];
+//! let wa = csp.add_variable("WA", colors);
+//! let nt = csp.add_variable("NT", colors);
+//! let sa = csp.add_variable("SA", colors);
+//! csp.add_all_different(&[wa, nt, sa]);
+//!
+//! let solution = Backtracking::new().solve(&csp).unwrap();
+//! assert!(csp.is_solution(&solution));
+//! ```
+
+use std::{fmt, sync::Arc};
+
+pub mod backtracking;
+pub mod consistency;
+pub mod local;
+
+pub use backtracking::{Backtracking, CspStats, Inference, ValueOrder, VariableOrder};
+pub use consistency::ac3;
+pub use local::MinConflicts;
+
+/// Index of a variable in a [`Csp`].
+pub type Variable = usize;
+
+/// Values of the variables, `None` for the unassigned ones.
+pub type Assignment<T> = Vec<Option<T>>;
+
+/// Test of a constraint, receiving the values in the order of its scope.
+type Predicate<T> = Arc<dyn Fn(&[&T]) -> bool + Send + Sync>;
+
+/// Constraint on the values of the variables of its scope.
+pub struct Constraint<T> {
+    scope: Vec<Variable>,
+    predicate: Predicate<T>,
+}
+
+impl<T> Constraint<T> {
+    pub fn scope(&self) -> &[Variable] {
+        &self.scope
+    }
+
+    /// Whether the values, in the order of the scope, satisfy the
+    /// constraint.
+    pub fn is_satisfied(&self, values: &[&T]) -> bool {
+        (self.predicate)(values)
+    }
+
+    /// Whether the assignment satisfies the constraint, `None` until every
+    /// variable of the scope is assigned.
+    pub fn check(&self, assignment: &[Option<T>]) -> Option<bool> {
+        let values: Option<Vec<&T>> = self
+            .scope
+            .iter()
+            .map(|variable| assignment[*variable].as_ref())
+            .collect();
+
+        values.map(|values| self.is_satisfied(&values))
+    }
+
+    /// Same as [`Constraint::check`], with `variable` set to `value`.
+    pub fn check_with(
+        &self,
+        assignment: &[Option<T>],
+        variable: Variable,
+        value: &T,
+    ) -> Option<bool> {
+        let values: Option<Vec<&T>> = self
+            .scope
+            .iter()
+            .map(|other| match *other == variable {
+                true => Some(value),
+                false => assignment[*other].as_ref(),
+            })
+            .collect();
+
+        values.map(|values| self.is_satisfied(&values))
+    }
+
+    /// Whether a complete assignment satisfies the constraint.
+    pub fn is_satisfied_by(&self, values: &[T]) -> bool {
+        let values: Vec<&T> = self
+            .scope
+            .iter()
+            .map(|variable| &values[*variable])
+            .collect();
+
+        self.is_satisfied(&values)
+    }
+}
+
+impl<T> Clone for Constraint<T> {
+    fn clone(&self) -> Self {
+        Self {
+            scope: self.scope.clone(),
+            predicate: Arc::clone(&self.predicate),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Constraint<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Constraint")
+            .field("scope", &self.scope)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Variables with finite domains, and constraints on them.
+#[derive(Debug, Clone)]
+pub struct Csp<T> {
+    names: Vec<String>,
+    domains: Vec<Vec<T>>,
+    constraints: Vec<Constraint<T>>,
+    /// Constraints on each variable, as indices into `constraints`.
+    involved: Vec<Vec<usize>>,
+}
+
+impl<T> Default for Csp<T> {
+    fn default() -> Self {
+        Self {
+            names: Vec::new(),
+            domains: Vec::new(),
+            constraints: Vec::new(),
+            involved: Vec::new(),
+        }
+    }
+}
+
+impl<T: Clone + PartialEq + 'static> Csp<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_variable(
+        &mut self,
+        name: impl Into<String>,
+        domain: impl IntoIterator<Item = T>,
+    ) -> Variable {
+        self.names.push(name.into());
+        self.domains.push(domain.into_iter().collect());
+        self.involved.push(Vec::new());
+
+        self.names.len() - 1
+    }
+
+    /// Adds a constraint on the variables of `scope`, whose values the
+    /// predicate receives in the same order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a variable of the scope doesn't exist or is repeated.
+    pub fn add_constraint(
+        &mut self,
+        scope: &[Variable],
+        predicate: impl Fn(&[&T]) -> bool + Send + Sync + 'static,
+    ) {
+        for (index, variable) in scope.iter().enumerate() {
+            assert!(*variable < self.len(), "unknown variable {variable}");
+            assert!(
+                !scope[..index].contains(variable),
+                "variable {variable} is repeated in the scope"
+            );
+            self.involved[*variable].push(self.constraints.len());
+        }
+
+        self.constraints.push(Constraint {
+            scope: scope.to_vec(),
+            predicate: Arc::new(predicate),
+        });
+    }
+
+    /// Adds a constraint on the values of a single variable.
+    pub fn add_unary(
+        &mut self,
+        variable: Variable,
+        predicate: impl Fn(&T) -> bool + Send + Sync + 'static,
+    ) {
+        self.add_constraint(&[variable], move |values| predicate(values[0]));
+    }
+
+    /// Adds a constraint between two variables.
+    pub fn add_binary(
+        &mut self,
+        first: Variable,
+        second: Variable,
+        predicate: impl Fn(&T, &T) -> bool + Send + Sync + 'static,
+    ) {
+        self.add_constraint(&[first, second], move |values| {
+            predicate(values[0], values[1])
+        });
+    }
+
+    /// Constrains the variables to pairwise different values, as binary
+    /// constraints the inference can work with.
+    pub fn add_all_different(&mut self, variables: &[Variable]) {
+        for (index, first) in variables.iter().enumerate() {
+            for second in &variables[index + 1..] {
+                self.add_binary(*first, *second, |a, b| a != b);
+            }
+        }
+    }
+}
+
+impl<T> Csp<T> {
+    /// Number of variables.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn name(&self, variable: Variable) -> &str {
+        &self.names[variable]
+    }
+
+    pub fn domain(&self, variable: Variable) -> &[T] {
+        &self.domains[variable]
+    }
+
+    pub fn domains(&self) -> &[Vec<T>] {
+        &self.domains
+    }
+
+    pub fn constraints(&self) -> &[Constraint<T>] {
+        &self.constraints
+    }
+
+    /// Indices of the constraints whose scope holds the variable.
+    pub fn involved(&self, variable: Variable) -> &[usize] {
+        &self.involved[variable]
+    }
+
+    /// Constraints whose scope holds the variable.
+    pub fn constraints_on(&self, variable: Variable) -> impl Iterator<Item = &Constraint<T>> + '_ {
+        self.involved[variable]
+            .iter()
+            .map(|constraint| &self.constraints[*constraint])
+    }
+
+    /// Variables sharing a constraint with `variable`.
+    pub fn neighbors(&self, variable: Variable) -> Vec<Variable> {
+        let mut neighbors: Vec<_> = self
+            .constraints_on(variable)
+            .flat_map(|constraint| constraint.scope().iter().copied())
+            .filter(|other| *other != variable)
+            .collect();
+        neighbors.sort_unstable();
+        neighbors.dedup();
+
+        neighbors
+    }
+
+    /// Whether setting `variable` to `value` violates none of its
+    /// constraints whose other variables are assigned.
+    pub fn is_consistent(&self, assignment: &[Option<T>], variable: Variable, value: &T) -> bool {
+        self.constraints_on(variable)
+            .all(|constraint| constraint.check_with(assignment, variable, value) != Some(false))
+    }
+
+    /// Constraints of `variable` that a complete assignment violates.
+    pub fn conflicts(&self, values: &[T], variable: Variable) -> usize {
+        self.constraints_on(variable)
+            .filter(|constraint| !constraint.is_satisfied_by(values))
+            .count()
+    }
+}
+
+impl<T: PartialEq> Csp<T> {
+    /// Whether the values are in the domains and satisfy every constraint.
+    pub fn is_solution(&self, values: &[T]) -> bool {
+        values.len() == self.len()
+            && values
+                .iter()
+                .zip(&self.domains)
+                .all(|(value, domain)| domain.contains(value))
+            && self
+                .constraints
+                .iter()
+                .all(|constraint| constraint.is_satisfied_by(values))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ac3, Backtracking, Csp, Inference, MinConflicts, ValueOrder, VariableOrder};
+
+    /// Coloring the states and territories of Australia, Tasmania apart,
+    /// with Western Australia red.
+    fn australia() -> Csp<&'static str> {
+        let mut csp = Csp::new();
+        let colors = ["red", "green", "blue"];
+        let [wa, nt, sa, q, nsw, v] =
+            ["WA", "NT", "SA", "Q", "NSW", "V"].map(|name| csp.add_variable(name, colors));
+
+        for (first, second) in [
+            (wa, nt),
+            (wa, sa),
+            (nt, sa),
+            (nt, q),
+            (sa, q),
+            (sa, nsw),
+            (sa, v),
+            (q, nsw),
+            (nsw, v),
+        ] {
+            csp.add_all_different(&[first, second]);
+        }
+        csp.add_unary(wa, |color| *color == "red");
+
+        csp
+    }
+
+    /// One queen per column, the variables being their rows.
+    fn queens(n: i64) -> Csp<i64> {
+        let mut csp = Csp::new();
+        let columns: Vec<_> = (0..n)
+            .map(|column| csp.add_variable(format!("Q{column}"), 0..n))
+            .collect();
+
+        for first in 0..n {
+            for second in first + 1..n {
+                let gap = second - first;
+                csp.add_binary(
+                    columns[first as usize],
+                    columns[second as usize],
+                    move |a, b| a != b && (a - b).abs() != gap,
+                );
+            }
+        }
+
+        csp
+    }
+
+    #[test]
+    fn test_csp() {
+        let csp = australia();
+        assert_eq!(csp.neighbors(2), [0, 1, 3, 4, 5]);
+
+        // Node and arc consistency leave a single color to WA, NT and SA
+        let mut domains = csp.domains().to_vec();
+        assert!(ac3(&csp, &mut domains));
+        assert_eq!(domains[0], ["red"]);
+        assert_eq!(domains[1], ["green", "blue"]);
+
+        for inference in [Inference::None, Inference::ForwardChecking, Inference::Mac] {
+            for variables in [
+                VariableOrder::Static,
+                VariableOrder::Mrv,
+                VariableOrder::MrvDegree,
+            ] {
+                for values in [ValueOrder::Static, ValueOrder::Lcv] {
+                    let mut solver = Backtracking::new()
+                        .with_inference(inference)
+                        .with_variable_order(variables)
+                        .with_value_order(values);
+                    let solution = solver.solve(&csp).unwrap();
+
+                    assert!(csp.is_solution(&solution), "{solver:?}");
+                    assert_eq!(solution[0], "red");
+                }
+            }
+        }
+
+        // Forward checking and MAC try fewer values than plain backtracking
+        let board = queens(8);
+        let assignments = |inference| {
+            let mut solver = Backtracking::new()
+                .with_inference(inference)
+                .with_variable_order(VariableOrder::Static);
+            let solution = solver.solve(&board).unwrap();
+            assert!(board.is_solution(&solution));
+            solver.stats().assignments
+        };
+        let plain = assignments(Inference::None);
+        let forward = assignments(Inference::ForwardChecking);
+        assert!(forward < plain);
+        assert!(assignments(Inference::Mac) <= forward);
+
+        let mut local = MinConflicts::new().with_seed(3).with_max_steps(10_000);
+        let board = queens(64);
+        let solution = local.solve(&board).unwrap();
+        assert!(board.is_solution(&solution));
+        assert!(local.steps() <= 10_000);
+
+        // Two colors aren't enough for a triangle, although every arc is
+        // consistent
+        let mut csp = Csp::new();
+        let [a, b, c] = ["A", "B", "C"].map(|name| csp.add_variable(name, [0, 1]));
+        csp.add_all_different(&[a, b, c]);
+        assert!(ac3(&csp, &mut csp.domains().to_vec()));
+        assert_eq!(Backtracking::new().solve(&csp), None);
+        assert_eq!(MinConflicts::new().with_max_steps(100).solve(&csp), None);
+    }
+}
@@ -21,6 +21,42 @@ pub trait Environment {
     /// Produces a signal based on an action.
     fn update(&mut self, action: Self::Action) -> Result<Self::Stimuli, Self::Error>;
 
-    /// Render the environment state
-    fn render(&self);
+    /// Brings the environment back to its initial state and starts a new
+    /// episode, returning the stimuli of the initial state.
+    ///
+    /// A seed asks the environment to regenerate itself deterministically
+    /// from it, e.g. to run the next episode on a different layout.
+    fn reset(&mut self, seed: Option<u64>) -> Self::Stimuli;
+
+    /// Checks if the current episode is over.
+    fn is_terminal(&self) -> bool;
+}
+
+/// How the actions of several agents sharing an environment are resolved
+/// within a tick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Resolution {
+    /// Agents act one after the other, each one seeing the effects of the
+    /// agents that acted before it in the same tick.
+    #[default]
+    TurnBased,
+    /// Every agent acts on the state of the world at the beginning of the
+    /// tick and the effects become visible together when the tick ends.
+    Simultaneous,
+}
+
+/// An [`Environment`] acted upon by several agents at once.
+///
+/// The environment keeps one view per agent; [`Environment::update`] and the
+/// stimuli it returns always refer to the selected agent.
+pub trait SharedEnvironment: Environment {
+    /// Selects the agent whose actions are handled next.
+    fn select_agent(&mut self, agent: usize);
+
+    /// Called before any agent acts in a tick.
+    fn begin_tick(&mut self, _resolution: Resolution) {}
+
+    /// Called after every agent acted in a tick. Environments resolving
+    /// moves simultaneously apply the buffered actions here.
+    fn end_tick(&mut self) {}
 }
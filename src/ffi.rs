@@ -0,0 +1,817 @@
+//! C bindings
+//!
+//! A stable C API over [`Maze`], [`PathFinder`] and [`Simulator`], declared
+//! in `include/maze.h`. Mazes and solvers are opaque handles created and
+//! freed by the functions of this module. Every fallible function returns a
+//! [`MazeStatus`], and panics never cross the boundary.
+//!
+//! Mazes are const generic, so their side must be one of [`SIZES`].
+
+use std::{
+    ffi::{c_char, CStr},
+    fmt::Debug,
+    panic::{self, AssertUnwindSafe},
+    ptr, slice,
+};
+
+use crate::{
+    agent::Agent,
+    environment::Environment,
+    maze::{
+        agent::{
+            astar::AStarFinder,
+            graph_based::{BFSFinder, DFSFinder},
+            greedy::GreedyFinder,
+            FrontierManager, PathFinder, RandomFinder,
+        },
+        coordinates::Coordinates,
+        generator::{RbGenerator, WalkGenerator},
+        with_size, Ground, Maze, SIZES,
+    },
+    render::Headless,
+    simulator::Simulator,
+};
+
+/// Result of the C functions.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MazeStatus {
+    Ok = 0,
+    NullPointer = 1,
+    /// The side of the maze isn't one of [`SIZES`].
+    InvalidSize = 2,
+    /// An unknown generator or finder, or a density above 100.
+    InvalidArgument = 3,
+    OutOfBounds = 4,
+    /// The finder failed to act.
+    AgentError = 5,
+    /// The buffer can't hold the result, whose length was written back.
+    BufferTooSmall = 6,
+    Panic = 7,
+}
+
+impl MazeStatus {
+    fn message(code: i32) -> &'static CStr {
+        match code {
+            0 => c"ok",
+            1 => c"null pointer",
+            2 => c"unsupported maze size",
+            3 => c"invalid argument",
+            4 => c"coordinates out of the maze",
+            5 => c"the finder failed to act",
+            6 => c"buffer too small",
+            7 => c"internal panic",
+            _ => c"unknown status",
+        }
+    }
+}
+
+/// Coordinates of a cell, `x` being the row.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MazeCoordinates {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl From<MazeCoordinates> for Coordinates {
+    fn from(value: MazeCoordinates) -> Self {
+        Coordinates::new(value.x, value.y)
+    }
+}
+
+impl From<Coordinates> for MazeCoordinates {
+    fn from(value: Coordinates) -> Self {
+        Self {
+            x: value.x,
+            y: value.y,
+        }
+    }
+}
+
+/// Statistics of a solver.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MazeStats {
+    /// Number of expansions.
+    pub steps: usize,
+    /// Distinct cells expanded.
+    pub expanded: usize,
+    pub frontier: usize,
+    /// Cells of the current path, start and end included.
+    pub path_length: usize,
+    /// The finder stopped, reaching the goal or exhausting its frontier.
+    pub finished: bool,
+    pub reached_goal: bool,
+}
+
+/// Generator values of `maze_generate`.
+const GENERATOR_BACKTRACKING: u32 = 0;
+const GENERATOR_RANDOM_WALK: u32 = 1;
+
+/// Finder values of `maze_solver_new`.
+const FINDER_ASTAR: u32 = 0;
+const FINDER_GREEDY: u32 = 1;
+const FINDER_BFS: u32 = 2;
+const FINDER_DFS: u32 = 3;
+const FINDER_RANDOM: u32 = 4;
+
+/// Cell values of `maze_get_cells` and `maze_from_cells`, doors, keys and
+/// items being or'ed with their index.
+const CELL_FREE: u8 = 0;
+const CELL_BLOCKED: u8 = 1;
+const CELL_DOOR: u8 = 0x40;
+const CELL_KEY: u8 = 0x80;
+const CELL_ITEM: u8 = 0xC0;
+const CELL_INDEX: u8 = 0x3F;
+
+/// Value of a cell. Doors, keys and items past [`CELL_INDEX`] are written as
+/// walls and free cells.
+fn cell_value(ground: Ground) -> u8 {
+    match ground {
+        Ground::Blocked => CELL_BLOCKED,
+        Ground::Door(door) if door <= CELL_INDEX => CELL_DOOR | door,
+        Ground::Door(_) => CELL_BLOCKED,
+        Ground::Key(key) if key <= CELL_INDEX => CELL_KEY | key,
+        Ground::Item(item) if item <= CELL_INDEX => CELL_ITEM | item,
+        _ => CELL_FREE,
+    }
+}
+
+/// Ground of a cell value, the values below [`CELL_DOOR`] other than
+/// [`CELL_FREE`] being walls.
+fn cell_ground(cell: u8) -> Ground {
+    let index = cell & CELL_INDEX;
+
+    match cell & !CELL_INDEX {
+        CELL_DOOR => Ground::Door(index),
+        CELL_KEY => Ground::Key(index),
+        CELL_ITEM => Ground::Item(index),
+        _ => (cell != CELL_FREE).into(),
+    }
+}
+
+/// A maze of any supported size.
+trait AnyMaze {
+    fn size(&self) -> usize;
+
+    /// Writes the value of every cell, row after row.
+    fn cells(&self, cells: &mut [u8]);
+
+    fn solver(self: Box<Self>, finder: u32) -> Result<Box<dyn AnySolver>, MazeStatus>;
+}
+
+/// A solver of any supported size and finder.
+trait AnySolver {
+    /// Returns whether the finder stopped.
+    fn step(&mut self) -> Result<bool, MazeStatus>;
+
+    fn stats(&self) -> MazeStats;
+
+    fn path(&self) -> Vec<Coordinates>;
+}
+
+impl<const N: usize> AnyMaze for Maze<N> {
+    fn size(&self) -> usize {
+        N
+    }
+
+    fn cells(&self, cells: &mut [u8]) {
+        for (index, cell) in cells.iter_mut().enumerate().take(N * N) {
+            let coords = Coordinates::new((index / N) as i64, (index % N) as i64);
+            *cell = cell_value(self.get(&coords).unwrap_or(Ground::Blocked));
+        }
+    }
+
+    fn solver(self: Box<Self>, finder: u32) -> Result<Box<dyn AnySolver>, MazeStatus> {
+        fn boxed<const N: usize, F: FrontierManager + Debug + 'static>(
+            maze: Maze<N>,
+        ) -> Box<dyn AnySolver> {
+            let finder = PathFinder::<N, F>::new(&maze);
+            Box::new(Simulator::new(finder, Headless, maze))
+        }
+
+        match finder {
+            FINDER_ASTAR => Ok(boxed::<N, AStarFinder>(*self)),
+            FINDER_GREEDY => Ok(boxed::<N, GreedyFinder>(*self)),
+            FINDER_BFS => Ok(boxed::<N, BFSFinder>(*self)),
+            FINDER_DFS => Ok(boxed::<N, DFSFinder>(*self)),
+            FINDER_RANDOM => Ok(boxed::<N, RandomFinder>(*self)),
+            _ => Err(MazeStatus::InvalidArgument),
+        }
+    }
+}
+
+impl<const N: usize, F> AnySolver for Simulator<PathFinder<N, F>, Headless>
+where
+    F: FrontierManager + Debug,
+{
+    fn step(&mut self) -> Result<bool, MazeStatus> {
+        Simulator::step(self).map_err(|()| MazeStatus::AgentError)
+    }
+
+    fn stats(&self) -> MazeStats {
+        let maze = self.environment();
+        let trace = maze.trace();
+
+        MazeStats {
+            steps: trace.steps,
+            expanded: trace.visited.len(),
+            frontier: self.agent().frontier().len(),
+            path_length: maze.current_path.len(),
+            finished: self.agent().should_stop(),
+            reached_goal: maze.is_terminal(),
+        }
+    }
+
+    fn path(&self) -> Vec<Coordinates> {
+        self.environment().current_path.cells()
+    }
+}
+
+/// Opaque maze handle.
+pub struct MazeHandle(Box<dyn AnyMaze>);
+
+/// Opaque solver handle.
+pub struct MazeSolver(Box<dyn AnySolver>);
+
+/// Runs `body`, turning panics into [`MazeStatus::Panic`].
+fn guard(body: impl FnOnce() -> Result<(), MazeStatus>) -> MazeStatus {
+    match panic::catch_unwind(AssertUnwindSafe(body)) {
+        Ok(Ok(())) => MazeStatus::Ok,
+        Ok(Err(status)) => status,
+        Err(_) => MazeStatus::Panic,
+    }
+}
+
+fn check_size(size: usize) -> Result<(), MazeStatus> {
+    if SIZES.contains(&size) {
+        Ok(())
+    } else {
+        Err(MazeStatus::InvalidSize)
+    }
+}
+
+fn check_bounds(size: usize, positions: &[MazeCoordinates]) -> Result<(), MazeStatus> {
+    let inside = |value: i64| (0..size as i64).contains(&value);
+
+    if positions.iter().all(|p| inside(p.x) && inside(p.y)) {
+        Ok(())
+    } else {
+        Err(MazeStatus::OutOfBounds)
+    }
+}
+
+/// Writes a new handle to `out`.
+///
+/// # Safety
+///
+/// `out` must be null or valid for writes.
+unsafe fn give<T>(out: *mut *mut T, value: T) -> Result<(), MazeStatus> {
+    let out = out.as_mut().ok_or(MazeStatus::NullPointer)?;
+    *out = Box::into_raw(Box::new(value));
+    Ok(())
+}
+
+/// Generates a maze of side `size` whose layout is determined by `seed`.
+///
+/// # Safety
+///
+/// `out` must be null or valid for writes. The maze written to it must be
+/// freed with `maze_free` or given to `maze_solver_new`.
+#[no_mangle]
+pub unsafe extern "C" fn maze_generate(
+    size: usize,
+    density: u8,
+    seed: u64,
+    generator: u32,
+    start: MazeCoordinates,
+    goal: MazeCoordinates,
+    out: *mut *mut MazeHandle,
+) -> MazeStatus {
+    guard(|| {
+        check_size(size)?;
+        check_bounds(size, &[start, goal])?;
+        if density > 100 {
+            return Err(MazeStatus::InvalidArgument);
+        }
+
+        let mut generator_seed = [0; 32];
+        generator_seed[..8].copy_from_slice(&seed.to_le_bytes());
+        let (start, goal) = (start.into(), goal.into());
+
+        let maze: Box<dyn AnyMaze> = with_size!(size, N => match generator {
+            GENERATOR_BACKTRACKING => Box::new(Maze::<N>::with_generator(
+                start,
+                goal,
+                density,
+                seed,
+                RbGenerator::new(Some(generator_seed)),
+            )),
+            GENERATOR_RANDOM_WALK => Box::new(Maze::<N>::with_generator(
+                start,
+                goal,
+                density,
+                seed,
+                WalkGenerator::new(Some(generator_seed)),
+            )),
+            _ => return Err(MazeStatus::InvalidArgument),
+        });
+
+        give(out, MazeHandle(maze))
+    })
+}
+
+/// Creates a maze of side `size` from `size * size` cells, row after row,
+/// `0` being free, doors, keys and items or'ed with their index, and
+/// anything else blocked. Start and goal are freed.
+///
+/// # Safety
+///
+/// `cells` must be null or valid for `size * size` reads, and `out` null or
+/// valid for writes. The maze written to `out` must be freed with
+/// `maze_free` or given to `maze_solver_new`.
+#[no_mangle]
+pub unsafe extern "C" fn maze_from_cells(
+    size: usize,
+    cells: *const u8,
+    start: MazeCoordinates,
+    goal: MazeCoordinates,
+    out: *mut *mut MazeHandle,
+) -> MazeStatus {
+    guard(|| {
+        check_size(size)?;
+        check_bounds(size, &[start, goal])?;
+        if cells.is_null() {
+            return Err(MazeStatus::NullPointer);
+        }
+
+        let cells = slice::from_raw_parts(cells, size * size);
+
+        let maze: Box<dyn AnyMaze> = with_size!(size, N => {
+            let mut grid = [[Ground::Free; N]; N];
+            for (index, cell) in cells.iter().enumerate() {
+                grid[index / N][index % N] = cell_ground(*cell);
+            }
+
+            Box::new(Maze::<N>::from_cells(start.into(), goal.into(), grid))
+        });
+
+        give(out, MazeHandle(maze))
+    })
+}
+
+/// Side of the maze, or `0` for a null maze.
+///
+/// # Safety
+///
+/// `maze` must be null or a maze that wasn't freed.
+#[no_mangle]
+pub unsafe extern "C" fn maze_size(maze: *const MazeHandle) -> usize {
+    maze.as_ref().map_or(0, |maze| maze.0.size())
+}
+
+/// Copies the cells of the maze to `cells`, row after row, `1` for blocked
+/// cells, `0` for free ones and doors, keys and items or'ed with their
+/// index. `len` must be at least `size * size`.
+///
+/// # Safety
+///
+/// `maze` must be null or a maze that wasn't freed, and `cells` null or valid
+/// for `len` writes.
+#[no_mangle]
+pub unsafe extern "C" fn maze_get_cells(
+    maze: *const MazeHandle,
+    cells: *mut u8,
+    len: usize,
+) -> MazeStatus {
+    guard(|| {
+        let maze = maze.as_ref().ok_or(MazeStatus::NullPointer)?;
+        if cells.is_null() {
+            return Err(MazeStatus::NullPointer);
+        }
+        if len < maze.0.size() * maze.0.size() {
+            return Err(MazeStatus::BufferTooSmall);
+        }
+
+        maze.0.cells(slice::from_raw_parts_mut(cells, len));
+        Ok(())
+    })
+}
+
+/// Frees a maze. Null is ignored.
+///
+/// # Safety
+///
+/// `maze` must be null or a maze that wasn't freed nor given to a solver.
+#[no_mangle]
+pub unsafe extern "C" fn maze_free(maze: *mut MazeHandle) {
+    if !maze.is_null() {
+        drop(Box::from_raw(maze));
+    }
+}
+
+/// Creates a solver running `finder` on the maze. On success the solver
+/// takes the maze, which must not be used nor freed anymore.
+///
+/// # Safety
+///
+/// `maze` must be null or a maze that wasn't freed, and `out` null or valid
+/// for writes. The solver written to `out` must be freed with
+/// `maze_solver_free`.
+#[no_mangle]
+pub unsafe extern "C" fn maze_solver_new(
+    maze: *mut MazeHandle,
+    finder: u32,
+    out: *mut *mut MazeSolver,
+) -> MazeStatus {
+    guard(|| {
+        if maze.is_null() || out.is_null() {
+            return Err(MazeStatus::NullPointer);
+        }
+        if finder > FINDER_RANDOM {
+            return Err(MazeStatus::InvalidArgument);
+        }
+
+        let maze = Box::from_raw(maze);
+        let solver = maze.0.solver(finder)?;
+
+        give(out, MazeSolver(solver))
+    })
+}
+
+/// Makes the finder expand one cell, unless it already stopped. Whether it
+/// stopped is written to `finished`, if not null.
+///
+/// # Safety
+///
+/// `solver` must be null or a solver that wasn't freed, and `finished` null
+/// or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn maze_solver_step(
+    solver: *mut MazeSolver,
+    finished: *mut bool,
+) -> MazeStatus {
+    guard(|| {
+        let solver = solver.as_mut().ok_or(MazeStatus::NullPointer)?;
+        let stopped = solver.0.step()?;
+
+        if let Some(finished) = finished.as_mut() {
+            *finished = stopped;
+        }
+        Ok(())
+    })
+}
+
+/// Steps the finder until it stops, or at most `max_steps` times unless
+/// `max_steps` is `0`. Whether it stopped is written to `finished`, if not
+/// null.
+///
+/// # Safety
+///
+/// `solver` must be null or a solver that wasn't freed, and `finished` null
+/// or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn maze_solver_run(
+    solver: *mut MazeSolver,
+    max_steps: usize,
+    finished: *mut bool,
+) -> MazeStatus {
+    guard(|| {
+        let solver = solver.as_mut().ok_or(MazeStatus::NullPointer)?;
+        let mut steps = 0;
+        let mut stopped = false;
+
+        while !stopped && (max_steps == 0 || steps < max_steps) {
+            stopped = solver.0.step()?;
+            steps += 1;
+        }
+
+        if let Some(finished) = finished.as_mut() {
+            *finished = stopped;
+        }
+        Ok(())
+    })
+}
+
+/// Writes the statistics of the solver to `out`.
+///
+/// # Safety
+///
+/// `solver` must be null or a solver that wasn't freed, and `out` null or
+/// valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn maze_solver_stats(
+    solver: *const MazeSolver,
+    out: *mut MazeStats,
+) -> MazeStatus {
+    guard(|| {
+        let solver = solver.as_ref().ok_or(MazeStatus::NullPointer)?;
+        let out = out.as_mut().ok_or(MazeStatus::NullPointer)?;
+
+        *out = solver.0.stats();
+        Ok(())
+    })
+}
+
+/// Copies the current path of the finder to `path`, from the start. Its
+/// length is written to `len`, even when `capacity` is too small.
+///
+/// # Safety
+///
+/// `solver` must be null or a solver that wasn't freed, `path` null or valid
+/// for `capacity` writes, and `len` null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn maze_solver_path(
+    solver: *const MazeSolver,
+    path: *mut MazeCoordinates,
+    capacity: usize,
+    len: *mut usize,
+) -> MazeStatus {
+    guard(|| {
+        let solver = solver.as_ref().ok_or(MazeStatus::NullPointer)?;
+        let len = len.as_mut().ok_or(MazeStatus::NullPointer)?;
+        let current = solver.0.path();
+
+        *len = current.len();
+        if capacity < current.len() {
+            return Err(MazeStatus::BufferTooSmall);
+        }
+        if path.is_null() {
+            return Err(MazeStatus::NullPointer);
+        }
+
+        for (index, coords) in current.iter().enumerate() {
+            ptr::write(path.add(index), (*coords).into());
+        }
+        Ok(())
+    })
+}
+
+/// Frees a solver and its maze. Null is ignored.
+///
+/// # Safety
+///
+/// `solver` must be null or a solver that wasn't freed.
+#[no_mangle]
+pub unsafe extern "C" fn maze_solver_free(solver: *mut MazeSolver) {
+    if !solver.is_null() {
+        drop(Box::from_raw(solver));
+    }
+}
+
+/// Static description of a status.
+#[no_mangle]
+pub extern "C" fn maze_status_message(status: i32) -> *const c_char {
+    MazeStatus::message(status).as_ptr()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{ffi::CStr, ptr};
+
+    use super::*;
+
+    #[test]
+    fn test_solve_through_c_api() {
+        let start = MazeCoordinates { x: 0, y: 0 };
+        let goal = MazeCoordinates { x: 9, y: 9 };
+        let mut maze = ptr::null_mut();
+
+        unsafe {
+            assert_eq!(
+                maze_generate(11, 20, 3, GENERATOR_BACKTRACKING, start, goal, &mut maze),
+                MazeStatus::InvalidSize
+            );
+            assert_eq!(
+                maze_generate(10, 20, 3, GENERATOR_BACKTRACKING, start, goal, &mut maze),
+                MazeStatus::Ok
+            );
+            assert_eq!(maze_size(maze), 10);
+
+            let mut cells = [0; 100];
+            assert_eq!(
+                maze_get_cells(maze, cells.as_mut_ptr(), 100),
+                MazeStatus::Ok
+            );
+
+            let mut solver = ptr::null_mut();
+            assert_eq!(
+                maze_solver_new(maze, FINDER_ASTAR, &mut solver),
+                MazeStatus::Ok
+            );
+
+            let mut finished = false;
+            assert_eq!(maze_solver_step(solver, &mut finished), MazeStatus::Ok);
+            assert_eq!(maze_solver_run(solver, 0, &mut finished), MazeStatus::Ok);
+            assert!(finished);
+
+            let mut stats = MazeStats::default();
+            assert_eq!(maze_solver_stats(solver, &mut stats), MazeStatus::Ok);
+            assert!(stats.reached_goal);
+
+            let mut len = 0;
+            assert_eq!(
+                maze_solver_path(solver, ptr::null_mut(), 0, &mut len),
+                MazeStatus::BufferTooSmall
+            );
+            assert_eq!(len, stats.path_length);
+
+            let mut path = vec![MazeCoordinates { x: 0, y: 0 }; len];
+            assert_eq!(
+                maze_solver_path(solver, path.as_mut_ptr(), len, &mut len),
+                MazeStatus::Ok
+            );
+            assert_eq!(path.first(), Some(&start));
+            assert_eq!(path.last(), Some(&goal));
+
+            maze_solver_free(solver);
+
+            // The same cells give back the same maze, doors, keys and items
+            // included
+            cells[1..4].copy_from_slice(&[CELL_DOOR | 2, CELL_KEY | 2, CELL_ITEM]);
+            let mut copy = ptr::null_mut();
+            assert_eq!(
+                maze_from_cells(10, cells.as_ptr(), start, goal, &mut copy),
+                MazeStatus::Ok
+            );
+            let mut copied = [0; 100];
+            maze_get_cells(copy, copied.as_mut_ptr(), 100);
+            assert_eq!(cells, copied);
+            maze_free(copy);
+
+            let message = CStr::from_ptr(maze_status_message(MazeStatus::InvalidSize as i32));
+            assert_eq!(message.to_str(), Ok("unsupported maze size"));
+        }
+    }
+
+    /// Parts of the header or of this file, without comments nor line
+    /// breaks.
+    fn tokens(source: &str) -> String {
+        source
+            .lines()
+            .map(|line| line.split("//").next().unwrap())
+            .collect::<Vec<_>>()
+            .join(" ")
+            .split("/*")
+            .map(|part| part.split_once("*/").map_or(part, |(_, rest)| rest))
+            .collect::<String>()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .replace("( ", "(")
+            .replace(" )", ")")
+    }
+
+    /// C declaration of `name`, of a Rust type.
+    fn c_declaration(rust: &str, name: &str) -> String {
+        let rust = rust.trim();
+        if let Some(pointee) = rust.strip_prefix("*const ") {
+            return format!("const {}", c_declaration(pointee, &format!("*{name}")));
+        }
+        if let Some(pointee) = rust.strip_prefix("*mut ") {
+            return c_declaration(pointee, &format!("*{name}"));
+        }
+
+        let base = match rust {
+            "usize" => "size_t",
+            "u8" => "uint8_t",
+            "u32" => "uint32_t",
+            "u64" => "uint64_t",
+            "i32" => "int32_t",
+            "i64" => "int64_t",
+            "c_char" => "char",
+            other => other,
+        };
+        format!("{base} {name}")
+    }
+
+    /// Fields of a `#[repr(C)]` struct of this file, as C declarations.
+    fn rust_fields(source: &str, name: &str) -> Vec<String> {
+        let body = source
+            .split(&format!("pub struct {name} {{"))
+            .nth(1)
+            .unwrap();
+        body[..body.find('}').unwrap()]
+            .split(',')
+            .filter_map(|field| field.trim().strip_prefix("pub "))
+            .map(|field| {
+                let (name, rust) = field.split_once(':').unwrap();
+                format!("{};", c_declaration(rust, name.trim()))
+            })
+            .collect()
+    }
+
+    fn header_fields(header: &str, name: &str) -> Vec<String> {
+        let body = header
+            .split(&format!("typedef struct {name} {{"))
+            .nth(1)
+            .unwrap();
+        body[..body.find('}').unwrap()]
+            .split_inclusive(';')
+            .map(|field| field.trim().to_string())
+            .filter(|field| !field.is_empty())
+            .collect()
+    }
+
+    #[test]
+    fn test_header_matches_bindings() {
+        let header = tokens(include_str!("../include/maze.h"));
+        let source = include_str!("ffi.rs");
+        let source = tokens(&source[..source.find("#[cfg(test)]").unwrap()]);
+
+        // Every function, with the same signature
+        let functions: Vec<_> = source.split("extern \"C\" fn ").skip(1).collect();
+        assert!(functions.len() > 10);
+        for function in functions {
+            let (name, rest) = function.split_once('(').unwrap();
+            let (parameters, rest) = rest.split_once(')').unwrap();
+            let parameters: Vec<_> = parameters
+                .split(',')
+                .filter(|parameter| !parameter.trim().is_empty())
+                .map(|parameter| {
+                    let (name, rust) = parameter.split_once(':').unwrap();
+                    c_declaration(rust, name.trim())
+                })
+                .collect();
+            let output = match rest.trim().strip_prefix("->") {
+                Some(output) => output.split('{').next().unwrap(),
+                None => "void",
+            };
+
+            let declaration = format!(
+                "{}({});",
+                c_declaration(output, name),
+                parameters.join(", ")
+            );
+            assert!(
+                header.contains(&declaration),
+                "include/maze.h doesn't declare {declaration}"
+            );
+        }
+
+        // Every constant, with the same value
+        let values: Vec<(String, i64)> = header
+            .split(['{', '}', ','])
+            .filter_map(|entry| entry.trim().split_once(" = "))
+            .filter(|(name, _)| name.starts_with("MAZE_"))
+            .map(|(name, value)| {
+                let value = match value.strip_prefix("0x") {
+                    Some(hex) => i64::from_str_radix(hex, 16).unwrap(),
+                    None => value.parse().unwrap(),
+                };
+                (name.to_string(), value)
+            })
+            .collect();
+        let constants = [
+            ("MAZE_OK", MazeStatus::Ok as i64),
+            ("MAZE_NULL_POINTER", MazeStatus::NullPointer as i64),
+            ("MAZE_INVALID_SIZE", MazeStatus::InvalidSize as i64),
+            ("MAZE_INVALID_ARGUMENT", MazeStatus::InvalidArgument as i64),
+            ("MAZE_OUT_OF_BOUNDS", MazeStatus::OutOfBounds as i64),
+            ("MAZE_AGENT_ERROR", MazeStatus::AgentError as i64),
+            ("MAZE_BUFFER_TOO_SMALL", MazeStatus::BufferTooSmall as i64),
+            ("MAZE_PANIC", MazeStatus::Panic as i64),
+            ("MAZE_GENERATOR_BACKTRACKING", GENERATOR_BACKTRACKING.into()),
+            ("MAZE_GENERATOR_RANDOM_WALK", GENERATOR_RANDOM_WALK.into()),
+            ("MAZE_FINDER_ASTAR", FINDER_ASTAR.into()),
+            ("MAZE_FINDER_GREEDY", FINDER_GREEDY.into()),
+            ("MAZE_FINDER_BFS", FINDER_BFS.into()),
+            ("MAZE_FINDER_DFS", FINDER_DFS.into()),
+            ("MAZE_FINDER_RANDOM", FINDER_RANDOM.into()),
+            ("MAZE_CELL_FREE", CELL_FREE.into()),
+            ("MAZE_CELL_BLOCKED", CELL_BLOCKED.into()),
+            ("MAZE_CELL_DOOR", CELL_DOOR.into()),
+            ("MAZE_CELL_KEY", CELL_KEY.into()),
+            ("MAZE_CELL_ITEM", CELL_ITEM.into()),
+            ("MAZE_CELL_INDEX", CELL_INDEX.into()),
+        ];
+        let constants: Vec<_> = constants
+            .iter()
+            .map(|(name, value)| (name.to_string(), *value))
+            .collect();
+        assert_eq!(values, constants);
+
+        // No constant of this file was left out of the list
+        let names = source
+            .split("const ")
+            .skip(1)
+            .filter_map(|constant| constant.split_once(':'))
+            .filter(|(_, rest)| {
+                let definition = rest.split(';').next().unwrap();
+                definition.split_whitespace().count() == 3 && definition.contains(" = ")
+            })
+            .map(|(name, _)| name);
+        for name in names {
+            assert!(
+                constants.iter().any(|(header, _)| header[5..] == *name),
+                "{name} is missing from the constants"
+            );
+        }
+
+        // Every field, in the same order
+        for name in ["MazeCoordinates", "MazeStats"] {
+            assert_eq!(rust_fields(&source, name), header_fields(&header, name));
+        }
+    }
+}
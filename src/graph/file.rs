@@ -0,0 +1,461 @@
+//! Graph files
+//!
+//! The edge list has one node or one edge per line. Edges `--` go both
+//! ways and edges `->` one way, weighing `1` without a weight. Nodes are
+//! added with their first edge, or alone on a line, with `@` and their
+//! coordinates. Comments start with `#`.
+//!
+//! ```text
+//! # Part of the map of Romania
+//! Arad @ 91 492
+//! Zerind @ 108 531
+//! Arad -- Zerind 75
+//! Arad -> Sibiu 140
+//! ```
+//!
+//! DOT files are read too, as long as they stick to nodes, edges and
+//! attributes, without subgraphs or ports. The `weight` attribute of the
+//! edges, or else their `label`, is their weight, and the `pos` attribute
+//! of the nodes their coordinates.
+//!
+//! ```text
+//! graph romania {
+//!     Arad [pos="91,492"];
+//!     Arad -- Zerind [weight=75];
+//!     Arad -- Sibiu -- Fagaras [label=99];
+//! }
+//! ```
+//!
+//! [`Graph`]'s [`FromStr`] reads a DOT file if it starts with `graph`,
+//! `digraph` or `strict`, and an edge list otherwise.
+
+use std::{error::Error, fmt, str::FromStr};
+
+use super::Graph;
+
+/// Error of parsing a [`Graph`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseGraphError {
+    Empty,
+    Unexpected {
+        line: usize,
+        token: String,
+    },
+    /// The file ends in the middle of a statement.
+    UnexpectedEnd,
+    InvalidNumber {
+        line: usize,
+        value: String,
+    },
+    /// Searches expect costs to never decrease along a path.
+    NegativeWeight {
+        line: usize,
+        weight: f32,
+    },
+    /// A `->` edge in a DOT `graph`, or a `--` edge in a `digraph`.
+    WrongEdge {
+        line: usize,
+        edge: String,
+    },
+}
+
+impl fmt::Display for ParseGraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "the graph has no node"),
+            Self::Unexpected { line, token } => write!(f, "unexpected `{token}` at line {line}"),
+            Self::UnexpectedEnd => write!(f, "unexpected end of the file"),
+            Self::InvalidNumber { line, value } => {
+                write!(f, "invalid number `{value}` at line {line}")
+            }
+            Self::NegativeWeight { line, weight } => {
+                write!(f, "negative weight {weight} at line {line}")
+            }
+            Self::WrongEdge { line, edge } => write!(
+                f,
+                "edge `{edge}` at line {line}, DOT graphs use `--` and digraphs `->`"
+            ),
+        }
+    }
+}
+
+impl Error for ParseGraphError {}
+
+fn number(line: usize, value: &str) -> Result<f32, ParseGraphError> {
+    value
+        .parse::<f32>()
+        .ok()
+        .filter(|number| number.is_finite())
+        .ok_or_else(|| ParseGraphError::InvalidNumber {
+            line,
+            value: value.to_string(),
+        })
+}
+
+fn weight(line: usize, value: &str) -> Result<f32, ParseGraphError> {
+    let weight = number(line, value)?;
+
+    if weight < 0.0 {
+        return Err(ParseGraphError::NegativeWeight { line, weight });
+    }
+
+    Ok(weight)
+}
+
+impl Graph {
+    /// Parses an edge list.
+    pub fn from_edge_list(text: &str) -> Result<Self, ParseGraphError> {
+        let mut graph = Self::new();
+
+        for (index, content) in text.lines().enumerate() {
+            let line = index + 1;
+            let content = content.split('#').next().unwrap_or_default();
+            let tokens: Vec<&str> = content.split_whitespace().collect();
+
+            let (from, edge, to, weight) = match tokens[..] {
+                [] => continue,
+                [name] => {
+                    graph.add_node(name);
+                    continue;
+                }
+                [name, "@", x, y] => {
+                    let node = graph.add_node(name);
+                    graph.set_coordinates(node, number(line, x)?, number(line, y)?);
+                    continue;
+                }
+                [from, edge @ ("--" | "->"), to] => (from, edge, to, 1.0),
+                [from, edge @ ("--" | "->"), to, value] => (from, edge, to, weight(line, value)?),
+                _ => {
+                    return Err(ParseGraphError::Unexpected {
+                        line,
+                        token: content.trim().to_string(),
+                    })
+                }
+            };
+
+            let from = graph.add_node(from);
+            let to = graph.add_node(to);
+            graph.add_edge(from, to, weight);
+            if edge == "--" {
+                graph.add_edge(to, from, weight);
+            }
+        }
+
+        if graph.is_empty() {
+            return Err(ParseGraphError::Empty);
+        }
+
+        Ok(graph)
+    }
+
+    /// Parses a DOT file.
+    pub fn from_dot(text: &str) -> Result<Self, ParseGraphError> {
+        let mut parser = DotParser {
+            tokens: tokenize(text)?,
+            position: 0,
+        };
+
+        let graph = parser.graph()?;
+        if let Some((line, token)) = parser.tokens.get(parser.position) {
+            return Err(ParseGraphError::Unexpected {
+                line: *line,
+                token: token.to_string(),
+            });
+        }
+
+        Ok(graph)
+    }
+}
+
+impl FromStr for Graph {
+    type Err = ParseGraphError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let keyword = s
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with("//"))
+            .and_then(|line| line.split(|c: char| c.is_whitespace() || c == '{').next())
+            .map(str::to_lowercase);
+
+        match keyword.as_deref() {
+            Some("graph" | "digraph" | "strict") => Self::from_dot(s),
+            _ => Self::from_edge_list(s),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// Name, number or quoted string.
+    Id(String),
+    Symbol(&'static str),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Id(id) => write!(f, "{id}"),
+            Self::Symbol(symbol) => write!(f, "{symbol}"),
+        }
+    }
+}
+
+/// Tokens of a DOT file, with their line.
+fn tokenize(text: &str) -> Result<Vec<(usize, Token)>, ParseGraphError> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    let mut line = 1;
+
+    while let Some(c) = chars.next() {
+        let symbol = match c {
+            '\n' => {
+                line += 1;
+                continue;
+            }
+            c if c.is_whitespace() => continue,
+            '#' => {
+                while chars.next_if(|c| *c != '\n').is_some() {}
+                continue;
+            }
+            '/' if chars.next_if_eq(&'/').is_some() => {
+                while chars.next_if(|c| *c != '\n').is_some() {}
+                continue;
+            }
+            '/' if chars.next_if_eq(&'*').is_some() => {
+                let mut last = ' ';
+                loop {
+                    let next = chars.next().ok_or(ParseGraphError::UnexpectedEnd)?;
+                    match next {
+                        '/' if last == '*' => break,
+                        '\n' => line += 1,
+                        _ => {}
+                    }
+                    last = next;
+                }
+                continue;
+            }
+            '{' => "{",
+            '}' => "}",
+            '[' => "[",
+            ']' => "]",
+            '=' => "=",
+            ';' => ";",
+            ',' => ",",
+            '-' if chars.next_if_eq(&'-').is_some() => "--",
+            '-' if chars.next_if_eq(&'>').is_some() => "->",
+            '"' => {
+                let mut id = String::new();
+                loop {
+                    match chars.next().ok_or(ParseGraphError::UnexpectedEnd)? {
+                        '"' => break,
+                        '\\' if chars.next_if_eq(&'"').is_some() => id.push('"'),
+                        '\n' => {
+                            line += 1;
+                            id.push('\n');
+                        }
+                        c => id.push(c),
+                    }
+                }
+                tokens.push((line, Token::Id(id)));
+                continue;
+            }
+            c if c.is_alphanumeric() || matches!(c, '_' | '.' | '-') => {
+                let mut id = c.to_string();
+                while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || matches!(c, '_' | '.'))
+                {
+                    id.push(c);
+                }
+                tokens.push((line, Token::Id(id)));
+                continue;
+            }
+            c => {
+                return Err(ParseGraphError::Unexpected {
+                    line,
+                    token: c.to_string(),
+                })
+            }
+        };
+
+        tokens.push((line, Token::Symbol(symbol)));
+    }
+
+    Ok(tokens)
+}
+
+struct DotParser {
+    tokens: Vec<(usize, Token)>,
+    position: usize,
+}
+
+impl DotParser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(_, token)| token)
+    }
+
+    fn next(&mut self) -> Result<(usize, Token), ParseGraphError> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .cloned()
+            .ok_or(ParseGraphError::UnexpectedEnd)?;
+        self.position += 1;
+
+        Ok(token)
+    }
+
+    fn unexpected(line: usize, token: Token) -> ParseGraphError {
+        ParseGraphError::Unexpected {
+            line,
+            token: token.to_string(),
+        }
+    }
+
+    fn id(&mut self) -> Result<(usize, String), ParseGraphError> {
+        match self.next()? {
+            (line, Token::Id(id)) => Ok((line, id)),
+            (line, token) => Err(Self::unexpected(line, token)),
+        }
+    }
+
+    fn symbol(&mut self, symbol: &str) -> Result<(), ParseGraphError> {
+        match self.next()? {
+            (_, Token::Symbol(found)) if found == symbol => Ok(()),
+            (line, token) => Err(Self::unexpected(line, token)),
+        }
+    }
+
+    /// `[strict] (graph | digraph) [name] { statements }`
+    fn graph(&mut self) -> Result<Graph, ParseGraphError> {
+        let (mut line, mut keyword) = self.id()?;
+        if keyword.eq_ignore_ascii_case("strict") {
+            (line, keyword) = self.id()?;
+        }
+        let directed = match keyword.to_lowercase().as_str() {
+            "graph" => false,
+            "digraph" => true,
+            _ => return Err(Self::unexpected(line, Token::Id(keyword))),
+        };
+        if let Some(Token::Id(_)) = self.peek() {
+            self.id()?;
+        }
+        self.symbol("{")?;
+
+        let mut graph = Graph::new();
+        loop {
+            match self.peek() {
+                Some(Token::Symbol("}")) => {
+                    self.next()?;
+                    break;
+                }
+                Some(Token::Symbol(";")) => {
+                    self.next()?;
+                }
+                Some(Token::Id(_)) => self.statement(&mut graph, directed)?,
+                Some(_) => {
+                    let (line, token) = self.next()?;
+                    return Err(Self::unexpected(line, token));
+                }
+                None => return Err(ParseGraphError::UnexpectedEnd),
+            }
+        }
+
+        if graph.is_empty() {
+            return Err(ParseGraphError::Empty);
+        }
+
+        Ok(graph)
+    }
+
+    /// Node, edge chain, default attributes or graph attribute.
+    fn statement(&mut self, graph: &mut Graph, directed: bool) -> Result<(), ParseGraphError> {
+        let (line, first) = self.id()?;
+
+        match self.peek() {
+            Some(Token::Symbol("[")) if matches!(first.as_str(), "graph" | "node" | "edge") => {
+                self.attributes()?;
+            }
+            Some(Token::Symbol("=")) => {
+                self.next()?;
+                self.id()?;
+            }
+            Some(Token::Symbol("--" | "->")) => {
+                let mut chain = vec![first];
+                while let Some(Token::Symbol(edge @ ("--" | "->"))) = self.peek() {
+                    let edge = *edge;
+                    let (line, _) = self.next()?;
+                    if (edge == "->") != directed {
+                        return Err(ParseGraphError::WrongEdge {
+                            line,
+                            edge: edge.to_string(),
+                        });
+                    }
+                    chain.push(self.id()?.1);
+                }
+
+                let attributes = self.attributes()?;
+                let value = attributes
+                    .iter()
+                    .find(|(key, _)| key == "weight")
+                    .or_else(|| attributes.iter().find(|(key, _)| key == "label"));
+                let weight = match value {
+                    Some((_, value)) => weight(line, value)?,
+                    None => 1.0,
+                };
+
+                for pair in chain.windows(2) {
+                    let from = graph.add_node(&pair[0]);
+                    let to = graph.add_node(&pair[1]);
+                    graph.add_edge(from, to, weight);
+                    if !directed {
+                        graph.add_edge(to, from, weight);
+                    }
+                }
+            }
+            _ => {
+                let node = graph.add_node(&first);
+                let attributes = self.attributes()?;
+
+                if let Some((_, pos)) = attributes.iter().find(|(key, _)| key == "pos") {
+                    let pos = pos.trim_end_matches('!');
+                    let (x, y) =
+                        pos.split_once(',')
+                            .ok_or_else(|| ParseGraphError::InvalidNumber {
+                                line,
+                                value: pos.to_string(),
+                            })?;
+                    graph.set_coordinates(node, number(line, x.trim())?, number(line, y.trim())?);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Attribute lists `[key=value, ...]`, none if the next token isn't `[`.
+    fn attributes(&mut self) -> Result<Vec<(String, String)>, ParseGraphError> {
+        let mut attributes = Vec::new();
+
+        while let Some(Token::Symbol("[")) = self.peek() {
+            self.next()?;
+
+            loop {
+                if let Some(Token::Symbol("]")) = self.peek() {
+                    self.next()?;
+                    break;
+                }
+
+                let (_, key) = self.id()?;
+                self.symbol("=")?;
+                let (_, value) = self.id()?;
+                attributes.push((key, value));
+
+                if let Some(Token::Symbol(";" | ",")) = self.peek() {
+                    self.next()?;
+                }
+            }
+        }
+
+        Ok(attributes)
+    }
+}
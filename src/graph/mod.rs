@@ -0,0 +1,437 @@
+//! Route finding on explicit graphs
+//!
+//! Road networks, city maps or the delivery robot's building are weighted
+//! directed graphs rather than grids. A [`Graph`] is read from an edge list
+//! or a DOT file, see [`file`](mod@file), and a [`RouteFinding`] walks it from a start
+//! node to a goal node, one edge at a time.
+//!
+//! [`RouteFinding`] is a [`Problem`], so the same frontiers as the maze
+//! solve it, for instance with the [`Planner`](crate::search::Planner)
+//! agent. The nodes can have coordinates, whose straight-line distance to
+//! the goal is the heuristic of the informed frontiers.
+//!
+//! ```
+//! use maze::{graph::Graph, prelude::*, search};
+//!
+//! let graph: Graph = "
+//!     A @ 0 0
+//!     B @ 3 0
+//!     C @ 3 4
+//!     A -- B 3
+//!     B -- C 4
+//!     A -> C 10
+//! "
+//! .parse()
+//! .unwrap();
+//!
+//! let (start, goal) = (graph.node("A").unwrap(), graph.node("C").unwrap());
+//! let route = graph.route(start, goal);
+//! let solution = search::solve::<_, UniformCostFinder>(&route).unwrap();
+//!
+//! assert_eq!(solution.cost, 7.0);
+//! ```
+
+use std::{collections::HashMap, error::Error, fmt};
+
+use crate::{environment::Environment, search::Problem};
+
+pub mod file;
+
+pub use file::ParseGraphError;
+
+/// Edge leaving a node.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Edge {
+    pub to: usize,
+    pub weight: f32,
+}
+
+/// Weighted directed graph, with named nodes.
+///
+/// Nodes are indexed in the order they were added. An undirected edge is a
+/// pair of edges, one each way.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Graph {
+    names: Vec<String>,
+    index: HashMap<String, usize>,
+    coordinates: Vec<Option<(f32, f32)>>,
+    edges: Vec<Vec<Edge>>,
+}
+
+impl Graph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index of the node named `name`, added if there is none yet.
+    pub fn add_node(&mut self, name: &str) -> usize {
+        if let Some(node) = self.index.get(name) {
+            return *node;
+        }
+
+        let node = self.names.len();
+        self.names.push(name.to_string());
+        self.index.insert(name.to_string(), node);
+        self.coordinates.push(None);
+        self.edges.push(Vec::new());
+
+        node
+    }
+
+    pub fn set_coordinates(&mut self, node: usize, x: f32, y: f32) {
+        self.coordinates[node] = Some((x, y));
+    }
+
+    pub fn add_edge(&mut self, from: usize, to: usize, weight: f32) {
+        self.edges[from].push(Edge { to, weight });
+    }
+
+    /// Index of the node named `name`.
+    pub fn node(&self, name: &str) -> Option<usize> {
+        self.index.get(name).copied()
+    }
+
+    pub fn name(&self, node: usize) -> &str {
+        &self.names[node]
+    }
+
+    /// Number of nodes.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Edges leaving `node`.
+    pub fn edges(&self, node: usize) -> &[Edge] {
+        &self.edges[node]
+    }
+
+    /// Weight of the lightest edge from `from` to `to`.
+    pub fn weight(&self, from: usize, to: usize) -> Option<f32> {
+        self.edges[from]
+            .iter()
+            .filter(|edge| edge.to == to)
+            .map(|edge| edge.weight)
+            .min_by(f32::total_cmp)
+    }
+
+    pub fn coordinates(&self, node: usize) -> Option<(f32, f32)> {
+        self.coordinates[node]
+    }
+
+    /// Straight-line distance between two nodes, `None` unless both have
+    /// coordinates.
+    pub fn distance(&self, from: usize, to: usize) -> Option<f32> {
+        let (x1, y1) = self.coordinates[from]?;
+        let (x2, y2) = self.coordinates[to]?;
+
+        Some((x1 - x2).hypot(y1 - y2))
+    }
+
+    /// Route finding from `start` to `goal` on this graph.
+    pub fn route(self, start: usize, goal: usize) -> RouteFinding {
+        RouteFinding::new(self, start, goal)
+    }
+}
+
+/// Errors of the route finding environment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteError {
+    /// There is no edge from the current node to the chosen one.
+    NoEdge { from: usize, to: usize },
+}
+
+impl fmt::Display for RouteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoEdge { from, to } => write!(f, "there is no edge from node {from} to {to}"),
+        }
+    }
+}
+
+impl Error for RouteError {}
+
+/// Stimuli produced by a [`RouteFinding`] update.
+///
+/// The reward is minus the weight of the edge taken.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RouteStimuli {
+    pub position: usize,
+    /// Weight of the edges taken since the start.
+    pub cost: f32,
+    pub reward: f32,
+    pub terminal: bool,
+}
+
+/// Travel on a [`Graph`] from a start node to a goal node.
+///
+/// The actions are the nodes to move to, along an edge from the current
+/// node.
+#[derive(Debug, Clone)]
+pub struct RouteFinding {
+    graph: Graph,
+    start: usize,
+    goal: usize,
+    /// Nodes visited since the start, the current one last.
+    route: Vec<usize>,
+    cost: f32,
+}
+
+impl RouteFinding {
+    /// # Panics
+    ///
+    /// Panics if `start` or `goal` isn't a node of the graph.
+    pub fn new(graph: Graph, start: usize, goal: usize) -> Self {
+        assert!(
+            start < graph.len() && goal < graph.len(),
+            "the start and the goal must be nodes of the graph"
+        );
+
+        Self {
+            graph,
+            start,
+            goal,
+            route: vec![start],
+            cost: 0.0,
+        }
+    }
+
+    pub fn graph(&self) -> &Graph {
+        &self.graph
+    }
+
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    pub fn goal(&self) -> usize {
+        self.goal
+    }
+
+    pub fn position(&self) -> usize {
+        self.route[self.route.len() - 1]
+    }
+
+    /// Nodes visited since the start, the current one last.
+    pub fn route(&self) -> &[usize] {
+        &self.route
+    }
+
+    /// Weight of the edges taken since the start.
+    pub fn cost(&self) -> f32 {
+        self.cost
+    }
+
+    fn stimuli(&self, reward: f32) -> RouteStimuli {
+        RouteStimuli {
+            position: self.position(),
+            cost: self.cost,
+            reward,
+            terminal: self.is_terminal(),
+        }
+    }
+}
+
+impl Environment for RouteFinding {
+    type Error = RouteError;
+
+    type Action = usize;
+
+    type Stimuli = RouteStimuli;
+
+    fn initial_stimuli(&self) -> Self::Stimuli {
+        self.stimuli(0.0)
+    }
+
+    fn update(&mut self, action: Self::Action) -> Result<Self::Stimuli, Self::Error> {
+        let from = self.position();
+        let weight = self
+            .graph
+            .weight(from, action)
+            .ok_or(RouteError::NoEdge { from, to: action })?;
+
+        self.route.push(action);
+        self.cost += weight;
+
+        Ok(self.stimuli(-weight))
+    }
+
+    /// Goes back to the start, the seed is ignored.
+    fn reset(&mut self, _seed: Option<u64>) -> Self::Stimuli {
+        self.route = vec![self.start];
+        self.cost = 0.0;
+
+        self.stimuli(0.0)
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.position() == self.goal
+    }
+}
+
+/// Reaching the goal from the current node. The heuristic is the
+/// straight-line distance to the goal, admissible as long as no edge is
+/// shorter than the distance between its nodes, and `0` without
+/// coordinates.
+impl Problem for RouteFinding {
+    type State = usize;
+
+    type Action = usize;
+
+    fn start(&self) -> Self::State {
+        self.position()
+    }
+
+    fn is_goal(&self, state: &Self::State) -> bool {
+        *state == self.goal
+    }
+
+    fn successors(&self, state: &Self::State) -> Vec<(Self::Action, Self::State, f32)> {
+        self.graph
+            .edges(*state)
+            .iter()
+            .map(|edge| (edge.to, edge.to, edge.weight))
+            .collect()
+    }
+
+    fn heuristic(&self, state: &Self::State) -> f32 {
+        self.graph.distance(*state, self.goal).unwrap_or(0.0)
+    }
+}
+
+impl fmt::Display for RouteFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let route: Vec<_> = self
+            .route
+            .iter()
+            .map(|node| self.graph.name(*node))
+            .collect();
+        writeln!(f, "{}", route.join(" -> "))?;
+
+        for edge in self.graph.edges(self.position()) {
+            writeln!(f, "  -> {} ({})", self.graph.name(edge.to), edge.weight)?;
+        }
+
+        write!(
+            f,
+            "at {}, goal {} | cost: {}{}",
+            self.graph.name(self.position()),
+            self.graph.name(self.goal),
+            self.cost,
+            if self.is_terminal() { ", arrived!" } else { "" }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        agent::Agent,
+        environment::Environment,
+        maze::agent::{
+            astar::AStarFinder, graph_based::BFSFinder, uniform_cost::UniformCostFinder,
+        },
+        render::FrameWriter,
+        search::{solve, Planner},
+        simulator::Simulator,
+    };
+
+    use super::{Graph, ParseGraphError, RouteError};
+
+    /// Part of the map of Romania, with the straight-line distances to
+    /// Bucharest as coordinates on the x axis.
+    const ROMANIA: &str = "
+        # name @ x y
+        Arad @ 366 0
+        Zerind @ 374 0
+        Oradea @ 380 0
+        Sibiu @ 253 0
+        Fagaras @ 176 0
+        Rimnicu @ 193 0
+        Pitesti @ 100 0
+        Timisoara @ 329 0
+        Bucharest @ 0 0
+
+        Arad -- Zerind 75
+        Zerind -- Oradea 71
+        Oradea -- Sibiu 151
+        Arad -- Sibiu 140
+        Arad -- Timisoara 118
+        Sibiu -- Fagaras 99
+        Sibiu -- Rimnicu 80
+        Rimnicu -- Pitesti 97
+        Fagaras -- Bucharest 211
+        Pitesti -- Bucharest 101
+    ";
+
+    #[test]
+    fn test_route_finding() {
+        let graph: Graph = ROMANIA.parse().unwrap();
+        let dot: Graph = "
+            graph romania {
+                Arad [pos=\"366,0\"]; Sibiu [pos=\"253,0!\"]
+                /* roads */
+                Arad -- Sibiu [weight=140]
+                Sibiu -- Fagaras -- Bucharest [label=\"99\"];
+            }
+        "
+        .parse()
+        .unwrap();
+        assert_eq!(graph.len(), 9);
+        assert_eq!(graph.weight(graph.node("Zerind").unwrap(), 0), Some(75.0));
+        assert_eq!(dot.len(), 4);
+        assert_eq!(dot.distance(0, 1), Some(113.0));
+        assert_eq!(dot.weight(2, 1), Some(99.0));
+        assert_eq!(
+            "a -- b -2".parse::<Graph>(),
+            Err(ParseGraphError::NegativeWeight {
+                line: 1,
+                weight: -2.0
+            })
+        );
+        assert_eq!(
+            "digraph {\n a -- b \n}".parse::<Graph>(),
+            Err(ParseGraphError::WrongEdge {
+                line: 2,
+                edge: "--".to_string()
+            })
+        );
+
+        let names = |graph: &Graph, nodes: &[usize]| -> Vec<String> {
+            nodes
+                .iter()
+                .map(|node| graph.name(*node).to_string())
+                .collect()
+        };
+        let route = graph.route(0, 8);
+
+        // BFS takes the fewest edges, the two others the lightest route
+        let bfs = solve::<_, BFSFinder>(&route).unwrap();
+        let ucs = solve::<_, UniformCostFinder>(&route).unwrap();
+        let astar = solve::<_, AStarFinder>(&route).unwrap();
+        let graph = route.graph();
+        assert_eq!(
+            names(graph, &bfs.states),
+            ["Arad", "Sibiu", "Fagaras", "Bucharest"]
+        );
+        assert_eq!(bfs.cost, 450.0);
+        assert_eq!(
+            names(graph, &astar.states),
+            ["Arad", "Sibiu", "Rimnicu", "Pitesti", "Bucharest"]
+        );
+        assert_eq!(ucs.states, astar.states);
+        assert_eq!(astar.cost, 418.0);
+        assert!(astar.expanded < ucs.expanded);
+
+        let planner = Planner::<_, AStarFinder>::new(&route);
+        let mut simulator = Simulator::new(planner, FrameWriter::new(Vec::new()), route.clone());
+        assert!(simulator.run_episodes(1).unwrap()[0].terminated);
+        assert_eq!(simulator.environment().cost(), 418.0);
+
+        let mut route = route;
+        assert_eq!(route.update(8), Err(RouteError::NoEdge { from: 0, to: 8 }));
+    }
+}
@@ -1,5 +1,12 @@
 pub mod agent;
+pub mod csp;
 pub mod environment;
+pub mod ffi;
+pub mod graph;
 pub mod maze;
+pub mod prelude;
+pub mod puzzle;
 pub mod render;
+pub mod search;
 pub mod simulator;
+pub mod sokoban;
@@ -4,51 +4,24 @@ use std::{cmp, collections::BinaryHeap};
 
 use ordered_float::OrderedFloat;
 
-use crate::maze::{coordinates::Coordinates, Path};
-
-use super::FrontierManager;
+use crate::search::{FrontierManager, NodeId, SearchArena};
 
 /// Heuristic + Cost
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct Heuristic {
     value: OrderedFloat<f32>,
-    path: Path,
+    node: NodeId,
 }
 
 #[derive(Debug, Clone)]
 pub struct AStarFinder {
     heap: BinaryHeap<Heuristic>,
-    goal: Coordinates,
-    current: Path,
-}
-
-impl AStarFinder {
-    /// Calculate the cost of the current path.
-    pub fn cost(&self) -> f32 {
-        let first = self.current.first();
-        let last = self.current.last();
-
-        first.euclidean_dist(&last)
-    }
 }
 
 impl FrontierManager for AStarFinder {
-    fn init(path: Path, goal: Coordinates) -> Self {
-        let mut heap = BinaryHeap::new();
-
-        let current = path.clone();
-
-        // initial cost is 0, use just the heuristic
-        let h = path.last().euclidean_dist(&goal);
-        heap.push(Heuristic {
-            value: (-1f32 * h).into(),
-            path,
-        });
-
+    fn init() -> Self {
         Self {
-            heap,
-            goal,
-            current,
+            heap: BinaryHeap::new(),
         }
     }
 
@@ -56,31 +29,32 @@ impl FrontierManager for AStarFinder {
         self.heap.is_empty()
     }
 
-    fn choose(&mut self, candidates: Vec<Path>) {
-        let cost = self.cost();
+    fn choose<S, A>(&mut self, candidates: Vec<NodeId>, arena: &SearchArena<S, A>) {
         for candidate in candidates {
-            let new_cost = cost + self.current.last().euclidean_dist(&candidate.last());
-            let new_h = new_cost + candidate.last().euclidean_dist(&self.goal);
+            let node = &arena[candidate];
+            let f = node.cost() + node.heuristic();
 
             let h = Heuristic {
-                value: (-1f32 * new_h).into(),
-                path: candidate,
+                value: (-f).into(),
+                node: candidate,
             };
 
             self.heap.push(h);
         }
     }
 
-    fn pop(&mut self) -> Path {
-        let path = self.heap.pop().unwrap().path;
-        self.current = path.clone();
-        path
+    fn pop(&mut self) -> Option<NodeId> {
+        self.heap.pop().map(|h| h.node)
+    }
+
+    fn frontier(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.heap.iter().map(|h| h.node)
     }
 }
 
 impl PartialOrd for Heuristic {
     fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
-        Some(self.value.cmp(&other.value))
+        Some(self.cmp(other))
     }
 }
 
@@ -1,49 +1,53 @@
-use crate::maze::{coordinates::Coordinates, Path};
+use std::collections::VecDeque;
 
-use super::FrontierManager;
+use crate::search::{FrontierManager, NodeId, SearchArena};
 
 #[derive(Debug, Clone)]
-pub struct BFSFinder(Vec<Path>);
+pub struct BFSFinder(VecDeque<NodeId>);
 
 impl FrontierManager for BFSFinder {
-    fn init(path: Path, _goal: Coordinates) -> Self {
-        Self(vec![path])
+    fn init() -> Self {
+        Self(VecDeque::new())
     }
 
     fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
 
-    fn choose(&mut self, candidates: Vec<Path>) {
-        for neighbor in candidates {
-            self.0.push(neighbor.clone());
-        }
+    fn choose<S, A>(&mut self, candidates: Vec<NodeId>, _arena: &SearchArena<S, A>) {
+        self.0.extend(candidates);
     }
 
-    fn pop(&mut self) -> Path {
-        self.0.remove(0)
+    fn pop(&mut self) -> Option<NodeId> {
+        self.0.pop_front()
+    }
+
+    fn frontier(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.0.iter().copied()
     }
 }
 
 #[derive(Debug, Clone)]
-pub struct DFSFinder(Vec<Path>);
+pub struct DFSFinder(Vec<NodeId>);
 
 impl FrontierManager for DFSFinder {
-    fn init(path: Path, _goal: Coordinates) -> Self {
-        Self(vec![path])
+    fn init() -> Self {
+        Self(Vec::new())
     }
 
     fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
 
-    fn choose(&mut self, candidates: Vec<Path>) {
-        for neighbor in candidates {
-            self.0.push(neighbor.clone());
-        }
+    fn choose<S, A>(&mut self, candidates: Vec<NodeId>, _arena: &SearchArena<S, A>) {
+        self.0.extend(candidates);
+    }
+
+    fn pop(&mut self) -> Option<NodeId> {
+        self.0.pop()
     }
 
-    fn pop(&mut self) -> Path {
-        self.0.pop().unwrap()
+    fn frontier(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.0.iter().copied()
     }
 }
@@ -4,57 +4,53 @@ use std::{cmp, collections::BinaryHeap};
 
 use ordered_float::OrderedFloat;
 
-use crate::maze::{coordinates::Coordinates, Path};
-
-use super::FrontierManager;
+use crate::search::{FrontierManager, NodeId, SearchArena};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct Heuristic {
     value: OrderedFloat<f32>,
-    path: Path,
+    node: NodeId,
 }
 
 #[derive(Debug, Clone)]
 pub struct GreedyFinder {
     heap: BinaryHeap<Heuristic>,
-    goal: Coordinates,
 }
 
 impl FrontierManager for GreedyFinder {
-    fn init(path: Path, goal: Coordinates) -> Self {
-        let mut heap = BinaryHeap::new();
-
-        heap.push(Heuristic {
-            value: (-1f32 * path.last().euclidean_dist(&goal)).into(),
-            path,
-        });
-
-        Self { heap, goal }
+    fn init() -> Self {
+        Self {
+            heap: BinaryHeap::new(),
+        }
     }
 
     fn is_empty(&self) -> bool {
         self.heap.is_empty()
     }
 
-    fn choose(&mut self, candidates: Vec<Path>) {
+    fn choose<S, A>(&mut self, candidates: Vec<NodeId>, arena: &SearchArena<S, A>) {
         for candidate in candidates {
             let h = Heuristic {
-                value: (-1f32 * candidate.last().euclidean_dist(&self.goal)).into(),
-                path: candidate,
+                value: (-arena[candidate].heuristic()).into(),
+                node: candidate,
             };
 
             self.heap.push(h);
         }
     }
 
-    fn pop(&mut self) -> Path {
-        self.heap.pop().unwrap().path
+    fn pop(&mut self) -> Option<NodeId> {
+        self.heap.pop().map(|h| h.node)
+    }
+
+    fn frontier(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.heap.iter().map(|h| h.node)
     }
 }
 
 impl PartialOrd for Heuristic {
     fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
-        Some(self.value.cmp(&other.value))
+        Some(self.cmp(other))
     }
 }
 
@@ -0,0 +1,47 @@
+//! Distances estimated by the informed finders
+//!
+//! A [`PathFinder`](super::PathFinder) is generic over the [`Distance`] to
+//! the goal, [`Euclidean`] by default, which orders the frontiers of
+//! [`AStarFinder`](super::astar::AStarFinder) and
+//! [`GreedyFinder`](super::greedy::GreedyFinder).
+
+use std::fmt::Debug;
+
+use crate::maze::coordinates::Coordinates;
+
+/// Estimate of the distance between two cells.
+pub trait Distance: Debug + Clone {
+    fn estimate(from: &Coordinates, to: &Coordinates) -> f32;
+}
+
+/// Straight line distance.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Euclidean;
+
+/// Moves along the four cardinal directions, admissible with
+/// [`Connectivity::Four`](crate::maze::Connectivity::Four).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Manhattan;
+
+/// Moves along the diagonals too, admissible with
+/// [`Connectivity::Eight`](crate::maze::Connectivity::Eight).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Chebyshev;
+
+impl Distance for Euclidean {
+    fn estimate(from: &Coordinates, to: &Coordinates) -> f32 {
+        from.euclidean_dist(to)
+    }
+}
+
+impl Distance for Manhattan {
+    fn estimate(from: &Coordinates, to: &Coordinates) -> f32 {
+        from.manhattan_dist(to) as f32
+    }
+}
+
+impl Distance for Chebyshev {
+    fn estimate(from: &Coordinates, to: &Coordinates) -> f32 {
+        from.chebyshev_dist(to) as f32
+    }
+}
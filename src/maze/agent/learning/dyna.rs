@@ -0,0 +1,75 @@
+//! Dyna-Q
+
+use std::collections::HashMap;
+
+use rand::Rng;
+use rand_chacha::ChaChaRng;
+
+use crate::maze::coordinates::{Coordinates, Direction};
+
+use super::{action_index, LearningConfig, QTable, Successor, Transition, UpdateRule};
+
+/// Q-learning that also replays transitions from a learned deterministic
+/// model, performing [`LearningConfig::planning_steps`] simulated updates after
+/// each real one.
+#[derive(Debug, Clone)]
+pub struct DynaQ {
+    model: HashMap<(Coordinates, usize), Transition>,
+    observed: Vec<(Coordinates, usize)>,
+}
+
+impl UpdateRule for DynaQ {
+    fn init(_config: &LearningConfig) -> Self {
+        Self {
+            model: HashMap::new(),
+            observed: Vec::new(),
+        }
+    }
+
+    fn bootstrap(&self, q: &QTable, successor: &Successor) -> f64 {
+        q.max(successor.state)
+    }
+
+    fn observe(
+        &mut self,
+        q: &mut QTable,
+        transition: &Transition,
+        config: &LearningConfig,
+        rng: &mut ChaChaRng,
+    ) {
+        let key = (transition.state, action_index(transition.action));
+        if self.model.insert(key, *transition).is_none() {
+            self.observed.push(key);
+        }
+
+        for _ in 0..config.planning_steps {
+            let key = self.observed[rng.gen_range(0..self.observed.len())];
+            let simulated = self.model[&key];
+
+            let target = if simulated.terminal {
+                simulated.reward
+            } else {
+                simulated.reward + config.discount * q.max(simulated.next_state)
+            };
+
+            q.update(
+                simulated.state,
+                simulated.action,
+                config.learning_rate,
+                target,
+            );
+        }
+    }
+}
+
+impl DynaQ {
+    /// Number of state-action pairs stored in the model.
+    pub fn model_size(&self) -> usize {
+        self.model.len()
+    }
+
+    /// Transition predicted by the model, if the pair was ever observed.
+    pub fn predict(&self, state: Coordinates, action: Direction) -> Option<&Transition> {
+        self.model.get(&(state, action_index(action)))
+    }
+}
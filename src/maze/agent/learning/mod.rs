@@ -0,0 +1,667 @@
+//! Reinforcement learning agents
+//!
+//! Temporal-difference learners that improve an action-value function
+//! `Q(s, a)` by walking a [`GridWorld`] for several episodes, see
+//! [`Learner::train`] or
+//! [`Simulator::run_episodes`](crate::simulator::Simulator::run_episodes).
+//! The agent stops at the end of each episode, and its knowledge survives
+//! [`Agent::reset`]. Each learner is a [`Learner`] parameterized by the
+//! [`UpdateRule`] that computes the bootstrap value of the next state:
+//!
+//! - [`QLearning`]: off-policy, bootstraps with `max_a Q(s', a)`.
+//! - [`Sarsa`]: on-policy, bootstraps with `Q(s', a')` for the next action
+//!   actually chosen.
+//! - [`ExpectedSarsa`]: bootstraps with the expectation of `Q(s', ·)` under
+//!   the exploration policy.
+//! - [`DynaQ`]: Q-learning plus planning updates replayed from a learned model.
+
+use std::{
+    collections::HashSet,
+    io::{self, Write},
+};
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaChaRng;
+
+use crate::{
+    agent::Agent,
+    environment::Environment,
+    maze::{
+        coordinates::{Coordinates, Direction},
+        grid_world::{GridStimuli, GridWorld},
+        Path,
+    },
+};
+
+pub mod dyna;
+pub mod qlearning;
+pub mod sarsa;
+
+pub use dyna::DynaQ;
+pub use qlearning::QLearning;
+pub use sarsa::{ExpectedSarsa, Sarsa};
+
+/// Actions available to a learner, indexed as in the [`QTable`].
+pub const ACTIONS: [Direction; 4] = [
+    Direction::North,
+    Direction::East,
+    Direction::South,
+    Direction::West,
+];
+
+/// Index of a cardinal direction in [`ACTIONS`].
+///
+/// # Panics
+/// Panics if the direction is not a cardinal one.
+pub fn action_index(direction: Direction) -> usize {
+    ACTIONS
+        .iter()
+        .position(|action| *action == direction)
+        .expect("learners only act in cardinal directions")
+}
+
+/// Action-value function of a square grid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QTable {
+    size: usize,
+    values: Vec<[f64; 4]>,
+}
+
+impl QTable {
+    /// Creates a table with every value set to zero.
+    pub fn new(size: usize) -> Self {
+        Self {
+            size,
+            values: vec![[0.0; 4]; size * size],
+        }
+    }
+
+    /// Action values of a given state.
+    pub fn get(&self, state: Coordinates) -> &[f64; 4] {
+        &self.values[state.x as usize * self.size + state.y as usize]
+    }
+
+    /// Mutable action values of a given state.
+    pub fn get_mut(&mut self, state: Coordinates) -> &mut [f64; 4] {
+        &mut self.values[state.x as usize * self.size + state.y as usize]
+    }
+
+    /// Highest action value of a state.
+    pub fn max(&self, state: Coordinates) -> f64 {
+        self.get(state)
+            .iter()
+            .copied()
+            .fold(f64::NEG_INFINITY, f64::max)
+    }
+
+    /// Action with the highest value in a state.
+    pub fn best_action(&self, state: Coordinates) -> Direction {
+        let values = self.get(state);
+        let best = (1..4).fold(0, |best, i| if values[i] > values[best] { i } else { best });
+
+        ACTIONS[best]
+    }
+
+    /// Moves `Q(s, a)` towards `target` by the learning rate `alpha`.
+    pub fn update(&mut self, state: Coordinates, action: Direction, alpha: f64, target: f64) {
+        let value = &mut self.get_mut(state)[action_index(action)];
+        *value += alpha * (target - *value);
+    }
+}
+
+/// Decay of an exploration parameter across episodes.
+///
+/// The value at episode `e` is `max(end, start * decay^e)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Schedule {
+    pub start: f64,
+    pub end: f64,
+    pub decay: f64,
+}
+
+impl Schedule {
+    /// A schedule that never changes.
+    pub fn constant(value: f64) -> Self {
+        Self {
+            start: value,
+            end: value,
+            decay: 1.0,
+        }
+    }
+
+    /// Value of the parameter at a given episode.
+    pub fn value(&self, episode: usize) -> f64 {
+        (self.start * self.decay.powi(episode as i32)).max(self.end)
+    }
+}
+
+/// How a learner balances exploration and exploitation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Exploration {
+    /// Takes a random action with probability ε, the greedy one otherwise.
+    EpsilonGreedy(Schedule),
+    /// Samples actions from a Boltzmann distribution with a given temperature.
+    Softmax(Schedule),
+}
+
+impl Exploration {
+    /// Exploration parameter (ε or temperature) at a given episode.
+    pub fn parameter(&self, episode: usize) -> f64 {
+        match self {
+            Self::EpsilonGreedy(schedule) | Self::Softmax(schedule) => schedule.value(episode),
+        }
+    }
+
+    /// Probability of choosing each action given its value.
+    pub fn probabilities(&self, values: &[f64; 4], episode: usize) -> [f64; 4] {
+        let mut probabilities = [0.0; 4];
+
+        match self {
+            Self::EpsilonGreedy(schedule) => {
+                let epsilon = schedule.value(episode).clamp(0.0, 1.0);
+                let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+                let ties = values.iter().filter(|value| **value == max).count() as f64;
+
+                for (probability, value) in probabilities.iter_mut().zip(values) {
+                    *probability = epsilon / 4.0;
+                    if *value == max {
+                        *probability += (1.0 - epsilon) / ties;
+                    }
+                }
+            }
+            Self::Softmax(schedule) => {
+                let temperature = schedule.value(episode).max(f64::EPSILON);
+                let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+                for (probability, value) in probabilities.iter_mut().zip(values) {
+                    *probability = ((value - max) / temperature).exp();
+                }
+
+                let total: f64 = probabilities.iter().sum();
+                probabilities.iter_mut().for_each(|p| *p /= total);
+            }
+        }
+
+        probabilities
+    }
+}
+
+/// Hyper-parameters shared by every [`Learner`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LearningConfig {
+    /// Learning rate α.
+    pub learning_rate: f64,
+    /// Discount factor γ.
+    pub discount: f64,
+    pub exploration: Exploration,
+    /// Episodes are cut after this many steps even if the goal wasn't reached.
+    pub max_steps: usize,
+    /// Simulated updates per real step, only used by [`DynaQ`].
+    pub planning_steps: usize,
+    /// Seed of the internal random generator, sourced from entropy if absent.
+    pub seed: Option<u64>,
+}
+
+impl Default for LearningConfig {
+    fn default() -> Self {
+        Self {
+            learning_rate: 0.1,
+            discount: 0.95,
+            exploration: Exploration::EpsilonGreedy(Schedule {
+                start: 1.0,
+                end: 0.05,
+                decay: 0.99,
+            }),
+            max_steps: 10_000,
+            planning_steps: 10,
+            seed: None,
+        }
+    }
+}
+
+/// A transition observed by a learner.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transition {
+    pub state: Coordinates,
+    pub action: Direction,
+    pub reward: f64,
+    pub next_state: Coordinates,
+    pub terminal: bool,
+}
+
+/// Next state and the decision the policy made in it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Successor {
+    pub state: Coordinates,
+    pub action: Direction,
+    pub probabilities: [f64; 4],
+}
+
+/// How a [`Learner`] computes its temporal-difference target.
+pub trait UpdateRule {
+    fn init(config: &LearningConfig) -> Self;
+
+    /// Estimated value of the successor state.
+    fn bootstrap(&self, q: &QTable, successor: &Successor) -> f64;
+
+    /// Called after every real transition, once `Q` was updated.
+    fn observe(
+        &mut self,
+        _q: &mut QTable,
+        _transition: &Transition,
+        _config: &LearningConfig,
+        _rng: &mut ChaChaRng,
+    ) {
+    }
+}
+
+/// Statistics of a finished episode.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EpisodeRecord {
+    pub episode: usize,
+    pub steps: usize,
+    pub total_reward: f64,
+    /// Exploration parameter (ε or temperature) used during the episode.
+    pub exploration: f64,
+    pub reached_goal: bool,
+}
+
+/// Learning curve of a [`Learner`], one record per episode.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LearningCurve(Vec<EpisodeRecord>);
+
+impl LearningCurve {
+    pub fn get(&self) -> &[EpisodeRecord] {
+        &self.0
+    }
+
+    pub fn push(&mut self, record: EpisodeRecord) {
+        self.0.push(record);
+    }
+
+    /// Writes the curve as CSV with a header row.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if writing fails.
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(
+            writer,
+            "episode,steps,total_reward,exploration,reached_goal"
+        )?;
+
+        for record in &self.0 {
+            writeln!(
+                writer,
+                "{},{},{},{},{}",
+                record.episode,
+                record.steps,
+                record.total_reward,
+                record.exploration,
+                record.reached_goal
+            )?;
+        }
+
+        writer.flush()
+    }
+}
+
+/// Temporal-difference learning agent.
+#[derive(Debug, Clone)]
+pub struct Learner<const N: usize, U: UpdateRule> {
+    pub config: LearningConfig,
+    pub curve: LearningCurve,
+    q: QTable,
+    rule: U,
+    rng: ChaChaRng,
+    state: Coordinates,
+    next_action: Option<Direction>,
+    episode: usize,
+    episode_reward: f64,
+    done: bool,
+}
+
+impl<const N: usize, U: UpdateRule> Learner<N, U> {
+    /// Replaces the default hyper-parameters.
+    pub fn with_config(mut self, config: LearningConfig) -> Self {
+        self.rng = match config.seed {
+            None => ChaChaRng::from_entropy(),
+            Some(seed) => ChaChaRng::seed_from_u64(seed),
+        };
+        self.rule = U::init(&config);
+        self.config = config;
+        self
+    }
+
+    /// Learned action values.
+    pub fn q_table(&self) -> &QTable {
+        &self.q
+    }
+
+    /// Number of finished episodes.
+    pub fn episode(&self) -> usize {
+        self.episode
+    }
+
+    /// Runs `episodes` episodes, starting the world over before each.
+    pub fn train(&mut self, world: &mut GridWorld<N>, episodes: usize) {
+        for _ in 0..episodes {
+            world.reset(None);
+            self.reset(world);
+
+            while !self.should_stop() {
+                self.act(world).expect("moves in a grid world never fail");
+            }
+        }
+    }
+
+    /// Follows the greedy policy from the initial position of the world.
+    ///
+    /// The walk stops at the target, at a wall or when a cell repeats.
+    pub fn greedy_path(&self, world: &GridWorld<N>) -> Path {
+        let mut current = world.maze.initial_position;
+        let mut path = Path::new(current);
+        let mut visited = HashSet::from([current]);
+
+        while current != world.maze.target_position {
+            let next = current.next(self.q.best_action(current));
+
+            if !world
+                .maze
+                .neighbors(current)
+                .into_iter()
+                .any(|dir| current.next(dir) == next)
+                || !visited.insert(next)
+            {
+                break;
+            }
+
+            path.push(next);
+            current = next;
+        }
+
+        path
+    }
+
+    fn choose(&mut self, state: Coordinates) -> (Direction, [f64; 4]) {
+        let probabilities = self
+            .config
+            .exploration
+            .probabilities(self.q.get(state), self.episode);
+
+        let mut sample = self.rng.gen::<f64>();
+        for (action, probability) in ACTIONS.iter().zip(probabilities) {
+            if sample < probability {
+                return (*action, probabilities);
+            }
+            sample -= probability;
+        }
+
+        (ACTIONS[3], probabilities)
+    }
+
+    fn finish_episode(&mut self, environment: &GridWorld<N>, reached_goal: bool) {
+        self.curve.push(EpisodeRecord {
+            episode: self.episode,
+            steps: environment.steps(),
+            total_reward: self.episode_reward,
+            exploration: self.config.exploration.parameter(self.episode),
+            reached_goal,
+        });
+
+        self.episode += 1;
+        self.done = true;
+    }
+}
+
+impl<const N: usize, U: UpdateRule> Agent for Learner<N, U> {
+    type Error = ();
+
+    type Action = Direction;
+
+    type Stimuli = GridStimuli;
+
+    type Environment = GridWorld<N>;
+
+    fn new(environment: &Self::Environment) -> Self {
+        let config = LearningConfig::default();
+
+        Self {
+            config,
+            curve: LearningCurve::default(),
+            q: QTable::new(N),
+            rule: U::init(&config),
+            rng: ChaChaRng::from_entropy(),
+            state: environment.initial_stimuli().position,
+            next_action: None,
+            episode: 0,
+            episode_reward: 0.0,
+            done: false,
+        }
+    }
+
+    fn act(&mut self, environment: &mut Self::Environment) -> Result<(), Self::Error> {
+        let state = self.state;
+        let action = match self.next_action.take() {
+            Some(action) => action,
+            None => self.choose(state).0,
+        };
+
+        let stimuli = environment.update(action)?;
+        self.episode_reward += stimuli.reward;
+
+        let (next_action, probabilities) = self.choose(stimuli.position);
+        let successor = Successor {
+            state: stimuli.position,
+            action: next_action,
+            probabilities,
+        };
+
+        let target = if stimuli.terminal {
+            stimuli.reward
+        } else {
+            stimuli.reward + self.config.discount * self.rule.bootstrap(&self.q, &successor)
+        };
+        self.q
+            .update(state, action, self.config.learning_rate, target);
+
+        let transition = Transition {
+            state,
+            action,
+            reward: stimuli.reward,
+            next_state: stimuli.position,
+            terminal: stimuli.terminal,
+        };
+        self.rule
+            .observe(&mut self.q, &transition, &self.config, &mut self.rng);
+
+        self.state = stimuli.position;
+        self.next_action = Some(next_action);
+
+        if stimuli.terminal || environment.steps() >= self.config.max_steps {
+            self.finish_episode(environment, stimuli.terminal);
+        }
+
+        Ok(())
+    }
+
+    /// At the end of every episode.
+    fn should_stop(&self) -> bool {
+        self.done
+    }
+
+    fn reset(&mut self, environment: &Self::Environment) {
+        self.state = environment.initial_stimuli().position;
+        self.next_action = None;
+        self.episode_reward = 0.0;
+        self.done = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        agent::Agent,
+        maze::{coordinates::Coordinates, grid_world::GridWorld, Maze},
+        render::Headless,
+        simulator::Simulator,
+    };
+
+    use super::{
+        DynaQ, EpisodeRecord, ExpectedSarsa, Exploration, Learner, LearningConfig, LearningCurve,
+        QLearning, Sarsa, Schedule, UpdateRule,
+    };
+
+    /// Learner trained on an open 5x5 grid, from the corner to the opposite
+    /// one.
+    fn trained<U: UpdateRule>(
+        config: LearningConfig,
+        episodes: usize,
+    ) -> (Learner<5, U>, GridWorld<5>) {
+        let maze = Maze::<5>::new(Coordinates::new(0, 0), Coordinates::new(4, 4), 0);
+        let mut world = GridWorld::new(maze);
+
+        let mut learner = Learner::<5, U>::new(&world).with_config(config);
+        learner.train(&mut world, episodes);
+
+        (learner, world)
+    }
+
+    #[test]
+    fn test_q_learning_reaches_goal() {
+        let maze = Maze::<5>::new(Coordinates::new(0, 0), Coordinates::new(4, 4), 0);
+        let mut world = GridWorld::new(maze);
+
+        let config = LearningConfig {
+            seed: Some(42),
+            ..Default::default()
+        };
+        let mut learner = Learner::<5, QLearning>::new(&world).with_config(config);
+        learner.train(&mut world, 300);
+
+        assert_eq!(learner.curve.get().len(), 300);
+        assert_eq!(
+            learner.greedy_path(&world).cells().last(),
+            Some(&Coordinates::new(4, 4))
+        );
+    }
+
+    #[test]
+    fn test_dyna_q_reaches_goal() {
+        let maze = Maze::<5>::new(Coordinates::new(0, 0), Coordinates::new(4, 4), 0);
+        let mut world = GridWorld::new(maze);
+
+        let config = LearningConfig {
+            seed: Some(7),
+            ..Default::default()
+        };
+        let mut learner = Learner::<5, DynaQ>::new(&world).with_config(config);
+        learner.train(&mut world, 50);
+
+        assert_eq!(
+            learner.greedy_path(&world).cells().last(),
+            Some(&Coordinates::new(4, 4))
+        );
+    }
+
+    #[test]
+    fn test_sarsa_variants_reach_goal() {
+        let config = LearningConfig {
+            seed: Some(42),
+            ..Default::default()
+        };
+
+        let (sarsa, world) = trained::<Sarsa>(config, 300);
+        assert_eq!(sarsa.greedy_path(&world).last(), Coordinates::new(4, 4));
+
+        let (expected, world) = trained::<ExpectedSarsa>(config, 300);
+        assert_eq!(expected.greedy_path(&world).last(), Coordinates::new(4, 4));
+    }
+
+    #[test]
+    fn test_exploration_schedules() {
+        let schedule = Schedule {
+            start: 1.0,
+            end: 0.1,
+            decay: 0.5,
+        };
+        assert_eq!(schedule.value(0), 1.0);
+        assert_eq!(schedule.value(2), 0.25);
+        assert_eq!(schedule.value(10), 0.1);
+        assert_eq!(Schedule::constant(0.3).value(100), 0.3);
+
+        let values = [0.0, 1.0, 0.5, 0.2];
+        let greedy = Exploration::EpsilonGreedy(schedule).probabilities(&values, 1);
+        assert_eq!(greedy, [0.125, 0.625, 0.125, 0.125]);
+
+        // A cold softmax picks the best action, a hot one any action
+        let cold = Exploration::Softmax(Schedule::constant(0.01)).probabilities(&values, 0);
+        assert!(cold[1] > 0.999);
+        let hot = Exploration::Softmax(Schedule::constant(1000.0)).probabilities(&values, 0);
+        assert!(hot
+            .iter()
+            .all(|probability| (probability - 0.25).abs() < 0.01));
+
+        let softmax = LearningConfig {
+            exploration: Exploration::Softmax(Schedule {
+                start: 1.0,
+                end: 0.01,
+                decay: 0.95,
+            }),
+            seed: Some(3),
+            ..Default::default()
+        };
+        let (learner, world) = trained::<QLearning>(softmax, 300);
+        assert_eq!(learner.greedy_path(&world).last(), Coordinates::new(4, 4));
+        assert_eq!(learner.curve.get()[100].exploration, 0.01);
+    }
+
+    #[test]
+    fn test_learning_curve_csv() {
+        let mut curve = LearningCurve::default();
+        for (episode, steps) in [(0, 12), (1, 8)] {
+            curve.push(EpisodeRecord {
+                episode,
+                steps,
+                total_reward: 1.0,
+                exploration: 0.5,
+                reached_goal: steps < 10,
+            });
+        }
+
+        let mut csv = Vec::new();
+        curve.write_csv(&mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "episode,steps,total_reward,exploration,reached_goal\n\
+             0,12,1,0.5,false\n\
+             1,8,1,0.5,true\n"
+        );
+    }
+
+    #[test]
+    fn test_simulated_episodes() {
+        let maze = Maze::<5>::new(Coordinates::new(0, 0), Coordinates::new(4, 4), 0);
+        let world = GridWorld::new(maze);
+
+        let config = LearningConfig {
+            seed: Some(42),
+            ..Default::default()
+        };
+        let learner = Learner::<5, QLearning>::new(&world).with_config(config);
+
+        let mut simulator = Simulator::new(learner, Headless, world);
+        let stats = simulator.run_episodes(300).unwrap();
+
+        assert_eq!(stats.len(), 300);
+        assert_eq!(simulator.agent().curve.get().len(), 300);
+        assert_eq!(
+            simulator
+                .agent()
+                .greedy_path(simulator.environment())
+                .cells()
+                .last(),
+            Some(&Coordinates::new(4, 4))
+        );
+    }
+}
@@ -0,0 +1,17 @@
+//! Q-learning
+
+use super::{LearningConfig, QTable, Successor, UpdateRule};
+
+/// Off-policy control: bootstraps with the best action of the next state.
+#[derive(Debug, Clone)]
+pub struct QLearning;
+
+impl UpdateRule for QLearning {
+    fn init(_config: &LearningConfig) -> Self {
+        Self
+    }
+
+    fn bootstrap(&self, q: &QTable, successor: &Successor) -> f64 {
+        q.max(successor.state)
+    }
+}
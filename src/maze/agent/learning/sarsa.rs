@@ -0,0 +1,35 @@
+//! SARSA and Expected SARSA
+
+use super::{action_index, LearningConfig, QTable, Successor, UpdateRule};
+
+/// On-policy control: bootstraps with the action the policy actually chose.
+#[derive(Debug, Clone)]
+pub struct Sarsa;
+
+impl UpdateRule for Sarsa {
+    fn init(_config: &LearningConfig) -> Self {
+        Self
+    }
+
+    fn bootstrap(&self, q: &QTable, successor: &Successor) -> f64 {
+        q.get(successor.state)[action_index(successor.action)]
+    }
+}
+
+/// Bootstraps with the expected value of the next state under the policy.
+#[derive(Debug, Clone)]
+pub struct ExpectedSarsa;
+
+impl UpdateRule for ExpectedSarsa {
+    fn init(_config: &LearningConfig) -> Self {
+        Self
+    }
+
+    fn bootstrap(&self, q: &QTable, successor: &Successor) -> f64 {
+        q.get(successor.state)
+            .iter()
+            .zip(successor.probabilities)
+            .map(|(value, probability)| value * probability)
+            .sum()
+    }
+}
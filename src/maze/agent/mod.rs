@@ -1,49 +1,72 @@
-use std::collections::HashSet;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    marker::PhantomData,
+};
 
 use rand::seq::SliceRandom;
 
-use crate::{agent::Agent, environment::Environment};
+use crate::{
+    agent::Agent,
+    environment::Environment,
+    search::{NodeId, Problem, Search, SearchArena},
+};
 
-use super::{coordinates::Coordinates, environment::MazeStimuli, Maze, Path};
+use self::heuristic::{Distance, Euclidean};
+
+use super::{
+    coordinates::{Coordinates, Direction},
+    environment::MazeStimuli,
+    Maze, Path,
+};
+
+pub use crate::search::FrontierManager;
 
 pub mod astar;
 pub mod graph_based;
 pub mod greedy;
+pub mod heuristic;
+pub mod learning;
+pub mod uniform_cost;
 
 /// Path finder solver
+///
+/// Runs a [`Search`] of the maze from its initial position, expanding one
+/// cell per action. The informed frontiers estimate how far cells are from
+/// the target with `D`.
 #[derive(Debug, Clone)]
-pub struct PathFinder<const N: usize, F: FrontierManager> {
+pub struct PathFinder<const N: usize, F: FrontierManager, D: Distance = Euclidean> {
     pub current_solution: Path,
     goal: Coordinates,
-    frontier: F,
-    visited: HashSet<Coordinates>,
+    search: Search<Maze<N>, F>,
+    /// Path to every expanded node, extending the path of its parent.
+    paths: HashMap<NodeId, Path>,
+    distance: PhantomData<D>,
 }
 
-/// How the [`PathFinder`] agent updates
-/// it's frontier.
-pub trait FrontierManager {
-    fn init(path: Path, goal: Coordinates) -> Self;
-
-    fn is_empty(&self) -> bool;
-
-    fn pop(&mut self) -> Path;
-
-    fn choose(&mut self, candidates: Vec<Path>);
-}
+impl<const N: usize, F: FrontierManager, D: Distance> PathFinder<N, F, D> {
+    /// Cells already expanded.
+    pub fn visited(&self) -> &HashSet<Coordinates> {
+        self.search.expanded()
+    }
 
-impl<const N: usize, F: FrontierManager> PathFinder<N, F> {
-    fn is_cycle(&self, path: &Path) -> bool {
-        let last = path.last();
+    /// Cells waiting to be expanded.
+    pub fn frontier(&self) -> Vec<Coordinates> {
+        self.search.frontier().map(|node| *node.state()).collect()
+    }
 
-        path.0[0..path.0.len() - 1].iter().any(|elem| *elem == last)
+    /// Cells reached so far, the path to each one being given by
+    /// [`SearchArena::states`].
+    pub fn arena(&self) -> &SearchArena<Coordinates, Direction> {
+        self.search.arena()
     }
 
-    fn is_visited(&self, path: &Path) -> bool {
-        self.visited.contains(&path.last())
+    /// Path to the goal, once found.
+    pub fn solution(&self) -> Option<&Path> {
+        (self.current_solution.last() == self.goal).then_some(&self.current_solution)
     }
 }
 
-impl<const N: usize, F: FrontierManager> Agent for PathFinder<N, F> {
+impl<const N: usize, F: FrontierManager, D: Distance> Agent for PathFinder<N, F, D> {
     type Error = ();
 
     type Action = Path;
@@ -53,68 +76,101 @@ impl<const N: usize, F: FrontierManager> Agent for PathFinder<N, F> {
     type Environment = Maze<N>;
 
     fn new(environment: &Self::Environment) -> Self {
-        let initial_stimuli = environment.initial_stimuli();
-        let initial_path = initial_stimuli.current_path.clone();
-
         Self {
-            current_solution: initial_stimuli.current_path,
-            goal: initial_stimuli.target_position,
-            frontier: F::init(initial_path, initial_stimuli.target_position),
-            visited: HashSet::new(),
+            current_solution: Path::new(environment.start()),
+            goal: environment.target_position,
+            search: Search::new(environment),
+            paths: HashMap::new(),
+            distance: PhantomData,
         }
     }
 
     fn act(&mut self, environment: &mut Self::Environment) -> Result<(), Self::Error> {
-        // Remove from frontier
-        let state = self.frontier.pop();
+        let goal = self.goal;
+        self.search
+            .step_with(environment, |cell| D::estimate(cell, &goal));
+
+        let Some(node) = self.search.last_expanded() else {
+            return Ok(());
+        };
+        let arena = self.search.arena();
+        let position = *arena[node].state();
+
+        // Act in the environment along the path to the expanded cell
+        self.current_solution = match arena[node].parent() {
+            Some(parent) => {
+                let mut path = self.paths[&parent].clone();
+                path.push(position);
+                path
+            }
+            None => Path::new(position),
+        };
+        self.paths.insert(node, self.current_solution.clone());
+        environment.update(self.current_solution.clone())?;
 
-        // Update current solution
-        self.current_solution = state.clone();
+        environment.record_expansion(position, self.search.frontier().map(|node| *node.state()));
 
-        // Act in the environment
-        let stimuli = environment.update(self.current_solution.clone())?;
+        Ok(())
+    }
 
-        // Visit neighbors of the last element of path
-        let mut viable_neighbors = Vec::new();
-        for action in stimuli.neighbors.iter() {
-            let neighbor = self.current_solution.walk(*action);
+    fn should_stop(&self) -> bool {
+        self.search.is_over()
+    }
+}
 
-            if !self.is_visited(&neighbor) && !self.is_cycle(&neighbor) {
-                viable_neighbors.push(neighbor);
-            }
-        }
+/// Moving from cell to cell towards the target position, along the moves
+/// given by [`Maze::neighbors`]. Steps cost their Euclidean length, which
+/// is also the estimate of the cost left.
+impl<const N: usize> Problem for Maze<N> {
+    type State = Coordinates;
 
-        self.visited.insert(self.current_solution.last());
+    type Action = Direction;
 
-        self.frontier.choose(viable_neighbors);
+    fn start(&self) -> Self::State {
+        self.initial_position
+    }
 
-        Ok(())
+    fn is_goal(&self, state: &Self::State) -> bool {
+        *state == self.target_position
     }
 
-    fn should_stop(&self) -> bool {
-        self.current_solution.last() == self.goal || self.frontier.is_empty()
+    fn successors(&self, state: &Self::State) -> Vec<(Self::Action, Self::State, f32)> {
+        self.neighbors(*state)
+            .into_iter()
+            .map(|direction| {
+                let next = state.next(direction);
+                (direction, next, state.euclidean_dist(&next))
+            })
+            .collect()
+    }
+
+    fn heuristic(&self, state: &Self::State) -> f32 {
+        Euclidean::estimate(state, &self.target_position)
     }
 }
 
 #[derive(Debug, Clone)]
-pub struct RandomFinder(Vec<Path>);
+pub struct RandomFinder(VecDeque<NodeId>);
 
 impl FrontierManager for RandomFinder {
-    fn init(path: Path, _goal: Coordinates) -> Self {
-        Self(vec![path])
+    fn init() -> Self {
+        Self(VecDeque::new())
     }
 
-    fn pop(&mut self) -> Path {
-        self.0.pop().unwrap()
+    fn pop(&mut self) -> Option<NodeId> {
+        self.0.pop_front()
     }
 
-    fn choose(&mut self, candidates: Vec<Path>) {
-        for path in candidates.choose_multiple(&mut rand::thread_rng(), candidates.len()) {
-            self.0.insert(0, path.clone());
-        }
+    fn choose<S, A>(&mut self, mut candidates: Vec<NodeId>, _arena: &SearchArena<S, A>) {
+        candidates.shuffle(&mut rand::thread_rng());
+        self.0.extend(candidates);
     }
 
     fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
+
+    fn frontier(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.0.iter().copied()
+    }
 }
@@ -0,0 +1,65 @@
+//! Uniform cost finder
+
+use std::{cmp, collections::BinaryHeap};
+
+use ordered_float::OrderedFloat;
+
+use crate::search::{FrontierManager, NodeId, SearchArena};
+
+/// Cost from the root
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Cost {
+    value: OrderedFloat<f32>,
+    node: NodeId,
+}
+
+/// Expands the cheapest node first, whatever the heuristic: the optimal
+/// path on weighted graphs, where BFS only finds the one with the fewest
+/// actions.
+#[derive(Debug, Clone)]
+pub struct UniformCostFinder {
+    heap: BinaryHeap<Cost>,
+}
+
+impl FrontierManager for UniformCostFinder {
+    fn init() -> Self {
+        Self {
+            heap: BinaryHeap::new(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    fn choose<S, A>(&mut self, candidates: Vec<NodeId>, arena: &SearchArena<S, A>) {
+        for candidate in candidates {
+            let cost = Cost {
+                value: (-arena[candidate].cost()).into(),
+                node: candidate,
+            };
+
+            self.heap.push(cost);
+        }
+    }
+
+    fn pop(&mut self) -> Option<NodeId> {
+        self.heap.pop().map(|cost| cost.node)
+    }
+
+    fn frontier(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.heap.iter().map(|cost| cost.node)
+    }
+}
+
+impl PartialOrd for Cost {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Cost {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.value.cmp(&other.value)
+    }
+}
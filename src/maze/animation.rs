@@ -0,0 +1,517 @@
+//! Animated exports of searches
+//!
+//! Renderers writing every frame of a run on a [`Maze`] to an animated GIF,
+//! an animated SVG or an [asciinema](https://asciinema.org) cast. They only
+//! need a writer, so they also run headlessly, e.g. in CI. Frontier cells
+//! are drawn when the maze keeps them, see [`Maze::with_frontier_trace`].
+
+use std::{borrow::Cow, fmt::Write as _, io::Write, time::Duration};
+
+use gif::{Encoder, Frame, Repeat};
+
+use super::{
+    coordinates::Coordinates,
+    render::{search_cells, Cell},
+    Ground, Maze,
+};
+
+use crate::render::Renderer;
+
+/// RGB colors of the exported animations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    pub background: [u8; 3],
+    pub wall: [u8; 3],
+    pub visited: [u8; 3],
+    pub frontier: [u8; 3],
+    pub path: [u8; 3],
+    pub start: [u8; 3],
+    pub goal: [u8; 3],
+    /// Doors, keys and items of puzzle mazes, only drawn by the still
+    /// images.
+    pub door: [u8; 3],
+    pub key: [u8; 3],
+    pub item: [u8; 3],
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            background: [255, 255, 255],
+            wall: [40, 40, 40],
+            visited: [160, 190, 230],
+            frontier: [240, 200, 60],
+            path: [60, 170, 90],
+            start: [40, 170, 200],
+            goal: [220, 60, 60],
+            door: [140, 90, 50],
+            key: [230, 170, 30],
+            item: [180, 90, 210],
+        }
+    }
+}
+
+impl Palette {
+    /// Colors in the order of [`Tile::index`].
+    fn colors(&self) -> [[u8; 3]; 7] {
+        [
+            self.background,
+            self.wall,
+            self.visited,
+            self.frontier,
+            self.path,
+            self.start,
+            self.goal,
+        ]
+    }
+
+    fn color(&self, tile: Tile) -> [u8; 3] {
+        self.colors()[tile.index()]
+    }
+}
+
+/// Look of the exported frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameStyle {
+    /// Side of a cell, in pixels.
+    pub cell_size: u16,
+    pub palette: Palette,
+    /// Time each frame is shown.
+    pub frame_duration: Duration,
+}
+
+impl Default for FrameStyle {
+    fn default() -> Self {
+        Self {
+            cell_size: 8,
+            palette: Palette::default(),
+            frame_duration: Duration::from_millis(100),
+        }
+    }
+}
+
+impl FrameStyle {
+    pub fn with_cell_size(mut self, cell_size: u16) -> Self {
+        self.cell_size = cell_size;
+        self
+    }
+
+    pub fn with_palette(mut self, palette: Palette) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    pub fn with_frame_duration(mut self, frame_duration: Duration) -> Self {
+        self.frame_duration = frame_duration;
+        self
+    }
+}
+
+/// What a cell of a frame shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tile {
+    Free,
+    Wall,
+    Search(Cell),
+}
+
+impl Tile {
+    fn index(&self) -> usize {
+        match self {
+            Tile::Free => 0,
+            Tile::Wall => 1,
+            Tile::Search(Cell::Visited) => 2,
+            Tile::Search(Cell::Frontier) => 3,
+            Tile::Search(Cell::Path) => 4,
+            Tile::Search(Cell::Start) => 5,
+            Tile::Search(Cell::Goal) => 6,
+        }
+    }
+}
+
+/// Tiles of every cell of the maze, indexed as the maze.
+fn tiles<const N: usize>(environment: &Maze<N>) -> Vec<Vec<Tile>> {
+    search_cells(environment)
+        .iter()
+        .enumerate()
+        .map(|(x, row)| {
+            row.iter()
+                .enumerate()
+                .map(|(y, cell)| match cell {
+                    Some(cell) => Tile::Search(*cell),
+                    None => match environment.get(&Coordinates::new(x as i64, y as i64)) {
+                        Some(Ground::Blocked) => Tile::Wall,
+                        _ => Tile::Free,
+                    },
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Writes the frames to an animated GIF, looping forever.
+///
+/// Rows of the maze are drawn top to bottom, as by the terminal renderers.
+pub struct GifRenderer<W: Write> {
+    writer: Option<W>,
+    encoder: Option<Encoder<W>>,
+    style: FrameStyle,
+}
+
+impl<W: Write> GifRenderer<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: Some(writer),
+            encoder: None,
+            style: FrameStyle::default(),
+        }
+    }
+
+    pub fn with_style(mut self, style: FrameStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Finishes the animation and gives the writer back.
+    pub fn into_inner(mut self) -> W {
+        self.finish();
+        self.writer
+            .take()
+            .expect("The writer is owned by the renderer")
+    }
+
+    fn finish(&mut self) {
+        if let Some(encoder) = self.encoder.take() {
+            self.writer = Some(encoder.into_inner().expect("Failed to finish the GIF"));
+        }
+    }
+
+    fn encoder(&mut self, side: u16) -> &mut Encoder<W> {
+        let palette = self.style.palette.colors().concat();
+        let writer = &mut self.writer;
+
+        self.encoder.get_or_insert_with(|| {
+            let writer = writer.take().expect("The writer is owned by the renderer");
+            let mut encoder =
+                Encoder::new(writer, side, side, &palette).expect("Failed to start the GIF");
+            encoder
+                .set_repeat(Repeat::Infinite)
+                .expect("Failed to loop the GIF");
+            encoder
+        })
+    }
+}
+
+impl<const N: usize, W: Write> Renderer<Maze<N>> for GifRenderer<W> {
+    fn setup(&mut self) {}
+
+    fn render(&mut self, environment: &Maze<N>) {
+        let cell = self.style.cell_size as usize;
+        let side = N * cell;
+        let mut pixels = vec![0; side * side];
+
+        for (x, row) in tiles(environment).iter().enumerate() {
+            for (y, tile) in row.iter().enumerate() {
+                for line in x * cell..(x + 1) * cell {
+                    pixels[line * side + y * cell..line * side + (y + 1) * cell]
+                        .fill(tile.index() as u8);
+                }
+            }
+        }
+
+        let frame = Frame {
+            width: side as u16,
+            height: side as u16,
+            // In hundredths of a second
+            delay: (self.style.frame_duration.as_millis() / 10) as u16,
+            buffer: Cow::Owned(pixels),
+            ..Frame::default()
+        };
+
+        self.encoder(side as u16)
+            .write_frame(&frame)
+            .expect("Failed to write GIF frame");
+    }
+
+    fn teardown(&mut self) {
+        self.finish();
+    }
+}
+
+/// Writes the frames to an animated SVG, looping forever.
+///
+/// Every frame is kept in memory until the teardown, since the length of the
+/// animation must be known up front.
+pub struct SvgRenderer<W: Write> {
+    writer: W,
+    style: FrameStyle,
+    side: usize,
+    /// Walls, drawn once below every frame.
+    walls: String,
+    frames: Vec<String>,
+}
+
+impl<W: Write> SvgRenderer<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            style: FrameStyle::default(),
+            side: 0,
+            walls: String::new(),
+            frames: Vec::new(),
+        }
+    }
+
+    pub fn with_style(mut self, style: FrameStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Gives the writer back.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn rect(&self, x: usize, y: usize, tile: Tile) -> String {
+        let size = self.style.cell_size as usize;
+        let [r, g, b] = self.style.palette.color(tile);
+
+        format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{size}\" height=\"{size}\" fill=\"#{r:02x}{g:02x}{b:02x}\"/>",
+            y * size,
+            x * size
+        )
+    }
+
+    fn document(&self) -> String {
+        let size = self.side * self.style.cell_size as usize;
+        let frames = self.frames.len();
+        let duration = self.style.frame_duration.as_secs_f64() * frames as f64;
+        let [r, g, b] = self.style.palette.background;
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{size}\" height=\"{size}\" viewBox=\"0 0 {size} {size}\">\n"
+        );
+        let _ = writeln!(
+            svg,
+            "<rect width=\"{size}\" height=\"{size}\" fill=\"#{r:02x}{g:02x}{b:02x}\"/>"
+        );
+        let _ = writeln!(svg, "<g>{}</g>", self.walls);
+
+        for (index, frame) in self.frames.iter().enumerate() {
+            let start = index as f64 / frames as f64;
+            let end = (index + 1) as f64 / frames as f64;
+
+            // Each frame is only visible during its own slice of the loop
+            let (values, key_times) = if index == 0 {
+                ("visible;hidden".to_string(), format!("0;{end}"))
+            } else {
+                (
+                    "hidden;visible;hidden".to_string(),
+                    format!("0;{start};{end}"),
+                )
+            };
+
+            let _ = writeln!(
+                svg,
+                "<g visibility=\"hidden\">{frame}<animate attributeName=\"visibility\" values=\"{values}\" keyTimes=\"{key_times}\" dur=\"{duration}s\" calcMode=\"discrete\" repeatCount=\"indefinite\"/></g>"
+            );
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+impl<const N: usize, W: Write> Renderer<Maze<N>> for SvgRenderer<W> {
+    fn setup(&mut self) {
+        self.frames.clear();
+        self.walls.clear();
+    }
+
+    fn render(&mut self, environment: &Maze<N>) {
+        let tiles = tiles(environment);
+        let mut frame = String::new();
+
+        self.side = N;
+        self.walls.clear();
+
+        for (x, row) in tiles.iter().enumerate() {
+            for (y, tile) in row.iter().enumerate() {
+                match tile {
+                    Tile::Free => {}
+                    Tile::Wall => {
+                        let rect = self.rect(x, y, *tile);
+                        self.walls.push_str(&rect);
+                    }
+                    Tile::Search(_) => frame.push_str(&self.rect(x, y, *tile)),
+                }
+            }
+        }
+
+        self.frames.push(frame);
+    }
+
+    fn teardown(&mut self) {
+        let document = self.document();
+
+        self.writer
+            .write_all(document.as_bytes())
+            .expect("Failed to write SVG");
+        self.writer.flush().expect("Failed to flush SVG");
+    }
+}
+
+/// Writes the frames to an asciinema cast (asciicast v2), each frame shown
+/// for the frame duration of the style. The cell size is ignored.
+pub struct CastRenderer<W: Write> {
+    writer: W,
+    style: FrameStyle,
+    frames: usize,
+}
+
+impl<W: Write> CastRenderer<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            style: FrameStyle::default(),
+            frames: 0,
+        }
+    }
+
+    pub fn with_style(mut self, style: FrameStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Gives the writer back.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn screen<const N: usize>(&self, environment: &Maze<N>) -> String {
+        let trace = environment.trace();
+
+        // Clear the screen and go home
+        let mut screen = String::from("\x1b[2J\x1b[H");
+
+        for row in tiles(environment) {
+            for tile in row {
+                let [r, g, b] = self.style.palette.color(tile);
+                let _ = write!(screen, "\x1b[38;2;{r};{g};{b}m██");
+            }
+            screen.push_str("\x1b[0m\r\n");
+        }
+
+        let _ = write!(
+            screen,
+            "step: {} | expanded: {} | frontier: {} | path length: {}\r\n",
+            trace.steps,
+            trace.visited.len(),
+            trace.frontier.len(),
+            environment.current_path.len() - 1,
+        );
+
+        screen
+    }
+}
+
+/// Escapes a string as a JSON string literal.
+fn json_string(value: &str) -> String {
+    let mut json = String::from("\"");
+
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            c if c.is_control() => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+
+    json.push('"');
+    json
+}
+
+impl<const N: usize, W: Write> Renderer<Maze<N>> for CastRenderer<W> {
+    fn setup(&mut self) {
+        self.frames = 0;
+    }
+
+    fn render(&mut self, environment: &Maze<N>) {
+        if self.frames == 0 {
+            writeln!(
+                self.writer,
+                "{{\"version\": 2, \"width\": {}, \"height\": {}}}",
+                2 * N,
+                N + 1
+            )
+            .expect("Failed to write cast header");
+        }
+
+        let time = self.style.frame_duration.as_secs_f64() * self.frames as f64;
+        let screen = json_string(&self.screen(environment));
+
+        writeln!(self.writer, "[{time:.3}, \"o\", {screen}]").expect("Failed to write cast event");
+        self.frames += 1;
+    }
+
+    fn teardown(&mut self) {
+        self.writer.flush().expect("Failed to flush cast");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        agent::Agent,
+        maze::{
+            agent::{astar::AStarFinder, PathFinder},
+            coordinates::Coordinates,
+            Maze,
+        },
+        render::{Headless, Renderer},
+        simulator::Simulator,
+    };
+
+    use super::{CastRenderer, GifRenderer, SvgRenderer};
+
+    #[test]
+    fn test_export_search() {
+        let maze = Maze::<8>::with_seed(Coordinates::new(0, 0), Coordinates::new(7, 7), 20, 3);
+        let finder = PathFinder::<8, AStarFinder>::new(&maze);
+
+        let mut simulator = Simulator::new(finder, Headless, maze);
+        simulator.simulate().unwrap();
+
+        let mut gif = GifRenderer::new(Vec::new());
+        let mut svg = SvgRenderer::new(Vec::new());
+        let mut cast = CastRenderer::new(Vec::new());
+
+        let environment = simulator.environment();
+        for renderer in [&mut gif as &mut dyn Renderer<Maze<8>>, &mut svg, &mut cast] {
+            renderer.setup();
+            renderer.render(environment);
+            renderer.render(environment);
+            renderer.teardown();
+        }
+
+        let gif = gif.into_inner();
+        assert!(gif.starts_with(b"GIF89a"));
+        assert_eq!(gif.last(), Some(&0x3b));
+
+        let svg = String::from_utf8(svg.into_inner()).unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("<animate").count(), 2);
+
+        let cast = String::from_utf8(cast.into_inner()).unwrap();
+        let lines: Vec<_> = cast.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].contains("\"version\": 2"));
+        assert!(lines[2].starts_with("[0.100, \"o\", "));
+    }
+}
@@ -0,0 +1,298 @@
+//! Any-angle paths
+//!
+//! A [`Path`] found by a [`PathFinder`](super::agent::PathFinder) moves from
+//! cell to adjacent cell, a staircase as soon as it isn't aligned with the
+//! grid. The paths here join the centers of cells with straight segments
+//! instead, allowed when the segment only crosses free cells:
+//!
+//! - [`Path::simplify`] drops the cells in the middle of straight runs;
+//! - [`Path::smooth`] pulls the path tight around the walls;
+//! - [`ThetaStar`] and its lazy variant plan any-angle paths directly, which
+//!   are usually shorter than smoothed grid paths.
+//!
+//! Segments cross the cells given by [`supercover`]. A segment running
+//! exactly through the corner of a cell crosses both cells beside the corner,
+//! so paths never squeeze between two diagonal walls.
+
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, HashMap, HashSet},
+};
+
+use ordered_float::OrderedFloat;
+
+use super::{coordinates::Coordinates, Maze, Path};
+
+/// Cells crossed by the segment between the centers of two cells, in order,
+/// both ends included.
+pub fn supercover(from: Coordinates, to: Coordinates) -> Vec<Coordinates> {
+    let (nx, ny) = ((to.x - from.x).abs(), (to.y - from.y).abs());
+    let (sx, sy) = ((to.x - from.x).signum(), (to.y - from.y).signum());
+    let (mut x, mut y) = (from.x, from.y);
+    let (mut ix, mut iy) = (0, 0);
+    let mut cells = vec![from];
+
+    while ix < nx || iy < ny {
+        // Compares where the segment next crosses a vertical and a
+        // horizontal cell border
+        match ((1 + 2 * ix) * ny).cmp(&((1 + 2 * iy) * nx)) {
+            Ordering::Equal => {
+                cells.push(Coordinates::new(x + sx, y));
+                cells.push(Coordinates::new(x, y + sy));
+                x += sx;
+                y += sy;
+                ix += 1;
+                iy += 1;
+            }
+            Ordering::Less => {
+                x += sx;
+                ix += 1;
+            }
+            Ordering::Greater => {
+                y += sy;
+                iy += 1;
+            }
+        }
+        cells.push(Coordinates::new(x, y));
+    }
+
+    cells
+}
+
+impl<const N: usize> Maze<N> {
+    /// Whether the segment between the centers of two cells only crosses
+    /// free cells.
+    pub fn line_of_sight(&self, from: Coordinates, to: Coordinates) -> bool {
+        supercover(from, to)
+            .iter()
+            .all(|cell| self.is_walkable(cell))
+    }
+}
+
+/// Corners of a path joined by straight segments, never empty.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Waypoints(Vec<Coordinates>);
+
+impl Waypoints {
+    /// Waypoints through the given points, or `None` if there are none.
+    pub fn new(points: Vec<Coordinates>) -> Option<Self> {
+        (!points.is_empty()).then_some(Self(points))
+    }
+
+    pub fn points(&self) -> &[Coordinates] {
+        &self.0
+    }
+
+    /// Euclidean length of the segments.
+    pub fn length(&self) -> f32 {
+        self.0
+            .windows(2)
+            .map(|segment| segment[0].euclidean_dist(&segment[1]))
+            .sum()
+    }
+
+    /// Cells crossed by the segments, as a grid path.
+    pub fn grid_path(&self) -> Path {
+        let mut cells = vec![self.0[0]];
+
+        for segment in self.0.windows(2) {
+            cells.extend(supercover(segment[0], segment[1]).into_iter().skip(1));
+        }
+
+        Path::from_cells(cells).expect("waypoints are never empty")
+    }
+}
+
+impl Path {
+    /// Keeps the ends of the path and the cells where it turns.
+    pub fn simplify(&self) -> Waypoints {
+        let cells = self.cells();
+        let step = |from: &Coordinates, to: &Coordinates| (to.x - from.x, to.y - from.y);
+
+        let mut points = vec![cells[0]];
+        points.extend(
+            cells
+                .windows(3)
+                .filter(|run| step(&run[0], &run[1]) != step(&run[1], &run[2]))
+                .map(|run| run[1]),
+        );
+        if cells.len() > 1 {
+            points.push(self.last());
+        }
+
+        Waypoints(points)
+    }
+
+    /// Skips every cell of the path that can be seen from the previous
+    /// waypoint, going straight to the next ones.
+    pub fn smooth<const N: usize>(&self, maze: &Maze<N>) -> Waypoints {
+        let cells = self.cells();
+        let mut points = vec![cells[0]];
+
+        for index in 2..cells.len() {
+            let anchor = *points.last().unwrap();
+            if !maze.line_of_sight(anchor, cells[index]) {
+                points.push(cells[index - 1]);
+            }
+        }
+        if cells.len() > 1 {
+            points.push(self.last());
+        }
+
+        Waypoints(points)
+    }
+}
+
+/// Theta* any-angle planner.
+///
+/// A* over the cells of the maze, where a cell takes the parent of the cell
+/// expanding it as its own parent whenever it can see it. The lazy variant
+/// assumes the line of sight when a cell is reached and only checks it when
+/// the cell is expanded, trading slightly longer paths for fewer checks.
+///
+/// Expansions are recorded in the [`Maze::trace`], as the path finders do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ThetaStar {
+    lazy: bool,
+}
+
+impl ThetaStar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lazy Theta*.
+    pub fn lazy() -> Self {
+        Self { lazy: true }
+    }
+
+    /// Plans a path from the initial to the target position of the maze,
+    /// moving to the cells given by [`Maze::neighbors`].
+    pub fn plan<const N: usize>(&self, maze: &mut Maze<N>) -> Option<Waypoints> {
+        let (start, goal) = (maze.initial_position, maze.target_position);
+        let heuristic = |cell: Coordinates| OrderedFloat(cell.euclidean_dist(&goal));
+
+        let mut cost = HashMap::from([(start, 0f32)]);
+        let mut parent = HashMap::from([(start, start)]);
+        let mut closed = HashSet::new();
+        let mut open = BinaryHeap::from([Reverse((heuristic(start), start))]);
+
+        while let Some(Reverse((_, cell))) = open.pop() {
+            if !closed.insert(cell) {
+                continue;
+            }
+
+            if self.lazy && !maze.line_of_sight(parent[&cell], cell) {
+                // Falls back to the best expanded neighbor, one exists since
+                // one of them reached the cell
+                let (from, through) = maze
+                    .neighbors(cell)
+                    .into_iter()
+                    .map(|direction| cell.next(direction))
+                    .filter(|neighbor| closed.contains(neighbor))
+                    .map(|neighbor| (neighbor, cost[&neighbor] + neighbor.euclidean_dist(&cell)))
+                    .min_by_key(|(_, through)| OrderedFloat(*through))
+                    .expect("an expanded neighbor reached the cell");
+
+                parent.insert(cell, from);
+                cost.insert(cell, through);
+            }
+
+            maze.record_expansion(cell, open.iter().map(|Reverse((_, cell))| *cell));
+
+            if cell == goal {
+                let mut points = vec![goal];
+                while *points.last().unwrap() != start {
+                    points.push(parent[points.last().unwrap()]);
+                }
+                points.reverse();
+
+                return Some(Waypoints(points));
+            }
+
+            for direction in maze.neighbors(cell) {
+                let neighbor = cell.next(direction);
+                if closed.contains(&neighbor) {
+                    continue;
+                }
+
+                let grandparent = parent[&cell];
+                let from = if self.lazy || maze.line_of_sight(grandparent, neighbor) {
+                    grandparent
+                } else {
+                    cell
+                };
+                let through = cost[&from] + from.euclidean_dist(&neighbor);
+
+                if cost.get(&neighbor).is_none_or(|known| through < *known) {
+                    cost.insert(neighbor, through);
+                    parent.insert(neighbor, from);
+                    open.push(Reverse((
+                        OrderedFloat(through) + heuristic(neighbor),
+                        neighbor,
+                    )));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::maze::{coordinates::Coordinates, Ground, Maze, Path};
+
+    use super::{supercover, ThetaStar, Waypoints};
+
+    #[test]
+    fn test_any_angle_paths() {
+        let mut cells = [[Ground::Free; 8]; 8];
+        for row in cells.iter_mut().take(6) {
+            row[3] = Ground::Blocked;
+        }
+        let mut maze = Maze::<8>::from_cells(Coordinates::new(0, 0), Coordinates::new(0, 7), cells);
+
+        assert_eq!(
+            supercover(Coordinates::new(0, 0), Coordinates::new(1, 1)),
+            [(0, 0), (1, 0), (0, 1), (1, 1)].map(|(x, y)| Coordinates::new(x, y))
+        );
+        assert!(maze.line_of_sight(Coordinates::new(6, 0), Coordinates::new(6, 7)));
+        assert!(!maze.line_of_sight(Coordinates::new(0, 0), Coordinates::new(6, 7)));
+
+        // Down the first column, across the last rows and up the last column
+        let staircase: Vec<_> = (0..7)
+            .map(|x| Coordinates::new(x, 0))
+            .chain((1..8).map(|y| Coordinates::new(6, y)))
+            .chain((0..6).rev().map(|x| Coordinates::new(x, 7)))
+            .collect();
+        let path = Path::from_cells(staircase).unwrap();
+
+        let simple = path.simplify();
+        assert_eq!(simple.points().len(), 4);
+        assert_eq!(simple.length(), path.cost());
+
+        let smooth = path.smooth(&maze);
+        assert!(smooth.length() < path.cost());
+        assert!(smooth
+            .points()
+            .windows(2)
+            .all(|segment| maze.line_of_sight(segment[0], segment[1])));
+
+        let theta = ThetaStar::new().plan(&mut maze).unwrap();
+        let lazy = ThetaStar::lazy().plan(&mut maze).unwrap();
+        assert!(theta.length() <= smooth.length() + 1e-4);
+        assert!(theta.length() <= lazy.length() + 1e-4);
+
+        let grid = lazy.grid_path();
+        assert_eq!(grid.first(), Coordinates::new(0, 0));
+        assert_eq!(grid.last(), Coordinates::new(0, 7));
+        assert!(grid
+            .iter()
+            .all(|cell| maze.get(&cell) == Some(Ground::Free)));
+
+        assert!(Waypoints::new(vec![]).is_none());
+        let single = Waypoints::new(vec![Coordinates::new(2, 3)]).unwrap();
+        assert_eq!(single.grid_path().cells(), [Coordinates::new(2, 3)]);
+    }
+}
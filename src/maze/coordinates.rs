@@ -32,16 +32,43 @@ impl Coordinates {
         }
     }
 
+    /// Gets the [`Direction`] leading to an adjacent coordinate, if any.
+    pub fn direction_to(&self, other: &Self) -> Option<Direction> {
+        [
+            Direction::North,
+            Direction::Northeast,
+            Direction::Northwest,
+            Direction::East,
+            Direction::West,
+            Direction::South,
+            Direction::Southeast,
+            Direction::Southwest,
+        ]
+        .into_iter()
+        .find(|dir| self.next(*dir) == *other)
+    }
+
+    /// Calculates the manhattan distance between two points.
+    pub fn manhattan_dist(&self, other: &Self) -> i64 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
     /// Calculates the euclidean distance between two points.
     pub fn euclidean_dist(&self, other: &Self) -> f32 {
         f32::sqrt((self.x - other.x).pow(2) as f32 + (self.y - other.y).pow(2) as f32)
     }
+
+    /// Calculates the chebyshev distance between two points, the number of
+    /// king moves between them.
+    pub fn chebyshev_dist(&self, other: &Self) -> i64 {
+        (self.x - other.x).abs().max((self.y - other.y).abs())
+    }
 }
 
 /// The four cardinal directions
 ///
 /// Also defines convenience functions to work with them.
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum Direction {
     North,
     Northeast,
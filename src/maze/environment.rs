@@ -1,14 +1,7 @@
 //! Maze Environment
 
-use std::{
-    io::{stdout, Write},
-    thread, time,
-};
-
-use crossterm::{cursor, terminal, QueueableCommand};
-
 use crate::{
-    environment::Environment,
+    environment::{Environment, Resolution, SharedEnvironment},
     maze::{
         coordinates::{Coordinates, Direction},
         Maze,
@@ -18,11 +11,30 @@ use crate::{
 use super::Path;
 
 /// Stimuli produced by a [`Maze`] update.
-#[derive(Debug, Clone, Eq, PartialEq)]
+///
+/// The reward is `1.0` when the current path reaches the target and `0.0`
+/// otherwise.
+#[derive(Debug, Clone, PartialEq)]
 pub struct MazeStimuli {
     pub current_path: Path,
     pub target_position: Coordinates,
     pub neighbors: Vec<Direction>,
+    pub reward: f64,
+    pub terminal: bool,
+}
+
+impl<const N: usize> Maze<N> {
+    fn stimuli(&self) -> MazeStimuli {
+        let terminal = self.current_path.last() == self.target_position;
+
+        MazeStimuli {
+            current_path: self.current_path.clone(),
+            target_position: self.target_position,
+            neighbors: self.neighbors(self.current_path.last()),
+            reward: if terminal { 1.0 } else { 0.0 },
+            terminal,
+        }
+    }
 }
 
 impl<const N: usize> Environment for Maze<N> {
@@ -33,35 +45,98 @@ impl<const N: usize> Environment for Maze<N> {
     type Stimuli = MazeStimuli;
 
     fn initial_stimuli(&self) -> Self::Stimuli {
-        Self::Stimuli {
-            current_path: self.current_path.clone(),
-            target_position: self.target_position,
-            neighbors: self.neighbors(self.initial_position),
-        }
+        self.stimuli()
     }
 
     fn update(&mut self, new_path: Self::Action) -> Result<Self::Stimuli, Self::Error> {
         self.current_path = new_path;
 
-        Ok(Self::Stimuli {
-            current_path: self.current_path.clone(),
-            target_position: self.target_position,
-            neighbors: self.neighbors(self.current_path.last()),
-        })
+        Ok(self.stimuli())
     }
 
-    fn render(&self) {
-        let mut stdout = stdout();
+    /// A seed generates the maze again the way it was first generated, from
+    /// a generator reseeded with it. Mazes given cell by cell keep their
+    /// cells.
+    fn reset(&mut self, seed: Option<u64>) -> Self::Stimuli {
+        if let (Some(seed), Some(regenerate)) = (seed, self.regenerate.clone()) {
+            *self = regenerate(self, seed)
+                .with_connectivity(self.connectivity())
+                .with_frontier_trace(self.trace_frontier);
+            self.regenerate = Some(regenerate);
+        }
+
+        self.current_path = Path::new(self.initial_position);
+        self.trace = Default::default();
+        self.tick_paths = None;
+        self.agent_paths
+            .iter_mut()
+            .for_each(|path| *path = self.current_path.clone());
+
+        self.stimuli()
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.current_path.last() == self.target_position
+    }
+}
+
+impl<const N: usize> SharedEnvironment for Maze<N> {
+    /// Every agent keeps its own current path, all of them starting at the
+    /// initial position.
+    fn select_agent(&mut self, agent: usize) {
+        if agent == self.active_agent {
+            return;
+        }
+
+        let agents = agent.max(self.active_agent) + 1;
+        if self.agent_paths.len() < agents {
+            let initial = Path::new(self.initial_position);
+            self.agent_paths.resize(agents, initial);
+        }
+
+        self.agent_paths[self.active_agent] = self.current_path.clone();
+        self.current_path = self.agent_paths[agent].clone();
+        self.active_agent = agent;
+    }
+
+    /// Simultaneous moves are buffered: [`Maze::agent_paths`] shows where
+    /// the agents were when the tick began until it ends.
+    fn begin_tick(&mut self, resolution: Resolution) {
+        self.tick_paths = (resolution == Resolution::Simultaneous)
+            .then(|| self.agent_paths().into_iter().cloned().collect());
+    }
+
+    fn end_tick(&mut self) {
+        self.tick_paths = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        environment::Environment,
+        maze::{coordinates::Coordinates, generator::WalkGenerator, Ground, Maze},
+    };
+
+    #[test]
+    fn test_seeded_reset_keeps_the_generator() {
+        let (start, goal) = (Coordinates::new(0, 0), Coordinates::new(15, 15));
+        let walk = || Maze::<16>::with_generator(start, goal, 60, 1, WalkGenerator::new(None));
 
-        stdout.queue(cursor::SavePosition).unwrap();
-        stdout.write_all(format!("{}", self).as_bytes()).unwrap();
-        stdout.queue(cursor::RestorePosition).unwrap();
-        stdout.flush().unwrap();
-        thread::sleep(time::Duration::from_millis(100));
+        // Reseeded walks, not the backtracking of `with_seed`
+        let (mut first, mut second) = (walk(), walk());
+        first.reset(Some(7));
+        second.reset(Some(7));
+        assert_eq!(first.to_string(), second.to_string());
+        assert_ne!(
+            first.to_string(),
+            Maze::<16>::with_seed(start, goal, 60, 7).to_string()
+        );
 
-        stdout.queue(cursor::RestorePosition).unwrap();
-        stdout
-            .queue(terminal::Clear(terminal::ClearType::FromCursorDown))
-            .unwrap();
+        let mut cells = [[Ground::Free; 16]; 16];
+        cells[3][4] = Ground::Blocked;
+        let mut given = Maze::<16>::from_cells(start, goal, cells);
+        given.reset(Some(7));
+        assert_eq!(given.get(&Coordinates::new(3, 4)), Some(Ground::Blocked));
     }
 }
@@ -0,0 +1,310 @@
+//! Plain text maze files
+//!
+//! A maze file has one line per row of the maze, and one character per
+//! cell: `#` for walls, `.` for free cells, `S` for the start and `G` for the
+//! goal. The maze is square, as many rows as columns.
+//!
+//! Puzzle mazes, see [`inventory`](super::inventory), also have doors as
+//! capital letters, the keys opening them as the same letters in lowercase,
+//! and items as `*`. Doors and keys skip `S` and `G`, so there are at most
+//! 24 of them: the ones past the last letter are saved as walls and free
+//! cells, as are the items past the [`Inventory::CAPACITY`]. Items are
+//! numbered row after row.
+//!
+//! ```text
+//! S.A#
+//! .#..
+//! a#*#
+//! ...G
+//! ```
+
+use std::{error::Error, fmt, str::FromStr};
+
+use super::{coordinates::Coordinates, inventory::Inventory, Ground, Maze};
+
+/// Letters of the doors, by index.
+const DOORS: &[u8] = b"ABCDEFHIJKLMNOPQRTUVWXYZ";
+
+/// Contents of a maze file, whose side is only known at runtime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MazeFile {
+    size: usize,
+    start: Coordinates,
+    goal: Coordinates,
+    /// Row after row, the items numbered in that order.
+    cells: Vec<Ground>,
+}
+
+/// Error of parsing a [`MazeFile`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseMazeError {
+    Empty,
+    /// A row has not as many cells as there are rows.
+    NotSquare {
+        row: usize,
+        len: usize,
+        size: usize,
+    },
+    UnknownCell {
+        row: usize,
+        column: usize,
+        cell: char,
+    },
+    MissingStart,
+    MissingGoal,
+    /// The start or goal is given more than once.
+    Duplicate(char),
+    /// There are more items than an [`Inventory`] holds.
+    TooManyItems,
+}
+
+impl fmt::Display for ParseMazeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "the maze is empty"),
+            Self::NotSquare { row, len, size } => write!(
+                f,
+                "row {row} has {len} cells, the maze has {size} rows and must be square"
+            ),
+            Self::UnknownCell { row, column, cell } => write!(
+                f,
+                "unknown cell `{cell}` at row {row}, column {column}, expected one of `#.SG*` or a door or key letter"
+            ),
+            Self::MissingStart => write!(f, "the maze has no start `S`"),
+            Self::MissingGoal => write!(f, "the maze has no goal `G`"),
+            Self::Duplicate(cell) => write!(f, "the maze has more than one `{cell}`"),
+            Self::TooManyItems => write!(
+                f,
+                "the maze has more than {} items",
+                Inventory::CAPACITY
+            ),
+        }
+    }
+}
+
+impl Error for ParseMazeError {}
+
+impl MazeFile {
+    pub fn from_maze<const N: usize>(maze: &Maze<N>) -> Self {
+        let mut items = 0;
+        let cells = (0..N * N)
+            .map(|index| {
+                let coords = Coordinates::new((index / N) as i64, (index % N) as i64);
+                match maze.get(&coords).unwrap_or(Ground::Blocked) {
+                    Ground::Door(door) if door as usize >= DOORS.len() => Ground::Blocked,
+                    Ground::Key(key) if key as usize >= DOORS.len() => Ground::Free,
+                    Ground::Item(_) if items == Inventory::CAPACITY => Ground::Free,
+                    Ground::Item(_) => {
+                        items += 1;
+                        Ground::Item(items - 1)
+                    }
+                    Ground::Path => Ground::Free,
+                    ground => ground,
+                }
+            })
+            .collect();
+
+        Self {
+            size: N,
+            start: maze.initial_position,
+            goal: maze.target_position,
+            cells,
+        }
+    }
+
+    /// Number of rows, and of columns.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn start(&self) -> Coordinates {
+        self.start
+    }
+
+    pub fn goal(&self) -> Coordinates {
+        self.goal
+    }
+
+    /// Creates the maze of the file.
+    ///
+    /// # Panics
+    /// Panics if `N` is not the size of the file.
+    pub fn to_maze<const N: usize>(&self) -> Maze<N> {
+        assert_eq!(N, self.size, "the maze file has side {}", self.size);
+
+        let mut cells = [[Ground::Free; N]; N];
+        for (index, ground) in self.cells.iter().enumerate() {
+            cells[index / N][index % N] = *ground;
+        }
+
+        Maze::from_cells(self.start, self.goal, cells)
+    }
+}
+
+impl FromStr for MazeFile {
+    type Err = ParseMazeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rows: Vec<&str> = s
+            .lines()
+            .map(str::trim_end)
+            .filter(|row| !row.is_empty())
+            .collect();
+        let size = rows.len();
+
+        if size == 0 {
+            return Err(ParseMazeError::Empty);
+        }
+
+        let mut start = None;
+        let mut goal = None;
+        let mut cells = Vec::with_capacity(size * size);
+        let mut items = 0;
+
+        for (row, line) in rows.iter().enumerate() {
+            let len = line.chars().count();
+            if len != size {
+                return Err(ParseMazeError::NotSquare { row, len, size });
+            }
+
+            for (column, cell) in line.chars().enumerate() {
+                let coords = Coordinates::new(row as i64, column as i64);
+                let place = |position: &mut Option<Coordinates>| match position {
+                    Some(_) => Err(ParseMazeError::Duplicate(cell)),
+                    None => {
+                        *position = Some(coords);
+                        Ok(())
+                    }
+                };
+
+                let door = |letter: char| DOORS.iter().position(|door| *door as char == letter);
+
+                cells.push(match cell {
+                    '#' => Ground::Blocked,
+                    '.' => Ground::Free,
+                    'S' => place(&mut start).map(|()| Ground::Free)?,
+                    'G' => place(&mut goal).map(|()| Ground::Free)?,
+                    '*' if items == Inventory::CAPACITY => {
+                        return Err(ParseMazeError::TooManyItems)
+                    }
+                    '*' => {
+                        items += 1;
+                        Ground::Item(items - 1)
+                    }
+                    _ => match (door(cell), door(cell.to_ascii_uppercase())) {
+                        (Some(door), _) => Ground::Door(door as u8),
+                        (None, Some(key)) if cell.is_ascii_lowercase() => Ground::Key(key as u8),
+                        _ => return Err(ParseMazeError::UnknownCell { row, column, cell }),
+                    },
+                });
+            }
+        }
+
+        Ok(Self {
+            size,
+            start: start.ok_or(ParseMazeError::MissingStart)?,
+            goal: goal.ok_or(ParseMazeError::MissingGoal)?,
+            cells,
+        })
+    }
+}
+
+impl fmt::Display for MazeFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for x in 0..self.size {
+            let row: String = (0..self.size)
+                .map(|y| {
+                    let coords = Coordinates::new(x as i64, y as i64);
+                    if coords == self.start {
+                        'S'
+                    } else if coords == self.goal {
+                        'G'
+                    } else {
+                        match self.cells[x * self.size + y] {
+                            Ground::Blocked => '#',
+                            Ground::Door(door) => DOORS[door as usize] as char,
+                            Ground::Key(key) => DOORS[key as usize].to_ascii_lowercase() as char,
+                            Ground::Item(_) => '*',
+                            Ground::Free | Ground::Path => '.',
+                        }
+                    }
+                })
+                .collect();
+
+            writeln!(f, "{row}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::maze::{
+        coordinates::Coordinates,
+        generator::{ObjectGenerator, WalkGenerator},
+        placement::Placer,
+        Ground, Maze,
+    };
+
+    use super::{MazeFile, ParseMazeError};
+
+    #[test]
+    fn test_maze_file_round_trip() {
+        let maze = Maze::<8>::with_seed(Coordinates::new(0, 0), Coordinates::new(7, 5), 30, 2);
+        let text = MazeFile::from_maze(&maze).to_string();
+
+        let file: MazeFile = text.parse().unwrap();
+        assert_eq!(file.size(), 8);
+        assert_eq!(file.goal(), Coordinates::new(7, 5));
+        assert_eq!(MazeFile::from_maze(&file.to_maze::<8>()).to_string(), text);
+
+        assert_eq!(
+            "S.\n.G.\n".parse::<MazeFile>(),
+            Err(ParseMazeError::NotSquare {
+                row: 1,
+                len: 3,
+                size: 2
+            })
+        );
+        assert_eq!(
+            "S.\n..\n".parse::<MazeFile>(),
+            Err(ParseMazeError::MissingGoal)
+        );
+        assert_eq!(
+            "Sg\n.G\n".parse::<MazeFile>(),
+            Err(ParseMazeError::UnknownCell {
+                row: 0,
+                column: 1,
+                cell: 'g'
+            })
+        );
+
+        // Doors, keys and items are saved too
+        let generator = ObjectGenerator::new(WalkGenerator::new(Some([0; 32])), Placer::new(2, 3));
+        let maze = Maze::<16>::with_generator(
+            Coordinates::new(0, 0),
+            Coordinates::new(15, 15),
+            40,
+            0,
+            generator,
+        );
+        let text = MazeFile::from_maze(&maze).to_string();
+        assert!(text.contains('A') && text.contains('a'));
+        assert_eq!(text.matches('*').count(), 3);
+
+        let loaded = text.parse::<MazeFile>().unwrap().to_maze::<16>();
+        for x in 0..16 {
+            for y in 0..16 {
+                let cell = Coordinates::new(x, y);
+                match maze.get(&cell) {
+                    Some(Ground::Item(_)) => {
+                        assert!(matches!(loaded.get(&cell), Some(Ground::Item(_))))
+                    }
+                    ground => assert_eq!(loaded.get(&cell), ground),
+                }
+            }
+        }
+        assert_eq!(MazeFile::from_maze(&loaded).to_string(), text);
+    }
+}
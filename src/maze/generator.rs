@@ -1,11 +1,23 @@
-use rand::SeedableRng;
+use rand::{seq::SliceRandom, Rng, SeedableRng};
 use rand_chacha::ChaChaRng;
 
 use super::{
     coordinates::{Coordinates, Direction},
+    placement::Placer,
     Ground, Maze,
 };
 
+/// Carves passages into the randomly blocked cells of a new [`Maze`].
+pub trait Generator {
+    /// Carves passages starting from `from`, the target position of the maze.
+    fn generate<const N: usize>(&mut self, maze: &mut Maze<N>, from: Coordinates);
+
+    /// The same generator, drawing from `seed` instead.
+    fn reseeded(&self, seed: [u8; 32]) -> Self
+    where
+        Self: Sized;
+}
+
 /// [`Generator`] implementation which uses the recursive-backtracking algorithm.
 #[derive(Debug, Clone)]
 pub struct RbGenerator {
@@ -64,3 +76,107 @@ impl RbGenerator {
         }
     }
 }
+
+impl Generator for RbGenerator {
+    fn generate<const N: usize>(&mut self, maze: &mut Maze<N>, from: Coordinates) {
+        self.carve(maze, from);
+    }
+
+    fn reseeded(&self, seed: [u8; 32]) -> Self {
+        Self::new(Some(seed))
+    }
+}
+
+/// [`Generator`] implementation which frees a random walk from the target to
+/// the initial position, so that the maze always has a solution while the
+/// rest of it keeps the blocks drawn from the density.
+#[derive(Debug, Clone)]
+pub struct WalkGenerator {
+    rng: ChaChaRng,
+}
+
+impl WalkGenerator {
+    /// Create a new instance, optionally seeded as [`RbGenerator::new`].
+    pub fn new(seed: Option<[u8; 32]>) -> WalkGenerator {
+        WalkGenerator {
+            rng: match seed {
+                None => ChaChaRng::from_entropy(),
+                Some(seed) => ChaChaRng::from_seed(seed),
+            },
+        }
+    }
+}
+
+impl Generator for WalkGenerator {
+    fn generate<const N: usize>(&mut self, maze: &mut Maze<N>, from: Coordinates) {
+        let goal = maze.initial_position;
+        let mut current = from;
+
+        while current != goal {
+            // Half of the moves head to the goal, the others wander
+            let direction = if self.rng.gen_bool(0.5) {
+                let horizontal =
+                    current.y == goal.y || (current.x != goal.x && self.rng.gen_bool(0.5));
+
+                match horizontal {
+                    true if goal.x > current.x => Direction::East,
+                    true => Direction::West,
+                    false if goal.y > current.y => Direction::South,
+                    false => Direction::North,
+                }
+            } else {
+                *[
+                    Direction::North,
+                    Direction::East,
+                    Direction::South,
+                    Direction::West,
+                ]
+                .choose(&mut self.rng)
+                .unwrap()
+            };
+
+            let next = current.next(direction);
+            if let Some(ground) = maze.get_mut(&next) {
+                *ground = Ground::Free;
+                current = next;
+            }
+        }
+    }
+
+    fn reseeded(&self, seed: [u8; 32]) -> Self {
+        Self::new(Some(seed))
+    }
+}
+
+/// [`Generator`] placing doors, their keys and items with a [`Placer`] once
+/// another generator carved the maze, so that it stays solvable.
+///
+/// Mazes without room for every door get as many as fit, and no object at
+/// all when the items don't fit either.
+#[derive(Debug, Clone)]
+pub struct ObjectGenerator<G> {
+    carver: G,
+    placer: Placer,
+}
+
+impl<G: Generator> ObjectGenerator<G> {
+    pub fn new(carver: G, placer: Placer) -> Self {
+        Self { carver, placer }
+    }
+}
+
+impl<G: Generator> Generator for ObjectGenerator<G> {
+    fn generate<const N: usize>(&mut self, maze: &mut Maze<N>, from: Coordinates) {
+        self.carver.generate(maze, from);
+
+        if let Some(placement) = self.placer.place_fitting(maze) {
+            placement.apply(maze);
+        }
+    }
+
+    /// Reseeds the carver, objects are placed the same way in the new
+    /// layout.
+    fn reseeded(&self, seed: [u8; 32]) -> Self {
+        Self::new(self.carver.reseeded(seed), self.placer)
+    }
+}
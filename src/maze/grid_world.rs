@@ -0,0 +1,152 @@
+//! Grid world environment for learning agents
+//!
+//! A [`GridWorld`] wraps a [`Maze`] so that an agent moves one cell at a time
+//! and receives a reward after every move, which is the interface expected by
+//! reinforcement learning agents.
+
+use crate::{
+    environment::Environment,
+    maze::{
+        coordinates::{Coordinates, Direction},
+        Maze, Path,
+    },
+};
+
+/// Rewards handed out by a [`GridWorld`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rewards {
+    /// Reward for moving into a free cell.
+    pub step: f64,
+    /// Reward for bumping into a wall or the border of the maze.
+    pub collision: f64,
+    /// Reward for reaching the target position.
+    pub goal: f64,
+}
+
+impl Default for Rewards {
+    fn default() -> Self {
+        Self {
+            step: -1.0,
+            collision: -5.0,
+            goal: 100.0,
+        }
+    }
+}
+
+/// Stimuli produced by a [`GridWorld`] update.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridStimuli {
+    pub position: Coordinates,
+    pub reward: f64,
+    pub terminal: bool,
+}
+
+/// Maze where the agent walks one cell per action.
+pub struct GridWorld<const N: usize> {
+    pub maze: Maze<N>,
+    pub rewards: Rewards,
+    position: Coordinates,
+    steps: usize,
+}
+
+impl<const N: usize> GridWorld<N> {
+    /// Creates a new grid world starting at the initial position of the maze.
+    pub fn new(mut maze: Maze<N>) -> Self {
+        let position = maze.initial_position;
+        maze.current_path = Path::new(position);
+
+        Self {
+            maze,
+            rewards: Rewards::default(),
+            position,
+            steps: 0,
+        }
+    }
+
+    /// Replaces the default rewards.
+    pub fn with_rewards(mut self, rewards: Rewards) -> Self {
+        self.rewards = rewards;
+        self
+    }
+
+    /// Current position of the agent.
+    pub fn position(&self) -> Coordinates {
+        self.position
+    }
+
+    /// Number of actions taken in the current episode.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Starts over from a new initial position, keeping the layout.
+    ///
+    /// # Panics
+    /// Panics if the position is outside of the maze.
+    pub fn reset_to(&mut self, initial_position: Coordinates) -> GridStimuli {
+        self.maze.reset_to(initial_position);
+        self.reset(None)
+    }
+}
+
+impl<const N: usize> Environment for GridWorld<N> {
+    type Error = ();
+
+    type Action = Direction;
+
+    type Stimuli = GridStimuli;
+
+    fn initial_stimuli(&self) -> Self::Stimuli {
+        GridStimuli {
+            position: self.maze.initial_position,
+            reward: 0.0,
+            terminal: self.maze.initial_position == self.maze.target_position,
+        }
+    }
+
+    fn update(&mut self, action: Self::Action) -> Result<Self::Stimuli, Self::Error> {
+        let next = self.position.next(action);
+        self.steps += 1;
+
+        let reward = if self.maze.is_walkable(&next) {
+            self.position = next;
+            self.maze.current_path.push(next);
+
+            if self.is_terminal() {
+                self.rewards.goal
+            } else {
+                self.rewards.step
+            }
+        } else {
+            self.rewards.collision
+        };
+
+        Ok(GridStimuli {
+            position: self.position,
+            reward,
+            terminal: self.is_terminal(),
+        })
+    }
+
+    fn reset(&mut self, seed: Option<u64>) -> Self::Stimuli {
+        self.maze.reset(seed);
+        self.position = self.maze.initial_position;
+        self.steps = 0;
+
+        GridStimuli {
+            position: self.position,
+            reward: 0.0,
+            terminal: self.is_terminal(),
+        }
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.position == self.maze.target_position
+    }
+}
+
+impl<const N: usize> std::fmt::Display for GridWorld<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.maze.fmt(f)
+    }
+}
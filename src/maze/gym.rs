@@ -0,0 +1,260 @@
+//! Gymnasium-style stepping API
+//!
+//! [`MazeEnv`] exposes a [`GridWorld`] through the `reset`/`step` interface
+//! used by Python reinforcement learning frameworks: actions are indices of a
+//! discrete space and observations are flat `f32` vectors produced by an
+//! [`ObservationEncoder`].
+
+use crate::{
+    environment::Environment,
+    maze::{
+        agent::learning::ACTIONS,
+        coordinates::Coordinates,
+        grid_world::{GridStimuli, GridWorld},
+        Maze,
+    },
+};
+
+/// Observation handed to the agent.
+pub type Observation = Vec<f32>;
+
+/// Result of [`MazeEnv::step`]: `(observation, reward, terminated, truncated, info)`.
+pub type StepResult = (Observation, f64, bool, bool, Info);
+
+/// Descriptor of an action or observation space.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Space {
+    /// Integers in `0..n`.
+    Discrete(usize),
+    /// Real-valued tensor of a given shape with every entry in `low..=high`.
+    Box {
+        low: f32,
+        high: f32,
+        shape: Vec<usize>,
+    },
+}
+
+/// How the agent position and the maze are turned into an [`Observation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObservationEncoder {
+    /// One-hot vector of length `N * N` marking the agent position.
+    OneHot,
+    /// Square window of side `2 * radius + 1` centered on the agent, where
+    /// walls and cells outside the maze are `1.0` and free cells `0.0`.
+    LocalWindow { radius: usize },
+    /// Tensor of shape `[3, N, N]` with walls, agent and goal channels.
+    FullGrid,
+}
+
+impl ObservationEncoder {
+    /// Space of the observations produced for a maze of side `n`.
+    pub fn space(&self, n: usize) -> Space {
+        let shape = match self {
+            Self::OneHot => vec![n * n],
+            Self::LocalWindow { radius } => vec![2 * radius + 1, 2 * radius + 1],
+            Self::FullGrid => vec![3, n, n],
+        };
+
+        Space::Box {
+            low: 0.0,
+            high: 1.0,
+            shape,
+        }
+    }
+
+    /// Encodes the agent position in a maze.
+    pub fn encode<const N: usize>(&self, maze: &Maze<N>, position: Coordinates) -> Observation {
+        let index = |coordinates: Coordinates| coordinates.x as usize * N + coordinates.y as usize;
+        let wall = |coordinates: Coordinates| !maze.is_walkable(&coordinates);
+
+        match self {
+            Self::OneHot => {
+                let mut observation = vec![0.0; N * N];
+                observation[index(position)] = 1.0;
+                observation
+            }
+            Self::LocalWindow { radius } => {
+                let radius = *radius as i64;
+                let mut observation =
+                    Vec::with_capacity(((2 * radius + 1) * (2 * radius + 1)) as usize);
+
+                for dx in -radius..=radius {
+                    for dy in -radius..=radius {
+                        let cell = Coordinates::new(position.x + dx, position.y + dy);
+                        observation.push(if wall(cell) { 1.0 } else { 0.0 });
+                    }
+                }
+
+                observation
+            }
+            Self::FullGrid => {
+                let mut observation = vec![0.0; 3 * N * N];
+
+                for x in 0..N {
+                    for y in 0..N {
+                        let cell = Coordinates::new(x as i64, y as i64);
+                        if wall(cell) {
+                            observation[index(cell)] = 1.0;
+                        }
+                    }
+                }
+
+                observation[N * N + index(position)] = 1.0;
+                observation[2 * N * N + index(maze.target_position)] = 1.0;
+                observation
+            }
+        }
+    }
+}
+
+/// Auxiliary diagnostic information returned with every observation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Info {
+    pub position: Coordinates,
+    /// Steps taken since the last reset.
+    pub steps: usize,
+    /// Euclidean distance from the agent to the target position.
+    pub distance_to_goal: f32,
+}
+
+/// Error returned when stepping with an action outside of the action space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidAction(pub usize);
+
+impl std::fmt::Display for InvalidAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "action {} is outside of the action space", self.0)
+    }
+}
+
+impl std::error::Error for InvalidAction {}
+
+/// Gymnasium-compatible maze environment.
+///
+/// Action `i` moves the agent towards [`ACTIONS`]`[i]`.
+pub struct MazeEnv<const N: usize> {
+    world: GridWorld<N>,
+    encoder: ObservationEncoder,
+    max_steps: Option<usize>,
+}
+
+impl<const N: usize> MazeEnv<N> {
+    /// Wraps a grid world, observing it with one-hot positions and no step limit.
+    pub fn new(world: GridWorld<N>) -> Self {
+        Self {
+            world,
+            encoder: ObservationEncoder::OneHot,
+            max_steps: None,
+        }
+    }
+
+    /// Replaces the observation encoder.
+    pub fn with_encoder(mut self, encoder: ObservationEncoder) -> Self {
+        self.encoder = encoder;
+        self
+    }
+
+    /// Truncates episodes after a number of steps.
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = Some(max_steps);
+        self
+    }
+
+    /// The wrapped grid world.
+    pub fn world(&self) -> &GridWorld<N> {
+        &self.world
+    }
+
+    pub fn action_space(&self) -> Space {
+        Space::Discrete(ACTIONS.len())
+    }
+
+    pub fn observation_space(&self) -> Space {
+        self.encoder.space(N)
+    }
+
+    /// Starts a new episode, regenerating the maze if a seed is given.
+    pub fn reset(&mut self, seed: Option<u64>) -> (Observation, Info) {
+        let stimuli = self.world.reset(seed);
+
+        (self.observe(&stimuli), self.info(&stimuli))
+    }
+
+    /// Moves the agent one cell.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the action is outside of the
+    /// action space.
+    pub fn step(&mut self, action: usize) -> Result<StepResult, InvalidAction> {
+        let direction = *ACTIONS.get(action).ok_or(InvalidAction(action))?;
+
+        let stimuli = self
+            .world
+            .update(direction)
+            .expect("grid world updates never fail");
+
+        let truncated = !stimuli.terminal
+            && self
+                .max_steps
+                .is_some_and(|max_steps| self.world.steps() >= max_steps);
+
+        Ok((
+            self.observe(&stimuli),
+            stimuli.reward,
+            stimuli.terminal,
+            truncated,
+            self.info(&stimuli),
+        ))
+    }
+
+    fn observe(&self, stimuli: &GridStimuli) -> Observation {
+        self.encoder.encode(&self.world.maze, stimuli.position)
+    }
+
+    fn info(&self, stimuli: &GridStimuli) -> Info {
+        Info {
+            position: stimuli.position,
+            steps: self.world.steps(),
+            distance_to_goal: stimuli
+                .position
+                .euclidean_dist(&self.world.maze.target_position),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::maze::{coordinates::Coordinates, grid_world::GridWorld, Maze};
+
+    use super::{MazeEnv, ObservationEncoder, Space};
+
+    #[test]
+    fn test_step_and_truncate() {
+        let maze = Maze::<4>::new(Coordinates::new(0, 0), Coordinates::new(3, 3), 0);
+        let mut env = MazeEnv::new(GridWorld::new(maze))
+            .with_encoder(ObservationEncoder::LocalWindow { radius: 1 })
+            .with_max_steps(2);
+
+        let (observation, info) = env.reset(None);
+        assert_eq!(
+            observation,
+            vec![1.0, 1.0, 1.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0]
+        );
+        assert_eq!(info.position, Coordinates::new(0, 0));
+
+        // East
+        let (_, _, terminated, truncated, info) = env.step(1).unwrap();
+        assert_eq!(info.position, Coordinates::new(1, 0));
+        assert!(!terminated && !truncated);
+
+        // North, into the border
+        let (_, reward, terminated, truncated, info) = env.step(0).unwrap();
+        assert_eq!(info.position, Coordinates::new(1, 0));
+        assert_eq!(reward, env.world().rewards.collision);
+        assert!(!terminated && truncated);
+
+        assert!(env.step(4).is_err());
+        assert_eq!(env.action_space(), Space::Discrete(4));
+    }
+}
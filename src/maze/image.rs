@@ -0,0 +1,380 @@
+//! Still images of mazes
+//!
+//! [`ImageExporter`] draws a [`Maze`], optionally with the current path as
+//! solution and a heat map of the order in which the search expanded cells,
+//! and writes it as PNG or SVG. The SVG keeps a `viewBox`, so it scales to
+//! any printed size.
+
+use std::{
+    fmt::Write as _,
+    io::{self, Write},
+};
+
+use super::{animation::Palette, coordinates::Coordinates, Ground, Maze};
+
+/// How walls are drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WallStyle {
+    /// Blocked cells are filled.
+    #[default]
+    Blocks,
+    /// Thin lines separate free cells from blocked cells and from the border.
+    Thin,
+}
+
+/// Colors of the heat map, from the first to the last expanded cell.
+const HEAT: ([u8; 3], [u8; 3]) = ([250, 230, 120], [200, 40, 40]);
+
+/// Axis aligned rectangle, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Rect {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    color: [u8; 3],
+}
+
+/// Draws still images of mazes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageExporter {
+    cell_size: u32,
+    margin: u32,
+    wall_style: WallStyle,
+    wall_thickness: u32,
+    solution: bool,
+    heat_map: bool,
+    palette: Palette,
+}
+
+impl Default for ImageExporter {
+    fn default() -> Self {
+        Self {
+            cell_size: 16,
+            margin: 16,
+            wall_style: WallStyle::default(),
+            wall_thickness: 2,
+            solution: true,
+            heat_map: false,
+            palette: Palette::default(),
+        }
+    }
+}
+
+impl ImageExporter {
+    /// Sets the side of a cell, in pixels.
+    pub fn with_cell_size(mut self, cell_size: u32) -> Self {
+        self.cell_size = cell_size;
+        self
+    }
+
+    /// Sets the blank border around the maze, in pixels.
+    pub fn with_margin(mut self, margin: u32) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Sets how walls are drawn, and the thickness of [`WallStyle::Thin`]
+    /// walls in pixels.
+    pub fn with_walls(mut self, wall_style: WallStyle, thickness: u32) -> Self {
+        self.wall_style = wall_style;
+        self.wall_thickness = thickness;
+        self
+    }
+
+    /// Draws the current path of the maze over it.
+    pub fn with_solution(mut self, solution: bool) -> Self {
+        self.solution = solution;
+        self
+    }
+
+    /// Colors the expanded cells by the order of their first expansion, as
+    /// recorded in [`Maze::trace`].
+    pub fn with_heat_map(mut self, heat_map: bool) -> Self {
+        self.heat_map = heat_map;
+        self
+    }
+
+    pub fn with_palette(mut self, palette: Palette) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    /// Width and height of the images of a maze of side `N`, in pixels.
+    pub fn dimensions<const N: usize>(&self) -> (u32, u32) {
+        let side = 2 * self.margin + N as u32 * self.cell_size;
+        (side, side)
+    }
+
+    /// Rectangle covering a cell.
+    fn cell(&self, coords: Coordinates, color: [u8; 3]) -> Rect {
+        Rect {
+            x: self.margin + coords.y as u32 * self.cell_size,
+            y: self.margin + coords.x as u32 * self.cell_size,
+            width: self.cell_size,
+            height: self.cell_size,
+            color,
+        }
+    }
+
+    /// Rectangle of the given thickness joining the centers of two cells.
+    fn segment(&self, from: Coordinates, to: Coordinates, thickness: u32, color: [u8; 3]) -> Rect {
+        let (top, bottom) = (from.x.min(to.x) as u32, from.x.max(to.x) as u32);
+        let (left, right) = (from.y.min(to.y) as u32, from.y.max(to.y) as u32);
+        let center = self.cell_size.saturating_sub(thickness) / 2;
+
+        Rect {
+            x: self.margin + left * self.cell_size + center,
+            y: self.margin + top * self.cell_size + center,
+            width: (right - left) * self.cell_size + thickness,
+            height: (bottom - top) * self.cell_size + thickness,
+            color,
+        }
+    }
+
+    /// Thin walls on the sides of a walkable cell facing blocked cells,
+    /// doors or the border.
+    fn thin_walls<const N: usize>(&self, maze: &Maze<N>, coords: Coordinates) -> Vec<Rect> {
+        let cell = self.cell(coords, self.palette.wall);
+        let thickness = self.wall_thickness.min(self.cell_size);
+        let half = thickness / 2;
+        let blocked =
+            |x: i64, y: i64| !maze.is_walkable(&Coordinates::new(coords.x + x, coords.y + y));
+
+        let mut walls = Vec::new();
+
+        if blocked(-1, 0) {
+            walls.push(Rect {
+                y: cell.y.saturating_sub(half),
+                height: thickness,
+                ..cell
+            });
+        }
+        if blocked(1, 0) {
+            walls.push(Rect {
+                y: (cell.y + cell.height).saturating_sub(half),
+                height: thickness,
+                ..cell
+            });
+        }
+        if blocked(0, -1) {
+            walls.push(Rect {
+                x: cell.x.saturating_sub(half),
+                width: thickness,
+                ..cell
+            });
+        }
+        if blocked(0, 1) {
+            walls.push(Rect {
+                x: (cell.x + cell.width).saturating_sub(half),
+                width: thickness,
+                ..cell
+            });
+        }
+
+        walls
+    }
+
+    /// Everything drawn, back to front.
+    fn scene<const N: usize>(&self, maze: &Maze<N>) -> Vec<Rect> {
+        let (width, height) = self.dimensions::<N>();
+        let mut scene = vec![Rect {
+            x: 0,
+            y: 0,
+            width,
+            height,
+            color: self.palette.background,
+        }];
+
+        if self.heat_map {
+            let order = &maze.trace().order;
+            let last = order.len().saturating_sub(1).max(1) as f64;
+
+            scene.extend(order.iter().enumerate().map(|(index, coords)| {
+                let t = index as f64 / last;
+                let color: [u8; 3] = std::array::from_fn(|channel| {
+                    let (cold, hot) = (HEAT.0[channel] as f64, HEAT.1[channel] as f64);
+                    (cold + (hot - cold) * t).round() as u8
+                });
+
+                self.cell(*coords, color)
+            }));
+        }
+
+        let marker = (self.cell_size / 2).max(1);
+
+        for x in 0..N as i64 {
+            for y in 0..N as i64 {
+                let coords = Coordinates::new(x, y);
+
+                match (self.wall_style, maze.get(&coords)) {
+                    (WallStyle::Blocks, Some(Ground::Blocked)) => {
+                        scene.push(self.cell(coords, self.palette.wall))
+                    }
+                    (_, Some(Ground::Door(_))) => scene.push(self.cell(coords, self.palette.door)),
+                    (WallStyle::Thin, Some(ground)) if ground.is_walkable() => {
+                        scene.extend(self.thin_walls(maze, coords))
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if self.solution {
+            let thickness = (self.cell_size / 3).max(1);
+            let path = maze.current_path.cells();
+
+            scene.extend(
+                path.windows(2)
+                    .map(|step| self.segment(step[0], step[1], thickness, self.palette.path)),
+            );
+            if let [single] = path.as_slice() {
+                scene.push(self.segment(*single, *single, thickness, self.palette.path));
+            }
+        }
+
+        for x in 0..N as i64 {
+            for y in 0..N as i64 {
+                let coords = Coordinates::new(x, y);
+                let color = match maze.get(&coords) {
+                    Some(Ground::Key(_)) => self.palette.key,
+                    Some(Ground::Item(_)) => self.palette.item,
+                    _ => continue,
+                };
+
+                scene.push(self.segment(coords, coords, marker, color));
+            }
+        }
+
+        scene.push(self.segment(
+            maze.initial_position,
+            maze.initial_position,
+            marker,
+            self.palette.start,
+        ));
+        scene.push(self.segment(
+            maze.target_position,
+            maze.target_position,
+            marker,
+            self.palette.goal,
+        ));
+
+        scene
+    }
+
+    /// Writes the maze as an RGB PNG.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the image can't be encoded or
+    /// written.
+    pub fn write_png<const N: usize, W: Write>(&self, maze: &Maze<N>, writer: W) -> io::Result<()> {
+        let (width, height) = self.dimensions::<N>();
+        let mut pixels = vec![0; width as usize * height as usize * 3];
+
+        for rect in self.scene(maze) {
+            let right = (rect.x + rect.width).min(width);
+            let bottom = (rect.y + rect.height).min(height);
+
+            for y in rect.y..bottom {
+                let row = y as usize * width as usize;
+
+                for x in rect.x..right {
+                    let pixel = (row + x as usize) * 3;
+                    pixels[pixel..pixel + 3].copy_from_slice(&rect.color);
+                }
+            }
+        }
+
+        let mut encoder = png::Encoder::new(writer, width, height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&pixels))
+            .map_err(io::Error::other)
+    }
+
+    /// Writes the maze as an SVG document.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the document can't be written.
+    pub fn write_svg<const N: usize, W: Write>(
+        &self,
+        maze: &Maze<N>,
+        mut writer: W,
+    ) -> io::Result<()> {
+        let (width, height) = self.dimensions::<N>();
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\" shape-rendering=\"crispEdges\">\n"
+        );
+
+        for rect in self.scene(maze) {
+            let [r, g, b] = rect.color;
+            let _ = writeln!(
+                svg,
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#{r:02x}{g:02x}{b:02x}\"/>",
+                rect.x, rect.y, rect.width, rect.height
+            );
+        }
+
+        svg.push_str("</svg>\n");
+
+        writer.write_all(svg.as_bytes())?;
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        agent::Agent,
+        maze::{
+            agent::{graph_based::BFSFinder, PathFinder},
+            coordinates::Coordinates,
+            Maze,
+        },
+        render::Headless,
+        simulator::Simulator,
+    };
+
+    use super::{ImageExporter, WallStyle};
+
+    #[test]
+    fn test_export_images() {
+        let maze = Maze::<8>::with_seed(Coordinates::new(0, 0), Coordinates::new(7, 7), 20, 3);
+        let finder = PathFinder::<8, BFSFinder>::new(&maze);
+
+        let mut simulator = Simulator::new(finder, Headless, maze);
+        simulator.simulate().unwrap();
+        let maze = simulator.environment();
+
+        let exporter = ImageExporter::default()
+            .with_cell_size(10)
+            .with_margin(5)
+            .with_heat_map(true);
+        assert_eq!(exporter.dimensions::<8>(), (90, 90));
+
+        let mut png = Vec::new();
+        exporter.write_png(maze, &mut png).unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+
+        let mut svg = Vec::new();
+        exporter
+            .with_walls(WallStyle::Thin, 2)
+            .write_svg(maze, &mut svg)
+            .unwrap();
+        let svg = String::from_utf8(svg).unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("viewBox=\"0 0 90 90\""));
+
+        // Cells without pixels leave the margin alone
+        let empty = ImageExporter::default().with_cell_size(0);
+        assert_eq!(empty.dimensions::<8>(), (32, 32));
+        empty.write_png(maze, &mut Vec::new()).unwrap();
+        empty.write_svg(maze, &mut Vec::new()).unwrap();
+    }
+}
@@ -0,0 +1,487 @@
+//! Keys, doors and items
+//!
+//! A [`Ground::Door`] only lets through the agents holding the
+//! [`Ground::Key`] with the same index, and the target only counts once
+//! every [`Ground::Item`] is collected. Keys and items are picked up by
+//! stepping on their cell, and keys are kept once used.
+//!
+//! What the agent holds is part of the state: a [`KeyMaze`] is both an
+//! [`Environment`] and a [`Problem`] over positions and inventories, which
+//! a [`KeyFinder`] solves with A* by default. Mazes are filled by an
+//! [`ObjectGenerator`](super::generator::ObjectGenerator) so that they stay
+//! solvable.
+//!
+//! ```
+//! use maze::{
+//!     maze::{
+//!         generator::{ObjectGenerator, WalkGenerator},
+//!         inventory::{KeyFinder, KeyMaze},
+//!         placement::Placer,
+//!     },
+//!     prelude::*,
+//! };
+//!
+//! let generator = ObjectGenerator::new(WalkGenerator::new(Some([0; 32])), Placer::new(2, 3));
+//! let maze =
+//!     Maze::<16>::with_generator(Coordinates::new(0, 0), Coordinates::new(15, 15), 40, 0, generator);
+//! let keys = KeyMaze::new(maze);
+//! let finder = KeyFinder::<16>::new(&keys);
+//!
+//! let mut simulator = Simulator::new(finder, Headless, keys);
+//! let stats = simulator.run_episodes(1).unwrap();
+//! assert!(stats[0].terminated);
+//! assert_eq!(simulator.environment().inventory().items(), 3);
+//! ```
+
+use std::{collections::HashSet, error::Error, fmt};
+
+use crate::{
+    environment::Environment,
+    search::{Planner, Problem},
+};
+
+use super::{
+    agent::astar::AStarFinder,
+    coordinates::{Coordinates, Direction},
+    Ground, Maze, Path,
+};
+
+/// Plans the way through a [`KeyMaze`], with A* by default.
+pub type KeyFinder<const N: usize, F = AStarFinder> = Planner<KeyMaze<N>, F>;
+
+/// Errors of the [`KeyMaze`] environment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyMazeError {
+    /// The agent walks into a wall or out of the maze.
+    Blocked,
+    /// The agent walks into a door without its key.
+    Locked(u8),
+}
+
+impl fmt::Display for KeyMazeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Blocked => write!(f, "the move is blocked"),
+            Self::Locked(door) => write!(f, "door {door} is locked"),
+        }
+    }
+}
+
+impl Error for KeyMazeError {}
+
+/// Keys and items picked up, up to 64 of each.
+///
+/// Keys and items whose index is [`Inventory::CAPACITY`] or more can't be
+/// held: they stay on the ground, and their doors stay locked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Inventory {
+    keys: u64,
+    items: u64,
+}
+
+/// Bit of an index in the masks of an [`Inventory`], if it holds one.
+fn bit(index: u8) -> Option<u64> {
+    1u64.checked_shl(index.into())
+}
+
+impl Inventory {
+    /// Number of keys, and of items, an inventory holds.
+    pub const CAPACITY: u8 = u64::BITS as u8;
+
+    pub fn has_key(&self, key: u8) -> bool {
+        bit(key).is_some_and(|bit| self.keys & bit != 0)
+    }
+
+    pub fn has_item(&self, item: u8) -> bool {
+        bit(item).is_some_and(|bit| self.items & bit != 0)
+    }
+
+    /// Indices of the keys held, in order.
+    pub fn keys(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..Self::CAPACITY).filter(|key| self.has_key(*key))
+    }
+
+    /// Number of items collected.
+    pub fn items(&self) -> usize {
+        self.items.count_ones() as usize
+    }
+
+    /// Picks up the key or the item lying on the ground, if any.
+    pub fn pick(&mut self, ground: Ground) {
+        match ground {
+            Ground::Key(key) => self.keys |= bit(key).unwrap_or(0),
+            Ground::Item(item) => self.items |= bit(item).unwrap_or(0),
+            _ => {}
+        }
+    }
+
+    /// Whether the ground can be walked on, doors being open with their key.
+    pub fn can_enter(&self, ground: Ground) -> bool {
+        match ground {
+            Ground::Door(door) => self.has_key(door),
+            ground => ground.is_walkable(),
+        }
+    }
+}
+
+/// Position of the agent and what it holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyState {
+    pub position: Coordinates,
+    pub inventory: Inventory,
+}
+
+/// Stimuli produced by a [`KeyMaze`] update.
+///
+/// The reward is `1.0` once the target is reached with every item and `0.0`
+/// otherwise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyMazeStimuli {
+    pub state: KeyState,
+    pub neighbors: Vec<Direction>,
+    pub reward: f64,
+    pub terminal: bool,
+}
+
+/// A [`Maze`] with doors, keys and items, walked one step at a time.
+pub struct KeyMaze<const N: usize> {
+    maze: Maze<N>,
+    /// Every item of the maze and its cell.
+    items: Vec<(u8, Coordinates)>,
+    state: KeyState,
+    path: Path,
+}
+
+impl<const N: usize> KeyMaze<N> {
+    pub fn new(maze: Maze<N>) -> Self {
+        let items = (0..N as i64)
+            .flat_map(|x| (0..N as i64).map(move |y| Coordinates::new(x, y)))
+            .filter_map(|cell| match maze.get(&cell) {
+                Some(Ground::Item(item)) => Some((item, cell)),
+                _ => None,
+            })
+            .collect();
+        let start = maze.initial_position;
+
+        Self {
+            items,
+            state: KeyState {
+                position: start,
+                inventory: Inventory::default(),
+            },
+            path: Path::new(start),
+            maze,
+        }
+    }
+
+    pub fn maze(&self) -> &Maze<N> {
+        &self.maze
+    }
+
+    pub fn state(&self) -> &KeyState {
+        &self.state
+    }
+
+    pub fn inventory(&self) -> &Inventory {
+        &self.state.inventory
+    }
+
+    /// Cells walked since the start of the episode.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Number of items in the maze.
+    pub fn items(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Whether the state is at the target with every item.
+    pub fn is_solved(&self, state: &KeyState) -> bool {
+        state.position == self.maze.target_position
+            && self
+                .items
+                .iter()
+                .all(|(item, _)| state.inventory.has_item(*item))
+    }
+
+    /// Directions the agent can walk in, through the doors it holds the
+    /// key of.
+    pub fn moves(&self, state: &KeyState) -> Vec<Direction> {
+        self.maze
+            .neighbors_where(state.position, |ground| state.inventory.can_enter(ground))
+    }
+
+    /// The state after walking in `direction`, which must be one of the
+    /// [`moves`](Self::moves).
+    fn walk(&self, state: &KeyState, direction: Direction) -> KeyState {
+        let position = state.position.next(direction);
+        let mut inventory = state.inventory;
+        if let Some(ground) = self.maze.get(&position) {
+            inventory.pick(ground);
+        }
+
+        KeyState {
+            position,
+            inventory,
+        }
+    }
+
+    fn stimuli(&self) -> KeyMazeStimuli {
+        let terminal = self.is_solved(&self.state);
+
+        KeyMazeStimuli {
+            state: self.state,
+            neighbors: self.moves(&self.state),
+            reward: if terminal { 1.0 } else { 0.0 },
+            terminal,
+        }
+    }
+
+    /// What a cell shows: doors are capital letters and their keys small
+    /// ones, until the key is picked up.
+    fn symbol(&self, cell: Coordinates, path: &HashSet<Coordinates>) -> String {
+        let ground = self.maze.get(&cell).unwrap_or(Ground::Blocked);
+        let letter = |index: u8, first: u8| match index < 26 {
+            true => format!("{} ", (first + index) as char),
+            false => ground.as_ref().to_string(),
+        };
+        let inventory = &self.state.inventory;
+
+        match ground {
+            Ground::Blocked => ground.as_ref().to_string(),
+            _ if path.contains(&cell) => Ground::Path.as_ref().to_string(),
+            Ground::Door(door) if !inventory.has_key(door) => letter(door, b'A'),
+            Ground::Key(key) if !inventory.has_key(key) => letter(key, b'a'),
+            Ground::Item(item) if !inventory.has_item(item) => ground.as_ref().to_string(),
+            _ => "  ".to_string(),
+        }
+    }
+}
+
+impl<const N: usize> Environment for KeyMaze<N> {
+    type Error = KeyMazeError;
+
+    type Action = Direction;
+
+    type Stimuli = KeyMazeStimuli;
+
+    fn initial_stimuli(&self) -> Self::Stimuli {
+        self.stimuli()
+    }
+
+    fn update(&mut self, action: Self::Action) -> Result<Self::Stimuli, Self::Error> {
+        if !self.moves(&self.state).contains(&action) {
+            return Err(match self.maze.get(&self.state.position.next(action)) {
+                Some(Ground::Door(door)) if !self.state.inventory.has_key(door) => {
+                    KeyMazeError::Locked(door)
+                }
+                _ => KeyMazeError::Blocked,
+            });
+        }
+
+        self.state = self.walk(&self.state, action);
+        self.path.push(self.state.position);
+
+        Ok(self.stimuli())
+    }
+
+    /// Starts over with an empty inventory, the seed is ignored.
+    fn reset(&mut self, _seed: Option<u64>) -> Self::Stimuli {
+        let start = self.maze.initial_position;
+        self.state = KeyState {
+            position: start,
+            inventory: Inventory::default(),
+        };
+        self.path = Path::new(start);
+
+        self.stimuli()
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.is_solved(&self.state)
+    }
+}
+
+/// Reaching the target with every item from the current state, on the
+/// shortest way.
+impl<const N: usize> Problem for KeyMaze<N> {
+    type State = KeyState;
+
+    type Action = Direction;
+
+    fn start(&self) -> Self::State {
+        self.state
+    }
+
+    fn is_goal(&self, state: &Self::State) -> bool {
+        self.is_solved(state)
+    }
+
+    fn successors(&self, state: &Self::State) -> Vec<(Self::Action, Self::State, f32)> {
+        self.moves(state)
+            .into_iter()
+            .map(|direction| {
+                let next = self.walk(state, direction);
+                let cost = state.position.euclidean_dist(&next.position);
+                (direction, next, cost)
+            })
+            .collect()
+    }
+
+    /// Distance to the farthest of the target and the items left, all of
+    /// them being visited.
+    fn heuristic(&self, state: &Self::State) -> f32 {
+        self.items
+            .iter()
+            .filter(|(item, _)| !state.inventory.has_item(*item))
+            .map(|(_, cell)| *cell)
+            .chain([self.maze.target_position])
+            .map(|cell| state.position.euclidean_dist(&cell))
+            .fold(0.0, f32::max)
+    }
+}
+
+impl<const N: usize> fmt::Display for KeyMaze<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path: HashSet<_> = self.path.rev_iter().collect();
+
+        for x in 0..N as i64 {
+            for y in 0..N as i64 {
+                f.write_str(&self.symbol(Coordinates::new(x, y), &path))?;
+            }
+            writeln!(f)?;
+        }
+
+        let keys: Vec<_> = self
+            .inventory()
+            .keys()
+            .map(|key| match key < 26 {
+                true => ((b'a' + key) as char).to_string(),
+                false => key.to_string(),
+            })
+            .collect();
+        write!(
+            f,
+            "keys: {} | items: {}/{} | moves: {}{}",
+            if keys.is_empty() {
+                "-".to_string()
+            } else {
+                keys.join(" ")
+            },
+            self.inventory().items(),
+            self.items(),
+            self.path.len() - 1,
+            if self.is_solved(&self.state) {
+                ", arrived!"
+            } else {
+                ""
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        agent::Agent,
+        environment::Environment,
+        maze::{
+            agent::{astar::AStarFinder, uniform_cost::UniformCostFinder},
+            coordinates::{Coordinates, Direction},
+            generator::{ObjectGenerator, WalkGenerator},
+            placement::Placer,
+            Ground, Maze,
+        },
+        render::FrameWriter,
+        search::solve,
+        simulator::Simulator,
+    };
+
+    use super::{Inventory, KeyFinder, KeyMaze, KeyMazeError};
+
+    /// Rows of the maze, `#` blocked, `A` a door, `a` its key and `*` an
+    /// item.
+    fn parse<const N: usize>(rows: [&str; N]) -> Maze<N> {
+        let mut cells = [[Ground::Free; N]; N];
+        let mut items = 0;
+
+        for (x, row) in rows.iter().enumerate() {
+            for (y, cell) in row.bytes().enumerate() {
+                cells[x][y] = match cell {
+                    b'#' => Ground::Blocked,
+                    b'A'..=b'Z' => Ground::Door(cell - b'A'),
+                    b'a'..=b'z' => Ground::Key(cell - b'a'),
+                    b'*' => {
+                        items += 1;
+                        Ground::Item(items - 1)
+                    }
+                    _ => Ground::Free,
+                };
+            }
+        }
+
+        Maze::from_cells(Coordinates::new(0, 0), Coordinates::new(0, 4), cells)
+    }
+
+    #[test]
+    fn test_key_maze() {
+        let maze = parse(["..A..", ".####", "..a.*", "#####", "#####"]);
+
+        // The door cuts the target off without its key
+        assert!(solve::<_, AStarFinder>(&maze).is_none());
+
+        let mut keys = KeyMaze::new(maze);
+        keys.update(Direction::South).unwrap();
+        assert_eq!(keys.update(Direction::South), Err(KeyMazeError::Locked(0)));
+        assert_eq!(keys.update(Direction::West), Err(KeyMazeError::Blocked));
+        keys.reset(None);
+
+        // Down to the key and the item, then back up through the door
+        let astar = solve::<_, AStarFinder>(&keys).unwrap();
+        let ucs = solve::<_, UniformCostFinder>(&keys).unwrap();
+        assert_eq!(astar.cost, 16.0);
+        assert_eq!(ucs.cost, astar.cost);
+        assert!(astar.expanded <= ucs.expanded);
+
+        let finder = KeyFinder::<5>::new(&keys);
+        let mut simulator = Simulator::new(finder, FrameWriter::new(Vec::new()), keys);
+        assert!(simulator.run_episodes(1).unwrap()[0].terminated);
+        let keys = simulator.environment();
+        assert_eq!(keys.inventory().keys().collect::<Vec<_>>(), [0]);
+        assert_eq!(keys.inventory().items(), 1);
+        assert!(keys
+            .to_string()
+            .ends_with("keys: a | items: 1/1 | moves: 16, arrived!"));
+
+        // Keys and items past the capacity are left on the ground
+        let mut inventory = Inventory::default();
+        inventory.pick(Ground::Key(Inventory::CAPACITY));
+        inventory.pick(Ground::Item(u8::MAX));
+        assert_eq!(inventory, Inventory::default());
+        assert!(!inventory.can_enter(Ground::Door(u8::MAX)));
+
+        // Generated mazes have doors, and stay solvable
+        for seed in 0..3 {
+            let generator = ObjectGenerator::new(
+                WalkGenerator::new(Some([seed; 32])),
+                Placer::new(3, 2).with_seed(seed as u64),
+            );
+            let maze = Maze::<16>::with_generator(
+                Coordinates::new(0, 0),
+                Coordinates::new(15, 15),
+                40,
+                seed as u64,
+                generator,
+            );
+            assert!(solve::<_, AStarFinder>(&maze).is_none());
+            let keys = KeyMaze::new(maze);
+            assert_eq!(keys.items(), 2);
+
+            let finder = KeyFinder::<16>::new(&keys);
+            assert!(finder.solution().is_some());
+            let mut simulator = Simulator::new(finder, FrameWriter::new(Vec::new()), keys);
+            assert!(simulator.run_episodes(1).unwrap()[0].terminated);
+        }
+    }
+}
@@ -0,0 +1,125 @@
+//! Conflict-Based Search
+
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use super::{
+    find_conflicts,
+    reservation::{space_time_astar, ReservationTable},
+    sum_of_costs, Conflict, MapfError, MapfSolver, MultiAgentMaze, TimedPath,
+};
+
+/// Node of the constraint tree.
+#[derive(Debug, Clone)]
+struct Node {
+    constraints: Vec<ReservationTable>,
+    paths: Vec<TimedPath>,
+    cost: usize,
+}
+
+/// Conflict-Based Search.
+///
+/// The high level searches a tree whose nodes hold one set of constraints per
+/// agent, expanding nodes by increasing sum of costs. When the paths of a node
+/// conflict, two children are created, each one forbidding the conflicting
+/// cell or move to one of the two agents, whose path is then replanned by
+/// space-time A*.
+#[derive(Debug, Clone, Copy)]
+pub struct ConflictBasedSearch {
+    /// Constraint tree nodes expanded before giving up.
+    pub max_nodes: usize,
+}
+
+impl Default for ConflictBasedSearch {
+    fn default() -> Self {
+        Self { max_nodes: 10_000 }
+    }
+}
+
+impl ConflictBasedSearch {
+    fn replan<const N: usize>(
+        problem: &MultiAgentMaze<N>,
+        mut node: Node,
+        agent: usize,
+    ) -> Option<Node> {
+        let task = problem.tasks()[agent];
+        let table = &node.constraints[agent];
+        let max_time = table.horizon() + N * N;
+
+        node.paths[agent] =
+            space_time_astar(&problem.maze, task.start, task.goal, table, max_time)?;
+        node.cost = sum_of_costs(&node.paths);
+
+        Some(node)
+    }
+}
+
+impl MapfSolver for ConflictBasedSearch {
+    fn solve<const N: usize>(
+        &self,
+        problem: &MultiAgentMaze<N>,
+    ) -> Result<Vec<TimedPath>, MapfError> {
+        let agents = problem.tasks().len();
+
+        let mut root = Node {
+            constraints: vec![ReservationTable::new(); agents],
+            paths: vec![TimedPath::new(Vec::new()); agents],
+            cost: 0,
+        };
+        for agent in 0..agents {
+            root = Self::replan(problem, root, agent).ok_or(MapfError::NoSolution { agent })?;
+        }
+
+        // Nodes are ordered by cost, then by insertion to keep the search FIFO.
+        let mut nodes = vec![root];
+        let mut open = BinaryHeap::from([Reverse((nodes[0].cost, 0))]);
+        // Agent whose path could last not be replanned around a conflict
+        let mut stuck = None;
+
+        for _ in 0..self.max_nodes {
+            let Some(Reverse((_, index))) = open.pop() else {
+                let agent = stuck.expect("the open set only empties when a replan fails");
+                return Err(MapfError::NoSolution { agent });
+            };
+
+            let node = nodes[index].clone();
+
+            let Some(conflict) = find_conflicts(&node.paths).into_iter().next() else {
+                return Ok(node.paths);
+            };
+
+            let children = match conflict {
+                Conflict::Vertex {
+                    agents: (a, b),
+                    position,
+                    time,
+                } => [(a, position, position, time), (b, position, position, time)],
+                Conflict::Edge {
+                    agents: (a, b),
+                    from,
+                    to,
+                    time,
+                } => [(a, from, to, time), (b, to, from, time)],
+            };
+
+            for (agent, from, to, time) in children {
+                let mut child = node.clone();
+
+                if from == to {
+                    child.constraints[agent].reserve_vertex(to, time);
+                } else {
+                    child.constraints[agent].reserve_move(from, to, time);
+                }
+
+                match Self::replan(problem, child, agent) {
+                    Some(child) => {
+                        open.push(Reverse((child.cost, nodes.len())));
+                        nodes.push(child);
+                    }
+                    None => stuck = Some(agent),
+                }
+            }
+        }
+
+        Err(MapfError::LimitReached)
+    }
+}
@@ -0,0 +1,483 @@
+//! Multi-agent pathfinding
+//!
+//! Several agents share a [`Maze`], each one with its own start and goal. Time
+//! is discrete: at every tick each agent either waits or moves to an adjacent
+//! free cell, and after reaching its goal an agent stays there. A solution is
+//! a set of [`TimedPath`]s free of [`Conflict`]s:
+//!
+//! - a vertex conflict happens when two agents occupy the same cell at the
+//!   same time;
+//! - an edge conflict happens when two agents swap cells during the same tick.
+//!
+//! Solvers implement [`MapfSolver`]:
+//!
+//! - [`PrioritizedPlanner`]: plans agents one at a time, farthest first,
+//!   retrying with shuffled priorities when an agent gets stuck.
+//! - [`CooperativeAStar`]: plans agents in index order against a shared
+//!   reservation table.
+//! - [`ConflictBasedSearch`]: optimal (sum of costs) search over a tree of
+//!   constraints, resolving one conflict per node.
+
+use std::collections::HashSet;
+
+use crate::{agent::Agent, environment::Environment};
+
+use super::{
+    coordinates::{Coordinates, Direction},
+    Ground, Maze,
+};
+
+pub mod cbs;
+pub mod prioritized;
+pub mod reservation;
+
+pub use cbs::ConflictBasedSearch;
+pub use prioritized::{CooperativeAStar, PrioritizedPlanner};
+pub use reservation::ReservationTable;
+
+/// Start and goal of a single agent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Task {
+    pub start: Coordinates,
+    pub goal: Coordinates,
+}
+
+impl Task {
+    pub fn new(start: Coordinates, goal: Coordinates) -> Self {
+        Self { start, goal }
+    }
+}
+
+/// Errors of multi-agent pathfinding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapfError {
+    /// The start or goal of an agent is outside the maze, blocked, or shared
+    /// with another agent.
+    InvalidTask { agent: usize },
+    /// No collision-free path was found for an agent.
+    NoSolution { agent: usize },
+    /// The solver gave up after expanding too many nodes.
+    LimitReached,
+    /// An agent tried to walk into a wall or jump over cells.
+    InvalidMove { agent: usize },
+}
+
+impl std::fmt::Display for MapfError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidTask { agent } => write!(f, "invalid start or goal for agent {agent}"),
+            Self::NoSolution { agent } => write!(f, "no collision-free path for agent {agent}"),
+            Self::LimitReached => f.write_str("search limit reached"),
+            Self::InvalidMove { agent } => write!(f, "invalid move for agent {agent}"),
+        }
+    }
+}
+
+impl std::error::Error for MapfError {}
+
+/// Positions of an agent indexed by time.
+///
+/// Once the path is over the agent stays at its last position.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TimedPath(Vec<Coordinates>);
+
+impl TimedPath {
+    pub fn new(positions: Vec<Coordinates>) -> Self {
+        Self(positions)
+    }
+
+    pub fn get(&self) -> &[Coordinates] {
+        &self.0
+    }
+
+    /// Position of the agent at a given time.
+    ///
+    /// # Panics
+    /// Panics if the path is empty.
+    pub fn at(&self, time: usize) -> Coordinates {
+        self.0[time.min(self.0.len() - 1)]
+    }
+
+    /// Arrival time at the last position.
+    pub fn cost(&self) -> usize {
+        self.0.len().saturating_sub(1)
+    }
+}
+
+/// Collision between two agents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Conflict {
+    /// Both agents are at `position` at `time`.
+    Vertex {
+        agents: (usize, usize),
+        position: Coordinates,
+        time: usize,
+    },
+    /// The first agent moves `from -> to` while the second moves `to -> from`,
+    /// both arriving at `time`.
+    Edge {
+        agents: (usize, usize),
+        from: Coordinates,
+        to: Coordinates,
+        time: usize,
+    },
+}
+
+/// Finds every conflict among a set of timed paths, ordered by time.
+pub fn find_conflicts(paths: &[TimedPath]) -> Vec<Conflict> {
+    let horizon = paths.iter().map(|path| path.get().len()).max().unwrap_or(0);
+    let mut conflicts = Vec::new();
+
+    for time in 0..horizon {
+        for a in 0..paths.len() {
+            for b in a + 1..paths.len() {
+                let (a_now, b_now) = (paths[a].at(time), paths[b].at(time));
+
+                if a_now == b_now {
+                    conflicts.push(Conflict::Vertex {
+                        agents: (a, b),
+                        position: a_now,
+                        time,
+                    });
+                } else if time > 0 {
+                    let (a_before, b_before) = (paths[a].at(time - 1), paths[b].at(time - 1));
+
+                    if a_before == b_now && b_before == a_now {
+                        conflicts.push(Conflict::Edge {
+                            agents: (a, b),
+                            from: a_before,
+                            to: a_now,
+                            time,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    conflicts
+}
+
+/// Sum of the arrival times of every agent.
+pub fn sum_of_costs(paths: &[TimedPath]) -> usize {
+    paths.iter().map(TimedPath::cost).sum()
+}
+
+/// Multi-agent pathfinding algorithm.
+pub trait MapfSolver {
+    /// Finds collision-free timed paths for every agent of the problem.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if no solution was found.
+    fn solve<const N: usize>(
+        &self,
+        problem: &MultiAgentMaze<N>,
+    ) -> Result<Vec<TimedPath>, MapfError>;
+}
+
+/// Move of a single agent during one tick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Move {
+    Wait,
+    Go(Direction),
+}
+
+/// Stimuli produced by a [`MultiAgentMaze`] update.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultiAgentStimuli {
+    pub time: usize,
+    pub positions: Vec<Coordinates>,
+    /// Conflicts caused by the last joint move.
+    pub conflicts: Vec<Conflict>,
+    /// Every agent is at its goal.
+    pub terminal: bool,
+}
+
+/// Maze shared by several agents, each one with its own [`Task`].
+pub struct MultiAgentMaze<const N: usize> {
+    pub maze: Maze<N>,
+    tasks: Vec<Task>,
+    positions: Vec<Coordinates>,
+    time: usize,
+}
+
+impl<const N: usize> MultiAgentMaze<N> {
+    /// Creates a multi-agent environment.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a start or goal is outside the
+    /// maze, blocked, or shared by two agents.
+    pub fn new(maze: Maze<N>, tasks: Vec<Task>) -> Result<Self, MapfError> {
+        let mut starts = HashSet::new();
+        let mut goals = HashSet::new();
+
+        for (agent, task) in tasks.iter().enumerate() {
+            let free = |coordinates| maze.is_walkable(coordinates);
+
+            if !free(&task.start)
+                || !free(&task.goal)
+                || !starts.insert(task.start)
+                || !goals.insert(task.goal)
+            {
+                return Err(MapfError::InvalidTask { agent });
+            }
+        }
+
+        let positions = tasks.iter().map(|task| task.start).collect();
+
+        Ok(Self {
+            maze,
+            tasks,
+            positions,
+            time: 0,
+        })
+    }
+
+    pub fn tasks(&self) -> &[Task] {
+        &self.tasks
+    }
+
+    /// Current position of every agent.
+    pub fn positions(&self) -> &[Coordinates] {
+        &self.positions
+    }
+
+    /// Ticks since the last reset.
+    pub fn time(&self) -> usize {
+        self.time
+    }
+
+    fn stimuli(&self, conflicts: Vec<Conflict>) -> MultiAgentStimuli {
+        MultiAgentStimuli {
+            time: self.time,
+            positions: self.positions.clone(),
+            conflicts,
+            terminal: self.is_terminal(),
+        }
+    }
+}
+
+impl<const N: usize> Environment for MultiAgentMaze<N> {
+    type Error = MapfError;
+
+    /// One move per agent.
+    type Action = Vec<Move>;
+
+    type Stimuli = MultiAgentStimuli;
+
+    fn initial_stimuli(&self) -> Self::Stimuli {
+        self.stimuli(Vec::new())
+    }
+
+    fn update(&mut self, moves: Self::Action) -> Result<Self::Stimuli, Self::Error> {
+        let mut next = self.positions.clone();
+
+        for (agent, step) in moves.into_iter().enumerate().take(next.len()) {
+            if let Move::Go(direction) = step {
+                next[agent] = next[agent].next(direction);
+
+                if !self.maze.is_walkable(&next[agent]) {
+                    return Err(MapfError::InvalidMove { agent });
+                }
+            }
+        }
+
+        // Conflicts of a one-tick path are found at time 1, shift them to now.
+        let paths: Vec<_> = self
+            .positions
+            .iter()
+            .zip(&next)
+            .map(|(from, to)| TimedPath::new(vec![*from, *to]))
+            .collect();
+        let conflicts = find_conflicts(&paths)
+            .into_iter()
+            .filter_map(|conflict| match conflict {
+                Conflict::Vertex { time: 0, .. } => None,
+                Conflict::Vertex {
+                    agents, position, ..
+                } => Some(Conflict::Vertex {
+                    agents,
+                    position,
+                    time: self.time + 1,
+                }),
+                Conflict::Edge {
+                    agents, from, to, ..
+                } => Some(Conflict::Edge {
+                    agents,
+                    from,
+                    to,
+                    time: self.time + 1,
+                }),
+            })
+            .collect();
+
+        self.positions = next;
+        self.time += 1;
+
+        Ok(self.stimuli(conflicts))
+    }
+
+    fn reset(&mut self, seed: Option<u64>) -> Self::Stimuli {
+        self.maze.reset(seed);
+        self.positions = self.tasks.iter().map(|task| task.start).collect();
+        self.time = 0;
+
+        self.stimuli(Vec::new())
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.positions
+            .iter()
+            .zip(&self.tasks)
+            .all(|(position, task)| *position == task.goal)
+    }
+}
+
+impl<const N: usize> std::fmt::Display for MultiAgentMaze<N> {
+    /// Agents are drawn as upper case letters and their goals as lower case
+    /// ones.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = |agent: usize| (b'A' + (agent % 26) as u8) as char;
+
+        for x in 0..N {
+            for y in 0..N {
+                let cell = Coordinates::new(x as i64, y as i64);
+
+                let symbol = if let Some(agent) = self.positions.iter().position(|p| *p == cell) {
+                    label(agent)
+                } else if let Some(agent) = self.tasks.iter().position(|t| t.goal == cell) {
+                    label(agent).to_ascii_lowercase()
+                } else if matches!(self.maze.get(&cell), Some(Ground::Blocked)) {
+                    '◼'
+                } else {
+                    ' '
+                };
+
+                write!(f, "{symbol} ")?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Agent that plans a joint solution with a [`MapfSolver`] and then executes
+/// it one tick at a time.
+#[derive(Debug, Clone)]
+pub struct MapfAgent<const N: usize, S: MapfSolver> {
+    pub plan: Result<Vec<TimedPath>, MapfError>,
+    solver: S,
+    time: usize,
+}
+
+impl<const N: usize, S: MapfSolver> MapfAgent<N, S> {
+    /// Replans with a configured solver.
+    pub fn with_solver(mut self, solver: S, environment: &MultiAgentMaze<N>) -> Self {
+        self.plan = solver.solve(environment);
+        self.solver = solver;
+        self
+    }
+}
+
+impl<const N: usize, S: MapfSolver + Default> Agent for MapfAgent<N, S> {
+    type Error = MapfError;
+
+    type Action = Vec<Move>;
+
+    type Stimuli = MultiAgentStimuli;
+
+    type Environment = MultiAgentMaze<N>;
+
+    fn new(environment: &Self::Environment) -> Self {
+        let solver = S::default();
+
+        Self {
+            plan: solver.solve(environment),
+            solver,
+            time: 0,
+        }
+    }
+
+    fn act(&mut self, environment: &mut Self::Environment) -> Result<(), Self::Error> {
+        let paths = self.plan.as_ref().map_err(|error| *error)?;
+
+        let moves = paths
+            .iter()
+            .enumerate()
+            .map(|(agent, path)| {
+                let (from, to) = (path.at(self.time), path.at(self.time + 1));
+
+                if from == to {
+                    Ok(Move::Wait)
+                } else {
+                    from.direction_to(&to)
+                        .map(Move::Go)
+                        .ok_or(MapfError::InvalidMove { agent })
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        environment.update(moves)?;
+        self.time += 1;
+
+        Ok(())
+    }
+
+    fn should_stop(&self) -> bool {
+        match &self.plan {
+            Ok(paths) => paths.iter().all(|path| self.time >= path.cost()),
+            Err(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::maze::{coordinates::Coordinates, Maze};
+
+    use super::{
+        find_conflicts, sum_of_costs, ConflictBasedSearch, CooperativeAStar, MapfSolver,
+        MultiAgentMaze, PrioritizedPlanner, Task, TimedPath,
+    };
+
+    fn swap() -> MultiAgentMaze<3> {
+        let maze = Maze::<3>::new(Coordinates::new(0, 0), Coordinates::new(2, 2), 0);
+
+        MultiAgentMaze::new(
+            maze,
+            vec![
+                Task::new(Coordinates::new(0, 0), Coordinates::new(2, 0)),
+                Task::new(Coordinates::new(2, 0), Coordinates::new(0, 0)),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_find_conflicts() {
+        let a = TimedPath::new(vec![Coordinates::new(0, 0), Coordinates::new(1, 0)]);
+        let b = TimedPath::new(vec![Coordinates::new(1, 0), Coordinates::new(0, 0)]);
+        let c = TimedPath::new(vec![Coordinates::new(2, 0), Coordinates::new(1, 0)]);
+
+        assert_eq!(find_conflicts(&[a.clone(), b]).len(), 1);
+        assert_eq!(find_conflicts(&[a, c]).len(), 1);
+    }
+
+    #[test]
+    fn test_solvers_avoid_conflicts() {
+        let problem = swap();
+
+        let cbs = ConflictBasedSearch::default().solve(&problem).unwrap();
+        assert!(find_conflicts(&cbs).is_empty());
+        assert_eq!(sum_of_costs(&cbs), 6);
+
+        let prioritized = PrioritizedPlanner::default().solve(&problem).unwrap();
+        assert!(find_conflicts(&prioritized).is_empty());
+
+        let cooperative = CooperativeAStar.solve(&problem).unwrap();
+        assert!(find_conflicts(&cooperative).is_empty());
+    }
+}
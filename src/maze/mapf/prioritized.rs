@@ -0,0 +1,101 @@
+//! Prioritized planning and Cooperative A*
+
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaChaRng;
+
+use super::{
+    reservation::{distances, space_time_astar, ReservationTable},
+    MapfError, MapfSolver, MultiAgentMaze, TimedPath,
+};
+
+/// Plans agents one by one in the given order, each one avoiding the paths of
+/// the agents planned before it.
+fn plan_in_order<const N: usize>(
+    problem: &MultiAgentMaze<N>,
+    order: &[usize],
+) -> Result<Vec<TimedPath>, MapfError> {
+    let mut table = ReservationTable::new();
+    let mut paths = vec![None; order.len()];
+
+    for &agent in order {
+        let task = problem.tasks()[agent];
+        let max_time = table.horizon() + N * N;
+
+        let path = space_time_astar(&problem.maze, task.start, task.goal, &table, max_time)
+            .ok_or(MapfError::NoSolution { agent })?;
+
+        table.reserve_path(&path);
+        paths[agent] = Some(path);
+    }
+
+    Ok(paths.into_iter().flatten().collect())
+}
+
+/// Cooperative A*: agents are planned in index order against a reservation
+/// table holding the paths of the agents planned before them.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CooperativeAStar;
+
+impl MapfSolver for CooperativeAStar {
+    fn solve<const N: usize>(
+        &self,
+        problem: &MultiAgentMaze<N>,
+    ) -> Result<Vec<TimedPath>, MapfError> {
+        let order: Vec<_> = (0..problem.tasks().len()).collect();
+
+        plan_in_order(problem, &order)
+    }
+}
+
+/// Prioritized planning: agents with longer shortest paths get higher
+/// priority. When an agent can't be planned the priorities are shuffled and
+/// planning starts over, up to `restarts` times.
+#[derive(Debug, Clone, Copy)]
+pub struct PrioritizedPlanner {
+    pub restarts: usize,
+    pub seed: Option<u64>,
+}
+
+impl Default for PrioritizedPlanner {
+    fn default() -> Self {
+        Self {
+            restarts: 10,
+            seed: None,
+        }
+    }
+}
+
+impl MapfSolver for PrioritizedPlanner {
+    fn solve<const N: usize>(
+        &self,
+        problem: &MultiAgentMaze<N>,
+    ) -> Result<Vec<TimedPath>, MapfError> {
+        let mut order: Vec<_> = (0..problem.tasks().len()).collect();
+
+        let mut lengths = Vec::with_capacity(order.len());
+        for (agent, task) in problem.tasks().iter().enumerate() {
+            let length = *distances(&problem.maze, task.goal)
+                .get(&task.start)
+                .ok_or(MapfError::NoSolution { agent })?;
+            lengths.push(length);
+        }
+        order.sort_by_key(|agent| std::cmp::Reverse(lengths[*agent]));
+
+        let mut rng = match self.seed {
+            None => ChaChaRng::from_entropy(),
+            Some(seed) => ChaChaRng::seed_from_u64(seed),
+        };
+
+        let mut result = plan_in_order(problem, &order);
+        for _ in 0..self.restarts {
+            if result.is_ok() {
+                break;
+            }
+
+            order.shuffle(&mut rng);
+            result = plan_in_order(problem, &order);
+        }
+
+        result
+    }
+}
@@ -0,0 +1,169 @@
+//! Reservation table and space-time A*
+
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, HashSet, VecDeque},
+};
+
+use crate::maze::{coordinates::Coordinates, Maze};
+
+use super::TimedPath;
+
+/// Cells and moves an agent is not allowed to use at a given time.
+///
+/// It doubles as the reservation table of cooperative planners, where the
+/// paths of already planned agents are reserved, and as the constraint set of
+/// a single agent in [`ConflictBasedSearch`](super::ConflictBasedSearch).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReservationTable {
+    vertices: HashSet<(Coordinates, usize)>,
+    /// Moves `from -> to` arriving at a given time.
+    moves: HashSet<(Coordinates, Coordinates, usize)>,
+    /// Cells occupied forever from a given time, by agents resting at their
+    /// goal.
+    parked: HashMap<Coordinates, usize>,
+    /// Last time each cell is reserved.
+    latest: HashMap<Coordinates, usize>,
+    horizon: usize,
+}
+
+impl ReservationTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forbids being at `position` at `time`.
+    pub fn reserve_vertex(&mut self, position: Coordinates, time: usize) {
+        self.vertices.insert((position, time));
+
+        let latest = self.latest.entry(position).or_default();
+        *latest = (*latest).max(time);
+        self.horizon = self.horizon.max(time);
+    }
+
+    /// Forbids moving `from -> to` arriving at `time`.
+    pub fn reserve_move(&mut self, from: Coordinates, to: Coordinates, time: usize) {
+        self.moves.insert((from, to, time));
+        self.horizon = self.horizon.max(time);
+    }
+
+    /// Reserves every cell of a path and the opposite of every move, so that
+    /// later agents can neither meet nor swap with it. The last cell stays
+    /// reserved forever.
+    pub fn reserve_path(&mut self, path: &TimedPath) {
+        for (time, position) in path.get().iter().enumerate() {
+            self.reserve_vertex(*position, time);
+
+            if time > 0 {
+                self.reserve_move(*position, path.at(time - 1), time);
+            }
+        }
+
+        if let Some(last) = path.get().last() {
+            self.parked.insert(*last, path.cost());
+        }
+    }
+
+    /// Checks if moving `from -> to` arriving at `time` is allowed.
+    pub fn is_free(&self, from: Coordinates, to: Coordinates, time: usize) -> bool {
+        !self.vertices.contains(&(to, time))
+            && !self.moves.contains(&(from, to, time))
+            && self.parked.get(&to).is_none_or(|since| time < *since)
+    }
+
+    /// Earliest time from which an agent may rest at `position` forever.
+    pub fn rest_from(&self, position: Coordinates) -> usize {
+        self.latest.get(&position).map_or(0, |time| time + 1)
+    }
+
+    /// Latest reserved time.
+    pub fn horizon(&self) -> usize {
+        self.horizon
+    }
+}
+
+/// Distance from every cell to `goal`, walking through free cells.
+pub fn distances<const N: usize>(maze: &Maze<N>, goal: Coordinates) -> HashMap<Coordinates, usize> {
+    let mut distances = HashMap::from([(goal, 0)]);
+    let mut queue = VecDeque::from([goal]);
+
+    while let Some(current) = queue.pop_front() {
+        let distance = distances[&current];
+
+        for direction in maze.neighbors(current) {
+            let next = current.next(direction);
+
+            if let Entry::Vacant(entry) = distances.entry(next) {
+                entry.insert(distance + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    distances
+}
+
+/// A* over `(cell, time)` states, where an agent may wait or move to an
+/// adjacent free cell every tick, avoiding the reservations of `table`.
+///
+/// Returns the shortest timed path that ends at `goal` and can rest there
+/// forever, or `None` if no such path exists within `max_time` ticks.
+pub fn space_time_astar<const N: usize>(
+    maze: &Maze<N>,
+    start: Coordinates,
+    goal: Coordinates,
+    table: &ReservationTable,
+    max_time: usize,
+) -> Option<TimedPath> {
+    let heuristic = distances(maze, goal);
+    let rest_from = table.rest_from(goal);
+
+    let mut open = BinaryHeap::new();
+    let mut parents: HashMap<(Coordinates, usize), (Coordinates, usize)> = HashMap::new();
+    let mut closed = HashSet::new();
+
+    open.push(Reverse((*heuristic.get(&start)?, 0, start)));
+
+    while let Some(Reverse((_, time, position))) = open.pop() {
+        if !closed.insert((position, time)) {
+            continue;
+        }
+
+        if position == goal && time >= rest_from {
+            let mut path = vec![position];
+            let mut current = (position, time);
+
+            while let Some(parent) = parents.get(&current) {
+                path.push(parent.0);
+                current = *parent;
+            }
+
+            path.reverse();
+            return Some(TimedPath::new(path));
+        }
+
+        if time >= max_time {
+            continue;
+        }
+
+        let moves = maze
+            .neighbors(position)
+            .into_iter()
+            .map(|dir| position.next(dir));
+
+        for next in std::iter::once(position).chain(moves) {
+            let state = (next, time + 1);
+
+            if closed.contains(&state) || !table.is_free(position, next, time + 1) {
+                continue;
+            }
+
+            if let Some(h) = heuristic.get(&next) {
+                parents.entry(state).or_insert((position, time));
+                open.push(Reverse((time + 1 + h, time + 1, next)));
+            }
+        }
+    }
+
+    None
+}
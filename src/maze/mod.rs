@@ -3,18 +3,78 @@
 //! A maze consists in a matrix of 0's and 1's, where 0's indicate
 //! the absence of a wall and 1's indicate that this position is blocked.
 
-use rand::Rng;
+use std::{collections::HashSet, sync::Arc};
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaChaRng;
 use tabled::settings::Style;
 use tabled::tables::IterTable;
 
 use self::coordinates::{Coordinates, Direction};
-use self::generator::RbGenerator;
+use self::environment::MazeStimuli;
+use self::generator::{Generator, RbGenerator};
+pub use self::path::Path;
+use crate::environment::Environment;
 
 pub mod agent;
+pub mod animation;
+pub mod any_angle;
 pub mod coordinates;
 pub mod environment;
+pub mod file;
 pub mod generator;
+pub mod grid_world;
+pub mod gym;
+pub mod image;
+pub mod inventory;
+pub mod mapf;
+pub mod path;
+pub mod placement;
+pub mod pursuit;
 pub mod render;
+pub mod text;
+pub mod tui;
+#[cfg(feature = "web")]
+pub mod web;
+
+/// Sides of the mazes that can be chosen at runtime, see [`with_size!`].
+pub const SIZES: [usize; 10] = [8, 10, 16, 20, 30, 32, 40, 50, 64, 100];
+
+/// Evaluates `$body` with the constant `$n` set to `$size`, which must be
+/// one of [`SIZES`]. Mazes are const generic, this is how sides only known at
+/// runtime pick their type.
+///
+/// ```
+/// use maze::maze::{with_size, Maze, coordinates::Coordinates};
+///
+/// let size = 16;
+/// let density = with_size!(size, N => {
+///     Maze::<N>::with_seed(Coordinates::new(0, 0), Coordinates::new(N as i64 - 1, 0), 20, 1)
+///         .density()
+/// });
+/// assert_eq!(density, 20);
+/// ```
+#[macro_export]
+macro_rules! with_size {
+    ($size:expr, $n:ident => $body:expr) => {
+        $crate::with_size!(@sides $size, $n => $body; 8 10 16 20 30 32 40 50 64 100)
+    };
+    (@sides $size:expr, $n:ident => $body:expr; $($side:literal)*) => {
+        match $size {
+            $(
+                $side => {
+                    const $n: usize = $side;
+                    $body
+                }
+            )*
+            size => unreachable!("unsupported maze size {size}"),
+        }
+    };
+}
+pub use crate::with_size;
+
+/// Generates a maze again from another seed.
+type Regenerate<const N: usize> = Arc<dyn Fn(&Maze<N>, u64) -> Maze<N> + Send + Sync>;
 
 /// Maze consists in a square matrix with obstacles.
 ///
@@ -22,17 +82,31 @@ pub mod render;
 /// The algorithm works as follows:
 ///
 /// 1. Choose a starting point in the field and make it the current cell
-/// 2. Randomly choose a direction, check if the field in that direction has not
-/// yet been visited.     If that is the case, make the cell in that direction
-/// the new current cell and carve a passage between the two. 3. If all adjacent
-/// fields have been visited, back up to the last field with unvisited
-/// neighbors. 4. The algorithm terminates when it has backed up all the way to
-/// the starting point.
+/// 2. Randomly choose a direction, check if the field in that direction has
+///    not yet been visited. If that is the case, make the cell in that
+///    direction the new current cell and carve a passage between the two.
+/// 3. If all adjacent fields have been visited, back up to the last field
+///    with unvisited neighbors.
+/// 4. The algorithm terminates when it has backed up all the way to the
+///    starting point.
 pub struct Maze<const N: usize> {
     pub initial_position: Coordinates,
     pub target_position: Coordinates,
     pub current_path: Path,
+    density: u8,
     maze: [[Ground; N]; N],
+    /// Paths of the agents sharing the maze, see [`Maze::agent_paths`].
+    agent_paths: Vec<Path>,
+    active_agent: usize,
+    /// Paths of the agents when a simultaneous tick began, until it ends.
+    tick_paths: Option<Vec<Path>>,
+    trace: SearchTrace,
+    /// Whether the trace keeps the frontier, see [`Maze::with_frontier_trace`].
+    trace_frontier: bool,
+    connectivity: Connectivity,
+    /// How a seeded [`Environment::reset`] generates the maze again, `None`
+    /// for mazes given cell by cell.
+    regenerate: Option<Regenerate<N>>,
 }
 
 impl<const N: usize> Maze<N> {
@@ -41,17 +115,131 @@ impl<const N: usize> Maze<N> {
     /// # Panics
     /// Panics if the density is not higher than 100.
     pub fn new(initial_position: Coordinates, target_position: Coordinates, density: u8) -> Self {
+        Self::generate(
+            initial_position,
+            target_position,
+            density,
+            &mut rand::thread_rng(),
+            RbGenerator::new(None),
+        )
+    }
+
+    /// Creates a new maze whose layout is fully determined by `seed`.
+    ///
+    /// # Panics
+    /// Panics if the density is not higher than 100.
+    pub fn with_seed(
+        initial_position: Coordinates,
+        target_position: Coordinates,
+        density: u8,
+        seed: u64,
+    ) -> Self {
+        let mut rng = ChaChaRng::seed_from_u64(seed);
+        let generator = RbGenerator::new(Some(rng.gen()));
+
+        Self::generate(
+            initial_position,
+            target_position,
+            density,
+            &mut rng,
+            generator,
+        )
+    }
+
+    /// Creates a new maze whose blocks are drawn from `seed`, then carved by
+    /// `generator`.
+    ///
+    /// # Panics
+    /// Panics if the density is not higher than 100.
+    pub fn with_generator<G>(
+        initial_position: Coordinates,
+        target_position: Coordinates,
+        density: u8,
+        seed: u64,
+        generator: G,
+    ) -> Self
+    where
+        G: Generator + Clone + Send + Sync + 'static,
+    {
+        let mut maze = Self::generate(
+            initial_position,
+            target_position,
+            density,
+            &mut ChaChaRng::seed_from_u64(seed),
+            generator.clone(),
+        );
+
+        // Other seeds reseed the generator as `with_seed` does
+        maze.regenerate = Some(Arc::new(move |maze: &Self, seed| {
+            let mut rng = ChaChaRng::seed_from_u64(seed);
+            let generator = generator.reseeded(rng.gen());
+
+            Self::generate(
+                maze.initial_position,
+                maze.target_position,
+                maze.density,
+                &mut rng,
+                generator,
+            )
+        }));
+
+        maze
+    }
+
+    /// Creates a maze with the given cells, indexed `cells[x][y]`. The
+    /// initial and target positions are freed.
+    ///
+    /// # Panics
+    /// Panics if the initial or target position is outside of the maze.
+    pub fn from_cells(
+        initial_position: Coordinates,
+        target_position: Coordinates,
+        cells: [[Ground; N]; N],
+    ) -> Self {
+        let blocked = cells
+            .iter()
+            .flatten()
+            .filter(|ground| **ground == Ground::Blocked)
+            .count();
+
+        let mut maze = Self {
+            initial_position,
+            target_position,
+            current_path: Path::new(initial_position),
+            density: (blocked * 100 / (N * N).max(1)) as u8,
+            maze: cells,
+            agent_paths: Vec::new(),
+            active_agent: 0,
+            tick_paths: None,
+            trace: SearchTrace::default(),
+            trace_frontier: false,
+            connectivity: Connectivity::default(),
+            regenerate: None,
+        };
+
+        for position in [initial_position, target_position] {
+            *maze
+                .get_mut(&position)
+                .expect("initial and target positions must be inside the maze") = Ground::Free;
+        }
+
+        maze
+    }
+
+    fn generate(
+        initial_position: Coordinates,
+        target_position: Coordinates,
+        density: u8,
+        rng: &mut impl Rng,
+        generator: impl Generator,
+    ) -> Self {
         assert!(density <= 100);
 
         // Just a naive way to initalize the matrix
-        let mut rng = rand::thread_rng();
-
         let mut maze: [[Ground; N]; N] = [[Ground::Free; N]; N];
 
-        (0..N).for_each(|i| {
-            for j in 0..N {
-                maze[i][j] = rng.gen_ratio(density as u32, 100).into();
-            }
+        maze.iter_mut().flatten().for_each(|ground| {
+            *ground = rng.gen_ratio(density as u32, 100).into();
         });
 
         // Makes sure that the initial and target position dont not have an obstacle
@@ -61,23 +249,121 @@ impl<const N: usize> Maze<N> {
         let maze = Self {
             initial_position,
             target_position,
-            current_path: Path::new(vec![initial_position]),
+            current_path: Path::new(initial_position),
+            density,
             maze,
+            agent_paths: Vec::new(),
+            active_agent: 0,
+            tick_paths: None,
+            trace: SearchTrace::default(),
+            trace_frontier: false,
+            connectivity: Connectivity::default(),
+            regenerate: Some(Arc::new(|maze: &Self, seed| {
+                Self::with_seed(
+                    maze.initial_position,
+                    maze.target_position,
+                    maze.density,
+                    seed,
+                )
+            })),
         };
 
-        maze.carve()
+        maze.carve(generator)
     }
 
     /// Enables the maze by carving cells from target to initial position.
-    fn carve(mut self) -> Self {
-        let mut gen = RbGenerator::new(None);
-
+    fn carve(mut self, mut gen: impl Generator) -> Self {
         let goal = self.target_position;
-        gen.carve(&mut self, goal);
+        gen.generate(&mut self, goal);
+
+        self
+    }
+
+    /// Current path of every agent sharing the maze, indexed by agent.
+    ///
+    /// A maze acted upon by a single agent has just its current path. During
+    /// a [`Resolution::Simultaneous`](crate::environment::Resolution) tick,
+    /// the agents keep the paths they began the tick with until it ends.
+    pub fn agent_paths(&self) -> Vec<&Path> {
+        let mut paths: Vec<_> = self.agent_paths.iter().collect();
+
+        if self.active_agent < paths.len() {
+            paths[self.active_agent] = &self.current_path;
+        } else {
+            paths.push(&self.current_path);
+        }
+
+        if let Some(tick_paths) = &self.tick_paths {
+            paths
+                .iter_mut()
+                .zip(tick_paths)
+                .for_each(|(path, tick_path)| *path = tick_path);
+        }
+
+        paths
+    }
+
+    /// What the searches run on the maze explored so far.
+    pub fn trace(&self) -> &SearchTrace {
+        &self.trace
+    }
+
+    /// Records that a search expanded `position`, leaving `frontier` to be
+    /// explored. Agents sharing the maze record into the same trace.
+    pub fn record_expansion(
+        &mut self,
+        position: Coordinates,
+        frontier: impl IntoIterator<Item = Coordinates>,
+    ) {
+        self.trace.steps += 1;
+        if self.trace.visited.insert(position) {
+            self.trace.order.push(position);
+        }
+        // Reuses the buffer of the previous frontier
+        self.trace.frontier.clear();
+        if self.trace_frontier {
+            self.trace.frontier.extend(frontier);
+        }
+    }
 
+    /// Keeps the frontier of the searches in the trace, or not.
+    ///
+    /// The frontier is copied at every expansion, which dominates large
+    /// searches, so only the mazes whose frontier is rendered should keep
+    /// it. Not kept by default.
+    pub fn with_frontier_trace(mut self, trace_frontier: bool) -> Self {
+        self.trace_frontier = trace_frontier;
+        self
+    }
+
+    /// Lets the agents move diagonally, or not.
+    pub fn with_connectivity(mut self, connectivity: Connectivity) -> Self {
+        self.connectivity = connectivity;
         self
     }
 
+    pub fn connectivity(&self) -> Connectivity {
+        self.connectivity
+    }
+
+    /// Percentage of cells initially blocked when the maze was generated.
+    pub fn density(&self) -> u8 {
+        self.density
+    }
+
+    /// Starts over from a new initial position, keeping the layout.
+    ///
+    /// # Panics
+    /// Panics if the position is outside of the maze.
+    pub fn reset_to(&mut self, initial_position: Coordinates) -> MazeStimuli {
+        *self
+            .get_mut(&initial_position)
+            .expect("initial position must be inside the maze") = Ground::Free;
+        self.initial_position = initial_position;
+
+        self.reset(None)
+    }
+
     /// Get the value of the cell if it exists
     pub fn get(&self, coordinates: &Coordinates) -> Option<Ground> {
         if coordinates.x < 0
@@ -104,65 +390,106 @@ impl<const N: usize> Maze<N> {
         Some(&mut self.maze[coordinates.x as usize][coordinates.y as usize])
     }
 
+    /// Whether the cell exists and can be walked on, see [`Ground::is_walkable`].
+    pub fn is_walkable(&self, coordinates: &Coordinates) -> bool {
+        self.get(coordinates).is_some_and(Ground::is_walkable)
+    }
+
     /// Gets the neighbors of a position where  there's a valid path.
+    ///
+    /// With [`Connectivity::Eight`], diagonal moves are valid when both
+    /// cells they pass by are free too, so walls can't be cut across.
     pub fn neighbors(&self, pos: Coordinates) -> Vec<Direction> {
-        [
+        self.neighbors_where(pos, Ground::is_walkable)
+    }
+
+    /// Gets the neighbors of a position whose ground is `passable`, e.g.
+    /// doors whose key is held.
+    pub fn neighbors_where(
+        &self,
+        pos: Coordinates,
+        passable: impl Fn(Ground) -> bool,
+    ) -> Vec<Direction> {
+        let free = |coord: Coordinates| self.get(&coord).is_some_and(&passable);
+
+        let mut neighbors: Vec<_> = [
             Direction::East,
             Direction::North,
             Direction::South,
             Direction::West,
         ]
         .into_iter()
-        .map(|dir| (dir, pos.next(dir)))
-        .filter(|(_, coord)| {
-            if let Some(ground) = self.get(coord) {
-                matches!(ground, Ground::Free)
-            } else {
-                false
-            }
-        })
-        .map(|(dir, _)| dir)
-        .collect()
+        .filter(|dir| free(pos.next(*dir)))
+        .collect();
+
+        if self.connectivity == Connectivity::Eight {
+            neighbors.extend(
+                [
+                    (Direction::Northeast, Direction::East, Direction::North),
+                    (Direction::Northwest, Direction::West, Direction::North),
+                    (Direction::Southeast, Direction::East, Direction::South),
+                    (Direction::Southwest, Direction::West, Direction::South),
+                ]
+                .into_iter()
+                .filter(|(diagonal, first, second)| {
+                    free(pos.next(*diagonal)) && free(pos.next(*first)) && free(pos.next(*second))
+                })
+                .map(|(diagonal, _, _)| diagonal),
+            );
+        }
+
+        neighbors
     }
 }
 
+/// Moves allowed between the cells of a [`Maze`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Connectivity {
+    /// The four cardinal directions.
+    #[default]
+    Four,
+    /// Diagonals too.
+    Eight,
+}
+
 /// Type of ground in a maze.
 ///
-/// A maze can be blocked (meaning a wall) or free (meaning free to pass)
+/// A maze can be blocked (meaning a wall) or free (meaning free to pass).
+/// Puzzle mazes also hold doors, the keys opening them and items to
+/// collect, see [`inventory`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Ground {
     Blocked,
     Free,
     Path,
+    /// Closed until the key with the same index is picked up.
+    Door(u8),
+    Key(u8),
+    Item(u8),
 }
 
-/// Path is a sequence of coordinates
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Path(Vec<Coordinates>);
-
-impl Path {
-    pub fn new(coordinates: Vec<Coordinates>) -> Self {
-        Self(coordinates)
-    }
-
-    pub fn get(&self) -> &[Coordinates] {
-        &self.0
-    }
-
-    pub fn last(&self) -> Coordinates {
-        *self.0.last().unwrap()
-    }
-
-
-    pub fn first(&self) -> Coordinates {
-        *self.0.first().unwrap()
+impl Ground {
+    /// Whether it can be walked on without opening a door.
+    ///
+    /// [`Ground::Path`] is a free cell already walked, only drawn by the
+    /// renderers, so it is walkable too.
+    pub fn is_walkable(self) -> bool {
+        matches!(self, Self::Free | Self::Path | Self::Key(_) | Self::Item(_))
     }
+}
 
-    pub fn walk(&self, dir: Direction) -> Self {
-        let mut new_path = self.0.clone();
-        new_path.push(self.last().next(dir));
-        Self(new_path)
-    }
+/// Cells explored by the searches run on a [`Maze`], kept for rendering.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchTrace {
+    /// Number of expansions.
+    pub steps: usize,
+    /// Cells already expanded.
+    pub visited: HashSet<Coordinates>,
+    /// Cells already expanded, in the order of their first expansion.
+    pub order: Vec<Coordinates>,
+    /// Cells waiting to be expanded, possibly repeated. Empty unless the
+    /// maze keeps the frontier, see [`Maze::with_frontier_trace`].
+    pub frontier: Vec<Coordinates>,
 }
 
 impl From<bool> for Ground {
@@ -181,6 +508,9 @@ impl AsRef<str> for Ground {
             Self::Blocked => "◼ ",
             Self::Free => " ",
             Self::Path => "★ ",
+            Self::Door(_) => "▥ ",
+            Self::Key(_) => "⚷ ",
+            Self::Item(_) => "◆ ",
         }
     }
 }
@@ -189,7 +519,7 @@ impl<const N: usize> std::fmt::Display for Maze<N> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut maze_with_path = self.maze;
 
-        for coord in self.current_path.get() {
+        for coord in self.current_path.rev_iter() {
             maze_with_path[coord.x as usize][coord.y as usize] = Ground::Path;
         }
 
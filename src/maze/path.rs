@@ -0,0 +1,209 @@
+//! Persistent paths
+//!
+//! A [`Path`] is a chain of cells sharing their prefixes: extending a path
+//! allocates a single cell pointing back to the path, whatever its length,
+//! and cloning a path only bumps a reference count. The searches keep many
+//! paths branching off each other, this keeps their memory linear in the
+//! number of expansions.
+
+use std::{fmt, iter, sync::Arc};
+
+use super::coordinates::{Coordinates, Direction};
+
+#[derive(Debug)]
+struct Node {
+    position: Coordinates,
+    parent: Option<Arc<Node>>,
+    /// Number of cells up to this one.
+    len: usize,
+    /// Euclidean length of the steps up to this one.
+    cost: f32,
+}
+
+impl Drop for Node {
+    /// Drops the unshared ancestors one after the other, long chains would
+    /// overflow the stack if dropped recursively.
+    fn drop(&mut self) {
+        let mut parent = self.parent.take();
+
+        while let Some(node) = parent {
+            parent = match Arc::try_unwrap(node) {
+                Ok(mut node) => node.parent.take(),
+                Err(_) => None,
+            };
+        }
+    }
+}
+
+/// Path is a sequence of coordinates, never empty.
+#[derive(Clone)]
+pub struct Path {
+    first: Coordinates,
+    last: Arc<Node>,
+}
+
+impl Path {
+    /// Path of a single cell.
+    pub fn new(start: Coordinates) -> Self {
+        Self {
+            first: start,
+            last: Arc::new(Node {
+                position: start,
+                parent: None,
+                len: 1,
+                cost: 0.0,
+            }),
+        }
+    }
+
+    /// Path going through the cells in order, `None` without cells.
+    pub fn from_cells(cells: impl IntoIterator<Item = Coordinates>) -> Option<Self> {
+        let mut cells = cells.into_iter();
+        let mut path = Self::new(cells.next()?);
+        cells.for_each(|cell| path.push(cell));
+
+        Some(path)
+    }
+
+    pub fn first(&self) -> Coordinates {
+        self.first
+    }
+
+    pub fn last(&self) -> Coordinates {
+        self.last.position
+    }
+
+    /// Number of cells, start and end included.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.last.len
+    }
+
+    /// Euclidean length of the steps of the path.
+    pub fn cost(&self) -> f32 {
+        self.last.cost
+    }
+
+    /// Appends a coordinate to the end of the path.
+    pub fn push(&mut self, coordinates: Coordinates) {
+        *self = self.extend(coordinates);
+    }
+
+    /// The path followed by one more step.
+    pub fn walk(&self, dir: Direction) -> Self {
+        self.extend(self.last().next(dir))
+    }
+
+    fn extend(&self, coordinates: Coordinates) -> Self {
+        Self {
+            first: self.first,
+            last: Arc::new(Node {
+                position: coordinates,
+                parent: Some(Arc::clone(&self.last)),
+                len: self.last.len + 1,
+                cost: self.last.cost + self.last.position.euclidean_dist(&coordinates),
+            }),
+        }
+    }
+
+    /// The cells from the last to the first, without allocating.
+    pub fn rev_iter(&self) -> impl Iterator<Item = Coordinates> + '_ {
+        iter::successors(Some(self.last.as_ref()), |node| node.parent.as_deref())
+            .map(|node| node.position)
+    }
+
+    /// The cells from the first to the last. Cells only point to the
+    /// previous one, so they are collected into a vector first.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = Coordinates> {
+        self.cells().into_iter()
+    }
+
+    /// The cells from the first to the last, in a new vector.
+    pub fn cells(&self) -> Vec<Coordinates> {
+        let mut cells: Vec<_> = self.rev_iter().collect();
+        cells.reverse();
+        cells
+    }
+
+    /// Moves from each cell to the next one, skipping the steps between
+    /// cells that aren't adjacent.
+    pub fn directions(&self) -> Vec<Direction> {
+        self.cells()
+            .windows(2)
+            .filter_map(|step| step[0].direction_to(&step[1]))
+            .collect()
+    }
+
+    /// Whether the path goes through a cell, in time linear in its length.
+    /// Lookups of many cells should collect [`rev_iter`](Self::rev_iter)
+    /// into a set first.
+    pub fn contains(&self, coordinates: &Coordinates) -> bool {
+        self.rev_iter().any(|cell| cell == *coordinates)
+    }
+}
+
+impl PartialEq for Path {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.last, &other.last)
+            || (self.len() == other.len() && self.rev_iter().eq(other.rev_iter()))
+    }
+}
+
+impl Eq for Path {}
+
+impl fmt::Debug for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Path").field(&self.cells()).finish()
+    }
+}
+
+impl IntoIterator for &Path {
+    type Item = Coordinates;
+    type IntoIter = std::vec::IntoIter<Coordinates>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cells().into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::maze::coordinates::{Coordinates, Direction};
+
+    use super::Path;
+
+    #[test]
+    fn test_shared_paths() {
+        let start = Path::new(Coordinates::new(0, 0));
+        let east = start.walk(Direction::East);
+        let south = east.walk(Direction::South);
+        let other = east.walk(Direction::East);
+
+        assert_eq!(start.len(), 1);
+        assert_eq!(south.len(), 3);
+        assert_eq!(south.first(), Coordinates::new(0, 0));
+        assert_eq!(south.last(), Coordinates::new(1, 1));
+        assert_eq!(other.last(), Coordinates::new(2, 0));
+        assert_eq!(south.cost(), 2.0);
+
+        assert_eq!(south.directions(), [Direction::East, Direction::South]);
+        assert!(south.contains(&Coordinates::new(1, 0)));
+        assert!(!south.contains(&Coordinates::new(2, 0)));
+
+        let cells = south.cells();
+        assert_eq!(Path::from_cells(cells.clone()), Some(south.clone()));
+        assert_eq!(Path::from_cells(Vec::new()), None);
+        assert_eq!(
+            south.iter().rev().collect::<Vec<_>>(),
+            south.rev_iter().collect::<Vec<_>>()
+        );
+
+        // Long chains are dropped without recursing
+        let mut long = Path::new(Coordinates::new(0, 0));
+        for step in 0..1_000_000 {
+            long.push(Coordinates::new(step % 2, 0));
+        }
+        assert_eq!(long.len(), 1_000_001);
+        drop(long);
+    }
+}
@@ -0,0 +1,370 @@
+//! Placing keys, doors and items
+//!
+//! Puzzle mazes hide keys opening doors, and items to collect on the way to
+//! the target. Where they go is a [`Csp`] over the free cells of the maze:
+//!
+//! - a door stands on a cell every path from the start to the target goes
+//!   through, so the target can't be reached without opening it;
+//! - the doors follow each other from the start to the target;
+//! - the key of a door can be reached from the start while the door is
+//!   closed, but only once the door before it is open, so the doors are
+//!   opened in order;
+//! - items can be reached from the start, and stand at least `spacing`
+//!   cells apart from each other, the start and the target;
+//! - no two objects share a cell, nor the start or the target.
+//!
+//! ```
+//! use maze::{maze::placement::Placer, prelude::*};
+//!
+//! let maze = Maze::<16>::with_seed(Coordinates::new(0, 0), Coordinates::new(15, 15), 40, 0);
+//! let placement = Placer::new(2, 3).with_seed(7).place(&maze).unwrap();
+//!
+//! assert_eq!(placement.keys.len(), 2);
+//! assert_eq!(placement.items.len(), 3);
+//! ```
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+};
+
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaChaRng;
+
+use crate::csp::{Backtracking, Csp};
+
+use super::{coordinates::Coordinates, inventory::Inventory, Ground, Maze};
+
+/// Cells of the objects, the key of a door at the same index as the door.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placement {
+    /// From the start to the target.
+    pub doors: Vec<Coordinates>,
+    pub keys: Vec<Coordinates>,
+    pub items: Vec<Coordinates>,
+}
+
+impl Placement {
+    /// Sets the ground of the cells to the objects, indexed as placed.
+    ///
+    /// # Panics
+    /// Panics if there are more doors or items than an [`Inventory`] holds.
+    pub fn apply<const N: usize>(&self, maze: &mut Maze<N>) {
+        let capacity = Inventory::CAPACITY.into();
+        assert!(self.doors.len() <= capacity && self.items.len() <= capacity);
+
+        let objects = [
+            (&self.doors, Ground::Door as fn(u8) -> Ground),
+            (&self.keys, Ground::Key),
+            (&self.items, Ground::Item),
+        ];
+        for (cells, ground) in objects {
+            for (index, cell) in cells.iter().enumerate() {
+                if let Some(cell) = maze.get_mut(cell) {
+                    *cell = ground(index as u8);
+                }
+            }
+        }
+    }
+}
+
+/// Places doors, their keys and items in a maze by solving a [`Csp`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Placer {
+    doors: usize,
+    items: usize,
+    spacing: i64,
+    seed: u64,
+}
+
+impl Placer {
+    pub fn new(doors: usize, items: usize) -> Self {
+        Self {
+            doors,
+            items,
+            spacing: 2,
+            seed: 0,
+        }
+    }
+
+    /// Manhattan distance at least between two items, and between an item
+    /// and the start or the target, `2` by default.
+    pub fn with_spacing(mut self, spacing: i64) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Seed of the order the cells are tried in, `0` by default.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// The placement as a constraint satisfaction problem, with the doors
+    /// first, then the keys, then the items.
+    pub fn csp<const N: usize>(&self, maze: &Maze<N>) -> Csp<Coordinates> {
+        let (start, target) = (maze.initial_position, maze.target_position);
+        let cuts = cut_cells(maze, start, target);
+        let depths = depths(maze, &cuts);
+        let mut rng = ChaChaRng::seed_from_u64(self.seed);
+
+        let mut cells: Vec<_> = depths
+            .keys()
+            .copied()
+            .filter(|cell| *cell != start && *cell != target)
+            .collect();
+        cells.sort_unstable();
+        let mut shuffled = |cells: &[Coordinates]| {
+            let mut cells = cells.to_vec();
+            cells.shuffle(&mut rng);
+            cells
+        };
+
+        let mut csp = Csp::new();
+        let doors: Vec<_> = (0..self.doors)
+            .map(|door| csp.add_variable(format!("door {door}"), shuffled(&cuts)))
+            .collect();
+        let keys: Vec<_> = (0..self.doors)
+            .map(|key| csp.add_variable(format!("key {key}"), shuffled(&cells)))
+            .collect();
+        let spaced: Vec<_> = cells
+            .iter()
+            .copied()
+            .filter(|cell| {
+                cell.manhattan_dist(&start) >= self.spacing
+                    && cell.manhattan_dist(&target) >= self.spacing
+            })
+            .collect();
+        let items: Vec<_> = (0..self.items)
+            .map(|item| csp.add_variable(format!("item {item}"), shuffled(&spaced)))
+            .collect();
+
+        let depths = Arc::new(depths);
+        let depth = move |depths: &HashMap<Coordinates, usize>, cell: &Coordinates| depths[cell];
+
+        for pair in doors.windows(2) {
+            let depths = Arc::clone(&depths);
+            csp.add_binary(pair[0], pair[1], move |first, second| {
+                depth(&depths, first) < depth(&depths, second)
+            });
+        }
+        for (index, (door, key)) in doors.iter().zip(&keys).enumerate() {
+            let behind = Arc::clone(&depths);
+            csp.add_binary(*key, *door, move |key, door| {
+                depth(&behind, key) < depth(&behind, door)
+            });
+
+            if index > 0 {
+                let behind = Arc::clone(&depths);
+                csp.add_binary(*key, doors[index - 1], move |key, previous| {
+                    depth(&behind, key) >= depth(&behind, previous)
+                });
+            }
+        }
+        for (index, first) in items.iter().enumerate() {
+            let spacing = self.spacing;
+            for second in &items[index + 1..] {
+                csp.add_binary(*first, *second, move |a, b| a.manhattan_dist(b) >= spacing);
+            }
+        }
+
+        let all: Vec<_> = doors.iter().chain(&keys).chain(&items).copied().collect();
+        csp.add_all_different(&all);
+
+        csp
+    }
+
+    /// Solves the placement, `None` when the maze has no room for the
+    /// objects.
+    pub fn place<const N: usize>(&self, maze: &Maze<N>) -> Option<Placement> {
+        let cells = Backtracking::new().solve(&self.csp(maze))?;
+        let (doors, rest) = cells.split_at(self.doors);
+        let (keys, items) = rest.split_at(self.doors);
+
+        Some(Placement {
+            doors: doors.to_vec(),
+            keys: keys.to_vec(),
+            items: items.to_vec(),
+        })
+    }
+
+    /// Like [`place`](Self::place), with as many of the doors as the maze
+    /// has room for.
+    pub fn place_fitting<const N: usize>(&self, maze: &Maze<N>) -> Option<Placement> {
+        (0..=self.doors)
+            .rev()
+            .find_map(|doors| Self { doors, ..*self }.place(maze))
+    }
+}
+
+/// Cells every path from `start` to `target` goes through, from the start
+/// to the target.
+///
+/// Found with Tarjan's lowpoints on a depth-first search from the start: a
+/// cell separates the start from the target when the target lies in the
+/// subtree of one of its children, and nothing in that subtree leads back
+/// above the cell.
+fn cut_cells<const N: usize>(
+    maze: &Maze<N>,
+    start: Coordinates,
+    target: Coordinates,
+) -> Vec<Coordinates> {
+    let mut discovery = HashMap::from([(start, 0)]);
+    let mut low = HashMap::from([(start, 0)]);
+    let mut cuts = Vec::new();
+    // Cell, its parent and the neighbors left to visit
+    let mut stack = vec![(start, start, maze.neighbors(start))];
+
+    while let Some((cell, parent, neighbors)) = stack.last_mut() {
+        let (cell, parent) = (*cell, *parent);
+
+        if let Some(direction) = neighbors.pop() {
+            let next = cell.next(direction);
+
+            match discovery.get(&next) {
+                Some(seen) => {
+                    if next != parent {
+                        let seen = *seen;
+                        low.entry(cell).and_modify(|low| *low = seen.min(*low));
+                    }
+                }
+                None => {
+                    let time = discovery.len();
+                    discovery.insert(next, time);
+                    low.insert(next, time);
+                    stack.push((next, cell, maze.neighbors(next)));
+                }
+            }
+            continue;
+        }
+
+        stack.pop();
+        if cell == start {
+            continue;
+        }
+
+        let cell_low = low[&cell];
+        low.entry(parent)
+            .and_modify(|low| *low = cell_low.min(*low));
+
+        // The target is in the subtree of `cell` when it was discovered
+        // since `cell`
+        let below = discovery
+            .get(&target)
+            .is_some_and(|time| *time >= discovery[&cell]);
+        if parent != start && cell_low >= discovery[&parent] && below {
+            cuts.push(parent);
+        }
+    }
+
+    cuts.sort_by_key(|cell| discovery[cell]);
+    cuts.dedup();
+
+    cuts
+}
+
+/// Number of cut cells passed on the way from the start to every reachable
+/// cell, counting the cell itself.
+fn depths<const N: usize>(maze: &Maze<N>, cuts: &[Coordinates]) -> HashMap<Coordinates, usize> {
+    let start = maze.initial_position;
+    let order: HashMap<_, _> = cuts
+        .iter()
+        .enumerate()
+        .map(|(index, cell)| (*cell, index + 1))
+        .collect();
+
+    let mut depths = HashMap::from([(start, 0)]);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(cell) = queue.pop_front() {
+        for direction in maze.neighbors(cell) {
+            let next = cell.next(direction);
+
+            if !depths.contains_key(&next) {
+                let depth = order.get(&next).copied().unwrap_or(depths[&cell]);
+                depths.insert(next, depth);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    depths
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashSet, VecDeque};
+
+    use crate::maze::{coordinates::Coordinates, Ground, Maze};
+
+    use super::{cut_cells, Placer};
+
+    /// Cells reachable from the start without going through `closed`.
+    fn reachable<const N: usize>(maze: &Maze<N>, closed: &[Coordinates]) -> HashSet<Coordinates> {
+        let mut seen = HashSet::from([maze.initial_position]);
+        let mut queue = VecDeque::from([maze.initial_position]);
+
+        while let Some(cell) = queue.pop_front() {
+            for direction in maze.neighbors(cell) {
+                let next = cell.next(direction);
+                if !closed.contains(&next) && seen.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        seen
+    }
+
+    #[test]
+    fn test_placement() {
+        let start = Coordinates::new(0, 0);
+        let target = Coordinates::new(15, 15);
+        let maze = Maze::<16>::with_seed(start, target, 40, 0);
+
+        // Closing any cut cell cuts the target off, and only them
+        let cuts = cut_cells(&maze, start, target);
+        assert!(!cuts.is_empty());
+        for cell in reachable(&maze, &[]) {
+            let separates = !reachable(&maze, &[cell]).contains(&target);
+            assert_eq!(
+                separates && cell != start && cell != target,
+                cuts.contains(&cell)
+            );
+        }
+
+        for seed in 0..3 {
+            let placement = Placer::new(3, 4)
+                .with_spacing(3)
+                .with_seed(seed)
+                .place(&maze)
+                .unwrap();
+            let doors = &placement.doors;
+
+            // Each door holds the next ones, and its key opens it
+            assert!(!reachable(&maze, &doors[..1]).contains(&target));
+            for (index, key) in placement.keys.iter().enumerate() {
+                assert!(reachable(&maze, &doors[index..]).contains(key));
+                if index > 0 {
+                    assert!(!reachable(&maze, &doors[index - 1..]).contains(key));
+                }
+            }
+            for (index, item) in placement.items.iter().enumerate() {
+                assert!(item.manhattan_dist(&start) >= 3);
+                for other in &placement.items[index + 1..] {
+                    assert!(item.manhattan_dist(other) >= 3);
+                }
+            }
+        }
+
+        // A maze without a chokepoint has no room for a door
+        let mut open = Maze::<8>::with_seed(start, Coordinates::new(7, 7), 0, 0);
+        for x in 0..8 {
+            for y in 0..8 {
+                *open.get_mut(&Coordinates::new(x, y)).unwrap() = Ground::Free;
+            }
+        }
+        assert_eq!(Placer::new(1, 0).place(&open), None);
+        assert_eq!(Placer::new(0, 2).place(&open).unwrap().items.len(), 2);
+    }
+}
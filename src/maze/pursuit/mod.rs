@@ -0,0 +1,456 @@
+//! Pursuit-evasion game
+//!
+//! One evader and one or more pursuers move in turns on a [`Maze`]: first the
+//! evader, then every pursuer in order. The pursuers win by stepping on the
+//! evader, the evader wins by surviving a fixed number of moves.
+//!
+//! Game values are always taken from the evader's point of view: the evader
+//! maximizes them and the pursuers minimize them. [`AdversarialAgent`] plays
+//! whichever player's turn it is, choosing moves with an
+//! [`AdversarialSearch`] strategy from [`search`].
+
+use rand::seq::SliceRandom;
+
+use crate::{
+    agent::Agent,
+    environment::{Environment, SharedEnvironment},
+};
+
+use super::{coordinates::Coordinates, mapf::Move, Ground, Maze};
+
+pub mod search;
+
+pub use search::{AdversarialSearch, AlphaBeta, Evaluation, Expectimax, Minimax};
+
+/// Errors of the pursuit-evasion game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PursuitError {
+    /// The acting agent doesn't control the player whose turn it is.
+    NotYourTurn,
+    /// The move leads into a wall or out of the maze.
+    InvalidMove,
+    /// The game is already over.
+    GameOver,
+}
+
+impl std::fmt::Display for PursuitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::NotYourTurn => "it is not this agent's turn",
+            Self::InvalidMove => "invalid move",
+            Self::GameOver => "the game is over",
+        })
+    }
+}
+
+impl std::error::Error for PursuitError {}
+
+/// Positions of every player and whose turn it is.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GameState {
+    pub evader: Coordinates,
+    pub pursuers: Vec<Coordinates>,
+    /// Player to move: `0` is the evader, `i > 0` is pursuer `i - 1`.
+    pub turn: usize,
+    /// Moves played so far.
+    pub moves: usize,
+}
+
+impl GameState {
+    /// Checks if a pursuer caught the evader.
+    pub fn caught(&self) -> bool {
+        self.pursuers.contains(&self.evader)
+    }
+
+    /// Checks if it is the evader's turn.
+    pub fn evader_to_move(&self) -> bool {
+        self.turn == 0
+    }
+
+    fn position(&self) -> Coordinates {
+        if self.evader_to_move() {
+            self.evader
+        } else {
+            self.pursuers[self.turn - 1]
+        }
+    }
+
+    fn apply(&self, to: Coordinates) -> Self {
+        let mut next = self.clone();
+
+        if self.evader_to_move() {
+            next.evader = to;
+        } else {
+            next.pursuers[self.turn - 1] = to;
+        }
+
+        next.turn = (self.turn + 1) % (self.pursuers.len() + 1);
+        next.moves += 1;
+        next
+    }
+}
+
+/// Stimuli produced by a [`PursuitEvasion`] update.
+///
+/// The reward is `1.0` if the evader survived, `-1.0` if it was caught and
+/// `0.0` while the game goes on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PursuitStimuli {
+    pub state: GameState,
+    pub reward: f64,
+    pub terminal: bool,
+}
+
+/// Pursuit-evasion game played on a maze.
+pub struct PursuitEvasion<const N: usize> {
+    pub maze: Maze<N>,
+    initial: GameState,
+    state: GameState,
+    max_moves: usize,
+    /// Agent selected by a multi-agent simulator, if any.
+    active_agent: Option<usize>,
+}
+
+impl<const N: usize> PursuitEvasion<N> {
+    /// Creates a new game which the evader wins after `max_moves` moves.
+    ///
+    /// # Panics
+    /// Panics if a player starts on a blocked cell or outside the maze.
+    pub fn new(
+        maze: Maze<N>,
+        evader: Coordinates,
+        pursuers: Vec<Coordinates>,
+        max_moves: usize,
+    ) -> Self {
+        for position in std::iter::once(&evader).chain(&pursuers) {
+            assert!(
+                maze.is_walkable(position),
+                "players must start on free cells"
+            );
+        }
+
+        let initial = GameState {
+            evader,
+            pursuers,
+            turn: 0,
+            moves: 0,
+        };
+
+        Self {
+            maze,
+            state: initial.clone(),
+            initial,
+            max_moves,
+            active_agent: None,
+        }
+    }
+
+    pub fn state(&self) -> &GameState {
+        &self.state
+    }
+
+    /// Checks if a state ends the game.
+    pub fn is_over(&self, state: &GameState) -> bool {
+        state.caught() || state.moves >= self.max_moves
+    }
+
+    /// Moves available to the player to move in a state.
+    pub fn moves(&self, state: &GameState) -> Vec<Move> {
+        let position = state.position();
+
+        std::iter::once(Move::Wait)
+            .chain(self.maze.neighbors(position).into_iter().map(Move::Go))
+            .collect()
+    }
+
+    /// State reached by playing a move.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the game is over or the move
+    /// leads into a wall.
+    pub fn successor(&self, state: &GameState, action: Move) -> Result<GameState, PursuitError> {
+        if self.is_over(state) {
+            return Err(PursuitError::GameOver);
+        }
+
+        let position = state.position();
+        let to = match action {
+            Move::Wait => position,
+            Move::Go(direction) => position.next(direction),
+        };
+
+        if !self.maze.is_walkable(&to) {
+            return Err(PursuitError::InvalidMove);
+        }
+
+        Ok(state.apply(to))
+    }
+
+    fn stimuli(&self) -> PursuitStimuli {
+        let reward = if self.state.caught() {
+            -1.0
+        } else if self.is_over(&self.state) {
+            1.0
+        } else {
+            0.0
+        };
+
+        PursuitStimuli {
+            state: self.state.clone(),
+            reward,
+            terminal: self.is_over(&self.state),
+        }
+    }
+}
+
+impl<const N: usize> Environment for PursuitEvasion<N> {
+    type Error = PursuitError;
+
+    /// Move of the player whose turn it is.
+    type Action = Move;
+
+    type Stimuli = PursuitStimuli;
+
+    fn initial_stimuli(&self) -> Self::Stimuli {
+        self.stimuli()
+    }
+
+    fn update(&mut self, action: Self::Action) -> Result<Self::Stimuli, Self::Error> {
+        self.state = self.successor(&self.state, action)?;
+
+        Ok(self.stimuli())
+    }
+
+    fn reset(&mut self, seed: Option<u64>) -> Self::Stimuli {
+        self.maze.reset(seed);
+        self.state = self.initial.clone();
+
+        // A new layout could have walled the players in.
+        for position in std::iter::once(&self.state.evader).chain(&self.state.pursuers) {
+            if let Some(ground) = self.maze.get_mut(position) {
+                *ground = Ground::Free;
+            }
+        }
+
+        self.stimuli()
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.is_over(&self.state)
+    }
+}
+
+impl<const N: usize> SharedEnvironment for PursuitEvasion<N> {
+    /// Agent `0` plays the evader and agent `i > 0` plays pursuer `i - 1`;
+    /// an agent can only act on its own turn, whatever the resolution. Until
+    /// an agent is selected, a single agent may play every player.
+    fn select_agent(&mut self, agent: usize) {
+        self.active_agent = Some(agent);
+    }
+}
+
+impl<const N: usize> std::fmt::Display for PursuitEvasion<N> {
+    /// The evader is drawn as `E` and the pursuers as `P`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for x in 0..N {
+            for y in 0..N {
+                let cell = Coordinates::new(x as i64, y as i64);
+
+                let symbol = if self.state.pursuers.contains(&cell) {
+                    'P'
+                } else if self.state.evader == cell {
+                    'E'
+                } else if matches!(self.maze.get(&cell), Some(Ground::Blocked)) {
+                    '◼'
+                } else {
+                    ' '
+                };
+
+                write!(f, "{symbol} ")?;
+            }
+            writeln!(f)?;
+        }
+
+        write!(
+            f,
+            "move {}/{}{}",
+            self.state.moves,
+            self.max_moves,
+            if self.state.caught() { ", caught!" } else { "" }
+        )
+    }
+}
+
+/// Agent that searches the game tree to play the player whose turn it is.
+///
+/// In a [`MultiSimulator`](crate::simulator::MultiSimulator) the agent added
+/// first plays the evader and the following ones the pursuers.
+#[derive(Debug, Clone)]
+pub struct AdversarialAgent<const N: usize, S: AdversarialSearch> {
+    pub search: S,
+    pub evaluation: Evaluation<N>,
+    done: bool,
+}
+
+impl<const N: usize, S: AdversarialSearch> AdversarialAgent<N, S> {
+    /// Replaces the default search strategy.
+    pub fn with_search(mut self, search: S) -> Self {
+        self.search = search;
+        self
+    }
+
+    /// Replaces the default evaluation of non-terminal states.
+    pub fn with_evaluation(mut self, evaluation: Evaluation<N>) -> Self {
+        self.evaluation = evaluation;
+        self
+    }
+}
+
+impl<const N: usize, S: AdversarialSearch + Default> Agent for AdversarialAgent<N, S> {
+    type Error = PursuitError;
+
+    type Action = Move;
+
+    type Stimuli = PursuitStimuli;
+
+    type Environment = PursuitEvasion<N>;
+
+    fn new(_environment: &Self::Environment) -> Self {
+        Self {
+            search: S::default(),
+            evaluation: search::maze_distance,
+            done: false,
+        }
+    }
+
+    fn act(&mut self, environment: &mut Self::Environment) -> Result<(), Self::Error> {
+        if environment.is_terminal() {
+            self.done = true;
+            return Ok(());
+        }
+
+        check_turn(environment)?;
+
+        let action = self
+            .search
+            .best_move(environment, environment.state(), self.evaluation);
+        self.done = environment.update(action)?.terminal;
+
+        Ok(())
+    }
+
+    fn should_stop(&self) -> bool {
+        self.done
+    }
+}
+
+/// Pursuer that moves uniformly at random, the kind of opponent
+/// [`Expectimax`] models.
+#[derive(Debug, Clone)]
+pub struct RandomPursuer<const N: usize> {
+    done: bool,
+}
+
+impl<const N: usize> Agent for RandomPursuer<N> {
+    type Error = PursuitError;
+
+    type Action = Move;
+
+    type Stimuli = PursuitStimuli;
+
+    type Environment = PursuitEvasion<N>;
+
+    fn new(_environment: &Self::Environment) -> Self {
+        Self { done: false }
+    }
+
+    fn act(&mut self, environment: &mut Self::Environment) -> Result<(), Self::Error> {
+        if environment.is_terminal() {
+            self.done = true;
+            return Ok(());
+        }
+
+        check_turn(environment)?;
+
+        let action = *environment
+            .moves(environment.state())
+            .choose(&mut rand::thread_rng())
+            .unwrap_or(&Move::Wait);
+        self.done = environment.update(action)?.terminal;
+
+        Ok(())
+    }
+
+    fn should_stop(&self) -> bool {
+        self.done
+    }
+}
+
+/// Makes sure the agent selected by a
+/// [`MultiSimulator`](crate::simulator::MultiSimulator) plays on its own turn.
+fn check_turn<const N: usize>(environment: &PursuitEvasion<N>) -> Result<(), PursuitError> {
+    if environment
+        .active_agent
+        .is_some_and(|agent| agent != environment.state.turn)
+    {
+        return Err(PursuitError::NotYourTurn);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        agent::Agent,
+        environment::Environment,
+        maze::{coordinates::Coordinates, Maze},
+        render::Headless,
+        simulator::MultiSimulator,
+    };
+
+    use super::{search, AdversarialAgent, AdversarialSearch, AlphaBeta, Minimax, PursuitEvasion};
+
+    fn game() -> PursuitEvasion<5> {
+        let maze = Maze::<5>::new(Coordinates::new(0, 0), Coordinates::new(4, 4), 0);
+
+        PursuitEvasion::new(
+            maze,
+            Coordinates::new(0, 0),
+            vec![Coordinates::new(4, 4), Coordinates::new(4, 0)],
+            30,
+        )
+    }
+
+    #[test]
+    fn test_alpha_beta_agrees_with_minimax() {
+        let mut game = game();
+
+        for _ in 0..6 {
+            let state = game.state().clone();
+            let minimax = Minimax { depth: 3 }.best_move(&game, &state, search::maze_distance);
+            let alpha_beta = AlphaBeta { depth: 3 }.best_move(&game, &state, search::maze_distance);
+
+            assert_eq!(minimax, alpha_beta);
+            game.update(minimax).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_pursuers_catch_evader_in_open_field() {
+        let game = game();
+
+        let evader = AdversarialAgent::<5, AlphaBeta>::new(&game);
+        let first = AdversarialAgent::<5, AlphaBeta>::new(&game);
+        let second = AdversarialAgent::<5, AlphaBeta>::new(&game);
+
+        let mut simulator = MultiSimulator::new(Headless, game)
+            .with_agent(evader)
+            .with_agent(first)
+            .with_agent(second);
+        simulator.simulate().unwrap();
+
+        assert!(simulator.environment().state().caught());
+    }
+}
@@ -0,0 +1,293 @@
+//! Adversarial search strategies
+
+use crate::maze::{mapf::reservation::distances, mapf::Move};
+
+use super::{GameState, PursuitEvasion};
+
+/// Estimates how good a non-terminal state is for the evader.
+pub type Evaluation<const N: usize> = fn(&PursuitEvasion<N>, &GameState) -> f64;
+
+/// Value of a state where the evader was caught.
+const CAUGHT: f64 = -1_000.0;
+
+/// Value of a state where the evader survived.
+const ESCAPED: f64 = 1_000.0;
+
+/// Distance, through the maze, from the evader to the closest pursuer, with
+/// the distances to the other pursuers and the room the evader has to move as
+/// tie-breakers.
+///
+/// Unreachable evaders are as good as escaped.
+pub fn maze_distance<const N: usize>(game: &PursuitEvasion<N>, state: &GameState) -> f64 {
+    let distances = distances(&game.maze, state.evader);
+    let to_pursuers: Vec<_> = state
+        .pursuers
+        .iter()
+        .filter_map(|pursuer| distances.get(pursuer))
+        .map(|distance| *distance as f64)
+        .collect();
+
+    let Some(closest) = to_pursuers.iter().copied().reduce(f64::min) else {
+        return ESCAPED;
+    };
+    let total: f64 = to_pursuers.iter().sum();
+    let room = game.maze.neighbors(state.evader).len() as f64;
+
+    closest + 0.1 * total + 0.01 * room
+}
+
+/// Manhattan distance from the evader to the closest pursuer, ignoring walls.
+pub fn manhattan_distance<const N: usize>(_game: &PursuitEvasion<N>, state: &GameState) -> f64 {
+    state
+        .pursuers
+        .iter()
+        .map(|pursuer| pursuer.manhattan_dist(&state.evader))
+        .min()
+        .map_or(ESCAPED, |distance| distance as f64)
+}
+
+/// Value of a state if the game is over.
+///
+/// Captures are worse the sooner they happen, so that a doomed evader still
+/// runs and a pursuer prefers the quickest capture.
+fn terminal_value<const N: usize>(game: &PursuitEvasion<N>, state: &GameState) -> Option<f64> {
+    if state.caught() {
+        Some(CAUGHT + state.moves as f64)
+    } else if game.is_over(state) {
+        Some(ESCAPED)
+    } else {
+        None
+    }
+}
+
+/// How an [`AdversarialAgent`](super::AdversarialAgent) picks its moves.
+pub trait AdversarialSearch {
+    /// Best move for the player to move in `state`.
+    fn best_move<const N: usize>(
+        &self,
+        game: &PursuitEvasion<N>,
+        state: &GameState,
+        evaluation: Evaluation<N>,
+    ) -> Move;
+}
+
+/// Picks the move with the best value for the player to move, the evader
+/// maximizing and the pursuers minimizing.
+fn choose<const N: usize>(
+    game: &PursuitEvasion<N>,
+    state: &GameState,
+    mut value: impl FnMut(&GameState) -> f64,
+) -> Move {
+    let sign = if state.evader_to_move() { 1.0 } else { -1.0 };
+
+    game.moves(state)
+        .into_iter()
+        .filter_map(|action| {
+            let next = game.successor(state, action).ok()?;
+            Some((action, sign * value(&next)))
+        })
+        .fold(
+            None,
+            |best: Option<(Move, f64)>, (action, value)| match best {
+                Some((_, best_value)) if best_value >= value => best,
+                _ => Some((action, value)),
+            },
+        )
+        .map_or(Move::Wait, |(action, _)| action)
+}
+
+/// Plain minimax search to a fixed depth, in plies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Minimax {
+    pub depth: usize,
+}
+
+impl Default for Minimax {
+    fn default() -> Self {
+        Self { depth: 4 }
+    }
+}
+
+impl Minimax {
+    fn value<const N: usize>(
+        game: &PursuitEvasion<N>,
+        state: &GameState,
+        depth: usize,
+        evaluation: Evaluation<N>,
+    ) -> f64 {
+        if let Some(value) = terminal_value(game, state) {
+            return value;
+        }
+        if depth == 0 {
+            return evaluation(game, state);
+        }
+
+        let values = game.moves(state).into_iter().filter_map(|action| {
+            let next = game.successor(state, action).ok()?;
+            Some(Self::value(game, &next, depth - 1, evaluation))
+        });
+
+        if state.evader_to_move() {
+            values.fold(f64::NEG_INFINITY, f64::max)
+        } else {
+            values.fold(f64::INFINITY, f64::min)
+        }
+    }
+}
+
+impl AdversarialSearch for Minimax {
+    fn best_move<const N: usize>(
+        &self,
+        game: &PursuitEvasion<N>,
+        state: &GameState,
+        evaluation: Evaluation<N>,
+    ) -> Move {
+        let depth = self.depth.saturating_sub(1);
+
+        choose(game, state, |next| {
+            Self::value(game, next, depth, evaluation)
+        })
+    }
+}
+
+/// Minimax with alpha-beta pruning, returning the same moves as [`Minimax`]
+/// while skipping branches that can't change the decision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlphaBeta {
+    pub depth: usize,
+}
+
+impl Default for AlphaBeta {
+    fn default() -> Self {
+        Self { depth: 6 }
+    }
+}
+
+impl AlphaBeta {
+    fn value<const N: usize>(
+        game: &PursuitEvasion<N>,
+        state: &GameState,
+        depth: usize,
+        mut alpha: f64,
+        mut beta: f64,
+        evaluation: Evaluation<N>,
+    ) -> f64 {
+        if let Some(value) = terminal_value(game, state) {
+            return value;
+        }
+        if depth == 0 {
+            return evaluation(game, state);
+        }
+
+        let successors = game
+            .moves(state)
+            .into_iter()
+            .filter_map(|action| game.successor(state, action).ok());
+
+        if state.evader_to_move() {
+            let mut best = f64::NEG_INFINITY;
+            for next in successors {
+                best = best.max(Self::value(game, &next, depth - 1, alpha, beta, evaluation));
+                alpha = alpha.max(best);
+                if alpha >= beta {
+                    break;
+                }
+            }
+            best
+        } else {
+            let mut best = f64::INFINITY;
+            for next in successors {
+                best = best.min(Self::value(game, &next, depth - 1, alpha, beta, evaluation));
+                beta = beta.min(best);
+                if alpha >= beta {
+                    break;
+                }
+            }
+            best
+        }
+    }
+}
+
+impl AdversarialSearch for AlphaBeta {
+    fn best_move<const N: usize>(
+        &self,
+        game: &PursuitEvasion<N>,
+        state: &GameState,
+        evaluation: Evaluation<N>,
+    ) -> Move {
+        let depth = self.depth.saturating_sub(1);
+
+        choose(game, state, |next| {
+            Self::value(
+                game,
+                next,
+                depth,
+                f64::NEG_INFINITY,
+                f64::INFINITY,
+                evaluation,
+            )
+        })
+    }
+}
+
+/// Expectimax search for an evader facing randomly moving pursuers: pursuer
+/// plies are chance nodes averaging over every legal move.
+///
+/// When used to play a pursuer, it picks the move with the lowest expected
+/// value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Expectimax {
+    pub depth: usize,
+}
+
+impl Default for Expectimax {
+    fn default() -> Self {
+        Self { depth: 4 }
+    }
+}
+
+impl Expectimax {
+    fn value<const N: usize>(
+        game: &PursuitEvasion<N>,
+        state: &GameState,
+        depth: usize,
+        evaluation: Evaluation<N>,
+    ) -> f64 {
+        if let Some(value) = terminal_value(game, state) {
+            return value;
+        }
+        if depth == 0 {
+            return evaluation(game, state);
+        }
+
+        let values: Vec<_> = game
+            .moves(state)
+            .into_iter()
+            .filter_map(|action| {
+                let next = game.successor(state, action).ok()?;
+                Some(Self::value(game, &next, depth - 1, evaluation))
+            })
+            .collect();
+
+        if state.evader_to_move() {
+            values.into_iter().fold(f64::NEG_INFINITY, f64::max)
+        } else {
+            values.iter().sum::<f64>() / values.len() as f64
+        }
+    }
+}
+
+impl AdversarialSearch for Expectimax {
+    fn best_move<const N: usize>(
+        &self,
+        game: &PursuitEvasion<N>,
+        state: &GameState,
+        evaluation: Evaluation<N>,
+    ) -> Move {
+        let depth = self.depth.saturating_sub(1);
+
+        choose(game, state, |next| {
+            Self::value(game, next, depth, evaluation)
+        })
+    }
+}
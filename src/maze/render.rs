@@ -1,39 +1,55 @@
-use crossterm::{cursor, terminal, QueueableCommand};
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    style::{Color, Stylize},
+    terminal, QueueableCommand,
+};
 use std::{
-    io::{stdout, Stdout, Write},
-    thread, time,
+    fmt::Display,
+    io::{self, stdout, IsTerminal, Stdout, Write},
+    thread,
+    time::{Duration, Instant},
 };
 
-use super::Maze;
+use super::{coordinates::Coordinates, text::TextStyle, Ground, Maze};
+
+use crate::{
+    environment::Environment,
+    render::{Control, Renderer},
+};
 
-use crate::render::Renderer;
+/// Delay between frames of the terminal renderers.
+pub const DEFAULT_DELAY: Duration = Duration::from_millis(100);
 
 pub struct DefaultRenderer {
     stdout: Stdout,
+    delay: Duration,
 }
 
 impl DefaultRenderer {
     pub fn new(stdout: Stdout) -> Self {
-        Self { stdout }
+        Self {
+            stdout,
+            delay: DEFAULT_DELAY,
+        }
+    }
+
+    /// Sets the delay between frames. [`Duration::ZERO`] draws as fast as
+    /// possible, e.g. when the output is piped.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
     }
 }
 
 impl Default for DefaultRenderer {
     fn default() -> Self {
-        Self { stdout: stdout() }
+        Self::new(stdout())
     }
 }
 
-impl<const N: usize> Renderer<Maze<N>> for DefaultRenderer {
-    fn setup(&mut self) {
-        self.stdout.flush().expect("Failed to flush stdout");
-    }
-
-    fn teardown(&mut self) {
-        self.stdout.flush().expect("Failed to flush stdout");
-    }
-
-    fn render(&mut self, environment: &Maze<N>) {
+impl DefaultRenderer {
+    fn draw(&mut self, frame: impl Display) {
         // // Save the current cursor position
         self.stdout.queue(cursor::SavePosition).unwrap();
 
@@ -47,13 +63,539 @@ impl<const N: usize> Renderer<Maze<N>> for DefaultRenderer {
 
         // // Write the new maze content
         self.stdout
-            .write_all(format!("{}\n", environment).as_bytes())
+            .write_all(format!("{}\n", frame).as_bytes())
             .unwrap();
 
         // Flush the output to ensure it's displayed
         self.stdout.flush().unwrap();
 
         // // Optionally sleep for a smooth rendering delay
-        thread::sleep(time::Duration::from_millis(100));
+        thread::sleep(self.delay);
+    }
+}
+
+/// Draws any environment that can be displayed, e.g. a [`Maze`], a
+/// [`GridWorld`](super::grid_world::GridWorld) or a
+/// [`PursuitEvasion`](super::pursuit::PursuitEvasion) game.
+impl<E: Environment + Display> Renderer<E> for DefaultRenderer {
+    fn setup(&mut self) {
+        self.stdout.flush().expect("Failed to flush stdout");
+    }
+
+    fn teardown(&mut self) {
+        self.stdout.flush().expect("Failed to flush stdout");
+    }
+
+    fn render(&mut self, environment: &E) {
+        self.draw(environment);
+    }
+}
+
+/// Shortest and longest frame delays of the [`InteractiveRenderer`].
+const DELAY_RANGE: (Duration, Duration) = (Duration::from_millis(5), Duration::from_secs(2));
+
+/// Raw mode on the alternate screen, with the cursor hidden, until dropped,
+/// so that the terminal is given back even when a run panics.
+pub(crate) struct RawScreen;
+
+impl RawScreen {
+    /// Switches the terminal of stdout.
+    pub(crate) fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        // Restores raw mode if the screen can't be switched
+        let screen = Self;
+
+        let mut stdout = stdout();
+        stdout.queue(terminal::EnterAlternateScreen)?;
+        stdout.queue(cursor::Hide)?;
+        stdout.flush()?;
+
+        Ok(screen)
+    }
+}
+
+impl Drop for RawScreen {
+    fn drop(&mut self) {
+        // Nothing is left to do with the errors while unwinding
+        let mut stdout = stdout();
+        let _ = stdout.queue(cursor::Show);
+        let _ = stdout.queue(terminal::LeaveAlternateScreen);
+        let _ = stdout.flush();
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Terminal renderer controlled from the keyboard.
+///
+/// The terminal is switched to raw mode on an alternate screen while the
+/// simulation runs:
+///
+/// - `space` pauses and resumes,
+/// - `n` or `→` advances a single frame while paused,
+/// - `+` and `-` halve and double the delay between frames,
+/// - `q`, `esc` or `ctrl-c` abort the simulation.
+///
+/// When the terminal can't be switched to raw mode, e.g. when stdout isn't a
+/// terminal, frames are drawn one after the other as by the
+/// [`DefaultRenderer`].
+pub struct InteractiveRenderer {
+    stdout: Stdout,
+    delay: Duration,
+    paused: bool,
+    aborted: bool,
+    /// Set while the terminal is switched, when stdout is one.
+    screen: Option<RawScreen>,
+    frames: usize,
+}
+
+impl InteractiveRenderer {
+    pub fn new(stdout: Stdout) -> Self {
+        Self {
+            stdout,
+            delay: DEFAULT_DELAY,
+            paused: false,
+            aborted: false,
+            screen: None,
+            frames: 0,
+        }
+    }
+
+    /// Sets the initial delay between frames.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Starts the simulation paused, waiting for a step or resume key.
+    pub fn paused(mut self) -> Self {
+        self.paused = true;
+        self
+    }
+
+    fn status(&self) -> String {
+        let state = if self.paused { "paused" } else { "running" };
+
+        format!(
+            "frame {} | {state} | {} ms | [space] pause [n] step [+/-] speed [q] quit",
+            self.frames,
+            self.delay.as_millis()
+        )
+    }
+
+    fn draw_status(&mut self) {
+        self.stdout.queue(cursor::MoveToColumn(0)).unwrap();
+        self.stdout
+            .queue(terminal::Clear(terminal::ClearType::CurrentLine))
+            .unwrap();
+        self.stdout.write_all(self.status().as_bytes()).unwrap();
+        self.stdout.flush().unwrap();
+    }
+
+    fn draw(&mut self, frame: impl Display) {
+        self.stdout.queue(cursor::MoveTo(0, 0)).unwrap();
+        self.stdout
+            .queue(terminal::Clear(terminal::ClearType::All))
+            .unwrap();
+
+        // Raw mode doesn't return the carriage on new lines
+        let frame = frame.to_string().replace('\n', "\r\n");
+        self.stdout.write_all(frame.as_bytes()).unwrap();
+        self.stdout.write_all(b"\r\n").unwrap();
+
+        self.draw_status();
+    }
+
+    /// Handles key presses until the next frame is due.
+    fn wait(&mut self) {
+        let deadline = Instant::now() + self.delay;
+
+        while !self.aborted {
+            if !self.paused {
+                let timeout = deadline.saturating_duration_since(Instant::now());
+
+                if timeout.is_zero() || !event::poll(timeout).unwrap_or(false) {
+                    return;
+                }
+            }
+
+            let Ok(Event::Key(key)) = event::read() else {
+                continue;
+            };
+
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            match key.code {
+                KeyCode::Char(' ') => self.paused = !self.paused,
+                KeyCode::Char('n') | KeyCode::Right if self.paused => return,
+                KeyCode::Char('+') => self.delay = (self.delay / 2).max(DELAY_RANGE.0),
+                KeyCode::Char('-') => self.delay = (self.delay * 2).min(DELAY_RANGE.1),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.aborted = true
+                }
+                KeyCode::Char('q') | KeyCode::Esc => self.aborted = true,
+                _ => continue,
+            }
+
+            self.draw_status();
+        }
+    }
+}
+
+impl Default for InteractiveRenderer {
+    fn default() -> Self {
+        Self::new(stdout())
+    }
+}
+
+impl<E: Environment + Display> Renderer<E> for InteractiveRenderer {
+    fn setup(&mut self) {
+        self.aborted = false;
+        self.frames = 0;
+        self.screen = self
+            .stdout
+            .is_terminal()
+            .then(RawScreen::enter)
+            .and_then(Result::ok);
+        self.stdout.flush().expect("Failed to flush stdout");
+    }
+
+    fn render(&mut self, environment: &E) {
+        self.frames += 1;
+
+        if self.screen.is_some() {
+            self.draw(environment);
+            self.wait();
+        } else {
+            writeln!(self.stdout, "{environment}\n").unwrap();
+            thread::sleep(self.delay);
+        }
+    }
+
+    fn teardown(&mut self) {
+        self.screen = None;
+        self.stdout.flush().expect("Failed to flush stdout");
+    }
+
+    fn control(&self) -> Control {
+        if self.aborted {
+            Control::Abort
+        } else {
+            Control::Continue
+        }
+    }
+}
+
+/// Colors given to the agents of a [`MultiAgentRenderer`], in agent order.
+pub const AGENT_COLORS: [Color; 6] = [
+    Color::Red,
+    Color::Green,
+    Color::Blue,
+    Color::Yellow,
+    Color::Magenta,
+    Color::Cyan,
+];
+
+/// Renders the paths of every agent sharing a maze, each one in its own
+/// color. Cells shared by several paths take the color of the last agent.
+pub struct MultiAgentRenderer {
+    stdout: Stdout,
+    names: Vec<String>,
+    delay: Duration,
+}
+
+impl MultiAgentRenderer {
+    /// Creates a renderer labelling the agents with the given names in the
+    /// legend.
+    pub fn new(stdout: Stdout, names: Vec<String>) -> Self {
+        Self {
+            stdout,
+            names,
+            delay: DEFAULT_DELAY,
+        }
+    }
+
+    /// Sets the delay between frames.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    fn draw<const N: usize>(&self, environment: &Maze<N>) -> String {
+        let mut owners: [[Option<usize>; N]; N] = [[None; N]; N];
+
+        for (agent, path) in environment.agent_paths().into_iter().enumerate() {
+            for coord in path.rev_iter() {
+                owners[coord.x as usize][coord.y as usize] = Some(agent);
+            }
+        }
+
+        let mut frame = String::new();
+
+        for (x, row) in owners.iter().enumerate() {
+            for (y, owner) in row.iter().enumerate() {
+                let ground = environment
+                    .get(&Coordinates::new(x as i64, y as i64))
+                    .unwrap_or(Ground::Blocked);
+
+                let cell = match (owner, ground) {
+                    (_, Ground::Blocked) => "◼ ".to_string(),
+                    (Some(agent), _) => {
+                        format!("{}", "★ ".with(AGENT_COLORS[agent % AGENT_COLORS.len()]))
+                    }
+                    (None, Ground::Free | Ground::Path) => "  ".to_string(),
+                    (None, ground) => ground.as_ref().to_string(),
+                };
+
+                frame.push_str(&cell);
+            }
+            frame.push('\n');
+        }
+
+        for (agent, path) in environment.agent_paths().into_iter().enumerate() {
+            let name = self
+                .names
+                .get(agent)
+                .cloned()
+                .unwrap_or_else(|| format!("Agent {agent}"));
+
+            frame.push_str(&format!(
+                "{} {name}: {} steps\n",
+                "★".with(AGENT_COLORS[agent % AGENT_COLORS.len()]),
+                path.len() - 1
+            ));
+        }
+
+        frame
+    }
+}
+
+impl<const N: usize> Renderer<Maze<N>> for MultiAgentRenderer {
+    fn setup(&mut self) {
+        self.stdout.flush().expect("Failed to flush stdout");
+    }
+
+    fn teardown(&mut self) {
+        self.stdout.flush().expect("Failed to flush stdout");
+    }
+
+    fn render(&mut self, environment: &Maze<N>) {
+        let frame = self.draw(environment);
+        redraw(&mut self.stdout, &frame, self.delay);
+    }
+}
+
+/// Draws the maze in one of the compact [`TextStyle`]s, e.g. to fit large
+/// mazes in a terminal.
+pub struct TextRenderer {
+    stdout: Stdout,
+    style: TextStyle,
+    delay: Duration,
+}
+
+impl TextRenderer {
+    pub fn new(stdout: Stdout, style: TextStyle) -> Self {
+        Self {
+            stdout,
+            style,
+            delay: DEFAULT_DELAY,
+        }
+    }
+
+    /// Sets the delay between frames.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+impl<const N: usize> Renderer<Maze<N>> for TextRenderer {
+    fn setup(&mut self) {
+        self.stdout.flush().expect("Failed to flush stdout");
+    }
+
+    fn teardown(&mut self) {
+        self.stdout.flush().expect("Failed to flush stdout");
+    }
+
+    fn render(&mut self, environment: &Maze<N>) {
+        let frame = self.style.draw(environment);
+        redraw(&mut self.stdout, &frame, self.delay);
+    }
+}
+
+/// Replaces the previous frame drawn above the cursor, then waits `delay`.
+fn redraw(stdout: &mut Stdout, frame: &str, delay: Duration) {
+    stdout.queue(cursor::SavePosition).unwrap();
+    stdout
+        .queue(terminal::Clear(terminal::ClearType::FromCursorUp))
+        .unwrap();
+    stdout.queue(cursor::RestorePosition).unwrap();
+
+    stdout.write_all(frame.as_bytes()).unwrap();
+    stdout.flush().unwrap();
+
+    thread::sleep(delay);
+}
+
+/// Renders what a search explored on a [`Maze`]: visited cells, frontier
+/// cells, the current candidate path, start and goal, each in its own
+/// color, with a legend and live counters.
+///
+/// The search is read from [`Maze::trace`], recorded by the
+/// [`PathFinder`](super::agent::PathFinder) agents. The frontier only shows
+/// on mazes keeping it, see [`Maze::with_frontier_trace`].
+pub struct SearchRenderer {
+    stdout: Stdout,
+    delay: Duration,
+}
+
+impl SearchRenderer {
+    pub fn new(stdout: Stdout) -> Self {
+        Self {
+            stdout,
+            delay: DEFAULT_DELAY,
+        }
+    }
+
+    /// Sets the delay between frames.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    fn draw<const N: usize>(&self, environment: &Maze<N>) -> String {
+        let trace = environment.trace();
+        let cells = search_cells(environment);
+
+        let mut frame = String::new();
+
+        for (x, row) in cells.iter().enumerate() {
+            for (y, cell) in row.iter().enumerate() {
+                let ground = environment
+                    .get(&Coordinates::new(x as i64, y as i64))
+                    .unwrap_or(Ground::Blocked);
+
+                match (cell, ground) {
+                    (Some(cell), _) => frame.push_str(&cell.to_string()),
+                    (None, Ground::Free | Ground::Path) => frame.push_str("  "),
+                    (None, ground) => frame.push_str(ground.as_ref()),
+                }
+            }
+            frame.push('\n');
+        }
+
+        for cell in Cell::ALL {
+            frame.push_str(&format!("{cell}{}  ", cell.label()));
+        }
+        frame.push('\n');
+
+        frame.push_str(&format!(
+            "step: {} | expanded: {} | frontier: {} | path length: {}\n",
+            trace.steps,
+            trace.visited.len(),
+            trace.frontier.len(),
+            environment.current_path.len() - 1,
+        ));
+
+        frame
+    }
+}
+
+impl Default for SearchRenderer {
+    fn default() -> Self {
+        Self::new(stdout())
+    }
+}
+
+impl<const N: usize> Renderer<Maze<N>> for SearchRenderer {
+    fn setup(&mut self) {
+        self.stdout.flush().expect("Failed to flush stdout");
+    }
+
+    fn teardown(&mut self) {
+        self.stdout.flush().expect("Failed to flush stdout");
+    }
+
+    fn render(&mut self, environment: &Maze<N>) {
+        let frame = self.draw(environment);
+        redraw(&mut self.stdout, &frame, self.delay);
+    }
+}
+
+/// Marks every cell of the maze with what the search knows about it.
+pub(crate) fn search_cells<const N: usize>(environment: &Maze<N>) -> [[Option<Cell>; N]; N] {
+    let trace = environment.trace();
+    let mut cells: [[Option<Cell>; N]; N] = [[None; N]; N];
+
+    let mut mark = |coord: Coordinates, cell: Cell| {
+        if let Some(ground) = cells
+            .get_mut(coord.x as usize)
+            .and_then(|row| row.get_mut(coord.y as usize))
+        {
+            *ground = Some(cell);
+        }
+    };
+
+    trace
+        .visited
+        .iter()
+        .for_each(|&coord| mark(coord, Cell::Visited));
+    trace
+        .frontier
+        .iter()
+        .for_each(|&coord| mark(coord, Cell::Frontier));
+    environment
+        .current_path
+        .rev_iter()
+        .for_each(|coord| mark(coord, Cell::Path));
+    mark(environment.initial_position, Cell::Start);
+    mark(environment.target_position, Cell::Goal);
+
+    cells
+}
+
+/// Kind of cell drawn by the [`SearchRenderer`], by increasing priority.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Cell {
+    Visited,
+    Frontier,
+    Path,
+    Start,
+    Goal,
+}
+
+impl Cell {
+    pub(crate) const ALL: [Cell; 5] = [
+        Cell::Visited,
+        Cell::Frontier,
+        Cell::Path,
+        Cell::Start,
+        Cell::Goal,
+    ];
+
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            Cell::Visited => "visited",
+            Cell::Frontier => "frontier",
+            Cell::Path => "path",
+            Cell::Start => "start",
+            Cell::Goal => "goal",
+        }
+    }
+}
+
+impl Display for Cell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            Cell::Visited => "· ".with(Color::DarkBlue),
+            Cell::Frontier => "○ ".with(Color::Yellow),
+            Cell::Path => "★ ".with(Color::Green),
+            Cell::Start => "S ".with(Color::Cyan),
+            Cell::Goal => "G ".with(Color::Red),
+        };
+
+        write!(f, "{symbol}")
     }
 }
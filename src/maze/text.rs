@@ -0,0 +1,294 @@
+//! Compact text drawings of mazes
+//!
+//! The [`Display`](std::fmt::Display) of a [`Maze`] takes two columns per
+//! cell. The other [`TextStyle`]s trade detail for room, down to the braille
+//! drawing which packs eight cells in a single character.
+
+use std::{error::Error, fmt, str::FromStr};
+
+use crossterm::style::{Color, Stylize};
+
+use super::{coordinates::Coordinates, Ground, Maze};
+
+/// How a maze is drawn as text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextStyle {
+    /// The [`Display`](std::fmt::Display) of the maze, two columns per cell.
+    #[default]
+    Table,
+    /// Thin box-drawing walls around the free cells.
+    BoxDrawing,
+    /// Half blocks, two rows of cells per line.
+    HalfBlock,
+    /// Braille dots, four rows and two columns of cells per character.
+    Braille,
+}
+
+/// Error of parsing an unknown [`TextStyle`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownTextStyle(pub String);
+
+impl fmt::Display for UnknownTextStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown text style `{}`, expected one of table, box, half-block, braille",
+            self.0
+        )
+    }
+}
+
+impl Error for UnknownTextStyle {}
+
+impl FromStr for TextStyle {
+    type Err = UnknownTextStyle;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(Self::Table),
+            "box" => Ok(Self::BoxDrawing),
+            "half-block" => Ok(Self::HalfBlock),
+            "braille" => Ok(Self::Braille),
+            _ => Err(UnknownTextStyle(s.to_string())),
+        }
+    }
+}
+
+impl TextStyle {
+    /// Draws the maze and its current path.
+    pub fn draw<const N: usize>(&self, maze: &Maze<N>) -> String {
+        match self {
+            Self::Table => format!("{maze}\n"),
+            Self::BoxDrawing => box_drawing(maze),
+            Self::HalfBlock => half_block(maze),
+            Self::Braille => braille(maze),
+        }
+    }
+}
+
+/// What a cell shows, for the styles that color cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Shade {
+    Free,
+    Wall,
+    Path,
+    Start,
+    Goal,
+}
+
+impl Shade {
+    /// Shades of every cell, indexed as the maze.
+    fn grid<const N: usize>(maze: &Maze<N>) -> Vec<Vec<Self>> {
+        let mut grid: Vec<Vec<Self>> = (0..N)
+            .map(|x| {
+                (0..N)
+                    .map(|y| match maze.get(&Coordinates::new(x as i64, y as i64)) {
+                        Some(Ground::Blocked) => Self::Wall,
+                        _ => Self::Free,
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut mark = |coords: Coordinates, shade: Self| {
+            grid[coords.x as usize][coords.y as usize] = shade;
+        };
+
+        maze.current_path
+            .rev_iter()
+            .for_each(|coords| mark(coords, Self::Path));
+        mark(maze.initial_position, Self::Start);
+        mark(maze.target_position, Self::Goal);
+
+        grid
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            Self::Free => Color::Reset,
+            Self::Wall => Color::Grey,
+            Self::Path => Color::Green,
+            Self::Start => Color::Cyan,
+            Self::Goal => Color::Red,
+        }
+    }
+
+    /// The goal shows over the start, which shows over the path.
+    fn max_priority(self, other: Self) -> Self {
+        let priority = |shade: Self| match shade {
+            Self::Free | Self::Wall => 0,
+            Self::Path => 1,
+            Self::Start => 2,
+            Self::Goal => 3,
+        };
+
+        if priority(other) > priority(self) {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+fn is_open<const N: usize>(maze: &Maze<N>, x: i64, y: i64) -> bool {
+    !matches!(
+        maze.get(&Coordinates::new(x, y)),
+        None | Some(Ground::Blocked)
+    )
+}
+
+/// Walls are drawn where a free cell meets a blocked cell or the border,
+/// on a grid of `(N + 1) x (N + 1)` corners.
+fn box_drawing<const N: usize>(maze: &Maze<N>) -> String {
+    let n = N as i64;
+    // Wall above cell (x, y), and on the left of cell (x, y)
+    let horizontal =
+        |x: i64, y: i64| (0..n).contains(&y) && is_open(maze, x - 1, y) != is_open(maze, x, y);
+    let vertical =
+        |x: i64, y: i64| (0..n).contains(&x) && is_open(maze, x, y - 1) != is_open(maze, x, y);
+
+    let shades = Shade::grid(maze);
+    let mut text = String::new();
+
+    for x in 0..=n {
+        for y in 0..=n {
+            let corner = match (
+                vertical(x - 1, y),
+                vertical(x, y),
+                horizontal(x, y - 1),
+                horizontal(x, y),
+            ) {
+                (false, false, false, false) => ' ',
+                (true, true, false, false)
+                | (true, false, false, false)
+                | (false, true, false, false) => '│',
+                (false, false, true, true)
+                | (false, false, true, false)
+                | (false, false, false, true) => '─',
+                (false, true, false, true) => '┌',
+                (false, true, true, false) => '┐',
+                (true, false, false, true) => '└',
+                (true, false, true, false) => '┘',
+                (true, true, false, true) => '├',
+                (true, true, true, false) => '┤',
+                (false, true, true, true) => '┬',
+                (true, false, true, true) => '┴',
+                (true, true, true, true) => '┼',
+            };
+            text.push(corner);
+
+            if y < n {
+                text.push(if horizontal(x, y) { '─' } else { ' ' });
+            }
+        }
+        text.push('\n');
+
+        if x == n {
+            break;
+        }
+
+        for y in 0..=n {
+            text.push(if vertical(x, y) { '│' } else { ' ' });
+
+            if y < n {
+                let cell = match shades[x as usize][y as usize] {
+                    Shade::Path => format!("{}", "•".with(Color::Green)),
+                    Shade::Start => format!("{}", "S".with(Color::Cyan)),
+                    Shade::Goal => format!("{}", "G".with(Color::Red)),
+                    Shade::Free | Shade::Wall => " ".to_string(),
+                };
+                text.push_str(&cell);
+            }
+        }
+        text.push('\n');
+    }
+
+    text
+}
+
+/// The upper half block takes the color of the upper cell, its background
+/// the color of the lower one.
+fn half_block<const N: usize>(maze: &Maze<N>) -> String {
+    let shades = Shade::grid(maze);
+    let mut text = String::new();
+
+    for x in (0..N).step_by(2) {
+        for y in 0..N {
+            let top = shades[x][y];
+            let bottom = shades.get(x + 1).map_or(Shade::Free, |row| row[y]);
+
+            text.push_str(&format!("{}", "▀".with(top.color()).on(bottom.color())));
+        }
+        text.push('\n');
+    }
+
+    text
+}
+
+/// Dots of a braille character, indexed by row then column.
+const BRAILLE_DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+/// Walls and the current path raise dots. Characters holding the path, the
+/// start or the goal are colored.
+fn braille<const N: usize>(maze: &Maze<N>) -> String {
+    let shades = Shade::grid(maze);
+    let mut text = String::new();
+
+    for x in (0..N).step_by(4) {
+        for y in (0..N).step_by(2) {
+            let mut dots = 0;
+            let mut color = Shade::Wall;
+
+            for (row, bits) in BRAILLE_DOTS.iter().enumerate() {
+                for (column, bit) in bits.iter().enumerate() {
+                    if x + row >= N || y + column >= N {
+                        continue;
+                    }
+
+                    let shade = shades[x + row][y + column];
+                    if shade != Shade::Free {
+                        dots |= bit;
+                    }
+                    if shade != Shade::Wall && shade != Shade::Free {
+                        color = color.max_priority(shade);
+                    }
+                }
+            }
+
+            let character = char::from_u32(0x2800 + dots).unwrap_or(' ');
+            match color {
+                Shade::Wall => text.push(character),
+                shade => text.push_str(&format!("{}", character.with(shade.color()))),
+            }
+        }
+        text.push('\n');
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::maze::{coordinates::Coordinates, Maze};
+
+    use super::TextStyle;
+
+    #[test]
+    fn test_text_sizes() {
+        let maze = Maze::<9>::with_seed(Coordinates::new(0, 0), Coordinates::new(8, 8), 30, 1);
+        let lines = |style: TextStyle| style.draw(&maze).lines().count();
+
+        assert_eq!(lines(TextStyle::BoxDrawing), 2 * 9 + 1);
+        assert_eq!(lines(TextStyle::HalfBlock), 5);
+        assert_eq!(lines(TextStyle::Braille), 3);
+
+        let braille = TextStyle::Braille.draw(&maze);
+        let first = braille.lines().next().unwrap();
+        assert!(first
+            .chars()
+            .any(|c| ('\u{2800}'..='\u{28ff}').contains(&c)));
+
+        assert_eq!("half-block".parse(), Ok(TextStyle::HalfBlock));
+        assert!("ascii".parse::<TextStyle>().is_err());
+    }
+}
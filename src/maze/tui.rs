@@ -0,0 +1,712 @@
+//! Full-screen terminal application for exploring mazes and path finders
+//!
+//! The maze is drawn on the alternate screen next to panels holding the
+//! settings, live statistics of the running search and the key bindings. A
+//! cursor moves over the maze to place the start and the goal and to draw or
+//! erase walls. Mazes larger than the terminal scroll to follow the cursor.
+//!
+//! Mazes are const generic, so only the sides in [`SIZES`] can be chosen.
+
+use std::{
+    io::{self, Stdout, Write},
+    time::{Duration, Instant},
+};
+
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    style::{Color, Stylize},
+    terminal, QueueableCommand,
+};
+use rand::Rng;
+
+use crate::{agent::Agent, environment::Environment, simulator::SharedAgent};
+
+use super::{
+    agent::{
+        astar::AStarFinder,
+        graph_based::{BFSFinder, DFSFinder},
+        greedy::GreedyFinder,
+        PathFinder, RandomFinder,
+    },
+    coordinates::{Coordinates, Direction},
+    generator::{RbGenerator, WalkGenerator},
+    render::{search_cells, Cell, RawScreen, DEFAULT_DELAY},
+    with_size, Ground, Maze, SIZES,
+};
+
+/// Shortest and longest delays between two search steps.
+const DELAY_RANGE: (Duration, Duration) = (Duration::from_millis(1), Duration::from_secs(1));
+
+/// Columns taken by the panel, right of the maze.
+const PANEL_WIDTH: u16 = 40;
+
+/// Algorithm carving the generated mazes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GeneratorKind {
+    Backtracking,
+    RandomWalk,
+}
+
+impl GeneratorKind {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Backtracking => "backtracking",
+            Self::RandomWalk => "random walk",
+        }
+    }
+
+    fn next(&self) -> Self {
+        match self {
+            Self::Backtracking => Self::RandomWalk,
+            Self::RandomWalk => Self::Backtracking,
+        }
+    }
+
+    fn generate<const N: usize>(
+        &self,
+        settings: &Settings,
+        start: Coordinates,
+        goal: Coordinates,
+    ) -> Maze<N> {
+        let mut seed = [0; 32];
+        seed[..8].copy_from_slice(&settings.seed.to_le_bytes());
+
+        let maze = match self {
+            Self::Backtracking => Maze::with_generator(
+                start,
+                goal,
+                settings.density,
+                settings.seed,
+                RbGenerator::new(Some(seed)),
+            ),
+            Self::RandomWalk => Maze::with_generator(
+                start,
+                goal,
+                settings.density,
+                settings.seed,
+                WalkGenerator::new(Some(seed)),
+            ),
+        };
+
+        maze.with_frontier_trace(true)
+    }
+}
+
+/// Frontier manager of the [`PathFinder`] solving the maze.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FinderKind {
+    AStar,
+    Greedy,
+    Bfs,
+    Dfs,
+    Random,
+}
+
+impl FinderKind {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::AStar => "A*",
+            Self::Greedy => "greedy",
+            Self::Bfs => "BFS",
+            Self::Dfs => "DFS",
+            Self::Random => "random",
+        }
+    }
+
+    fn next(&self) -> Self {
+        match self {
+            Self::AStar => Self::Greedy,
+            Self::Greedy => Self::Bfs,
+            Self::Bfs => Self::Dfs,
+            Self::Dfs => Self::Random,
+            Self::Random => Self::AStar,
+        }
+    }
+
+    fn build<const N: usize>(&self, maze: &Maze<N>) -> SharedAgent<Maze<N>, ()> {
+        match self {
+            Self::AStar => Box::new(PathFinder::<N, AStarFinder>::new(maze)),
+            Self::Greedy => Box::new(PathFinder::<N, GreedyFinder>::new(maze)),
+            Self::Bfs => Box::new(PathFinder::<N, BFSFinder>::new(maze)),
+            Self::Dfs => Box::new(PathFinder::<N, DFSFinder>::new(maze)),
+            Self::Random => Box::new(PathFinder::<N, RandomFinder>::new(maze)),
+        }
+    }
+}
+
+/// Choices kept when the maze is resized.
+#[derive(Debug, Clone)]
+struct Settings {
+    size: usize,
+    density: u8,
+    generator: GeneratorKind,
+    finder: FinderKind,
+    seed: u64,
+    delay: Duration,
+}
+
+/// Why a [`Session`] ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Exit {
+    Quit,
+    /// The size changed, a new session must start.
+    Resize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Editing,
+    Running,
+    Paused,
+    Finished,
+}
+
+impl State {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Editing => "editing",
+            Self::Running => "running",
+            Self::Paused => "paused",
+            Self::Finished => "finished",
+        }
+    }
+}
+
+/// The terminal application.
+///
+/// ```no_run
+/// use std::io::stdout;
+///
+/// use maze::maze::tui::App;
+///
+/// App::new(stdout()).run().unwrap();
+/// ```
+pub struct App {
+    stdout: Stdout,
+    settings: Settings,
+}
+
+impl App {
+    pub fn new(stdout: Stdout) -> Self {
+        Self {
+            stdout,
+            settings: Settings {
+                size: 20,
+                density: 30,
+                generator: GeneratorKind::Backtracking,
+                finder: FinderKind::AStar,
+                seed: rand::thread_rng().gen(),
+                delay: DEFAULT_DELAY,
+            },
+        }
+    }
+
+    /// Sets the side of the first maze, rounded up to one of [`SIZES`].
+    pub fn with_size(mut self, size: usize) -> Self {
+        self.settings.size = SIZES
+            .into_iter()
+            .find(|side| *side >= size)
+            .unwrap_or(SIZES[SIZES.len() - 1]);
+        self
+    }
+
+    /// Sets the percentage of cells blocked before carving.
+    ///
+    /// # Panics
+    /// Panics if the density is higher than 100.
+    pub fn with_density(mut self, density: u8) -> Self {
+        assert!(density <= 100);
+        self.settings.density = density;
+        self
+    }
+
+    /// Sets the seed of the first maze.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.settings.seed = seed;
+        self
+    }
+
+    /// Runs the application until the user quits.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the terminal can't be
+    /// controlled.
+    pub fn run(mut self) -> io::Result<()> {
+        let _screen = RawScreen::enter()?;
+
+        self.run_sessions()
+    }
+
+    fn run_sessions(&mut self) -> io::Result<()> {
+        loop {
+            let exit = with_size!(self.settings.size, N => {
+                Session::<N>::new(&mut self.settings).run(&mut self.stdout)?
+            });
+
+            if exit == Exit::Quit {
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl Default for App {
+    fn default() -> Self {
+        Self::new(io::stdout())
+    }
+}
+
+/// Cells of the maze shown on the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Viewport {
+    /// Top left cell shown.
+    origin: Coordinates,
+    rows: usize,
+    columns: usize,
+}
+
+impl Viewport {
+    /// The largest viewport of a maze of side `N` fitting next to the panel
+    /// of a terminal of `(columns, rows)`, scrolled as little as possible
+    /// for the cursor to be shown.
+    fn fit<const N: usize>(self, terminal: (u16, u16), cursor: Coordinates) -> Self {
+        let (columns, rows) = terminal;
+        // Two characters per cell, the title and message lines around
+        let columns = (columns.saturating_sub(PANEL_WIDTH + 4) / 2).clamp(1, N as u16) as i64;
+        let rows = rows.saturating_sub(4).clamp(1, N as u16) as i64;
+
+        let scroll = |origin: i64, cursor: i64, visible: i64| {
+            origin
+                .clamp(cursor - visible + 1, cursor)
+                .clamp(0, N as i64 - visible)
+        };
+
+        Self {
+            origin: Coordinates::new(
+                scroll(self.origin.x, cursor.x, rows),
+                scroll(self.origin.y, cursor.y, columns),
+            ),
+            rows: rows as usize,
+            columns: columns as usize,
+        }
+    }
+}
+
+/// Exploration of a maze of a given size.
+struct Session<'a, const N: usize> {
+    settings: &'a mut Settings,
+    maze: Maze<N>,
+    cursor: Coordinates,
+    view: Viewport,
+    finder: Option<SharedAgent<Maze<N>, ()>>,
+    state: State,
+    elapsed: Duration,
+    message: String,
+}
+
+impl<'a, const N: usize> Session<'a, N> {
+    fn new(settings: &'a mut Settings) -> Self {
+        let start = Coordinates::new(0, 0);
+        let goal = Coordinates::new(N as i64 - 1, N as i64 - 1);
+
+        Self {
+            maze: settings.generator.generate(settings, start, goal),
+            settings,
+            cursor: start,
+            view: Viewport {
+                origin: start,
+                rows: N,
+                columns: N,
+            },
+            finder: None,
+            state: State::Editing,
+            elapsed: Duration::ZERO,
+            message: String::new(),
+        }
+    }
+
+    fn run(mut self, stdout: &mut Stdout) -> io::Result<Exit> {
+        stdout.queue(terminal::Clear(terminal::ClearType::All))?;
+
+        loop {
+            self.view = self.view.fit::<N>(terminal::size()?, self.cursor);
+            self.draw(stdout)?;
+
+            if self.state == State::Running && !event::poll(self.settings.delay)? {
+                self.step();
+                continue;
+            }
+
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    if let Some(exit) = self.handle(key) {
+                        return Ok(exit);
+                    }
+                }
+                Event::Resize(..) => {
+                    stdout.queue(terminal::Clear(terminal::ClearType::All))?;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Regenerates the maze from the settings, keeping start and goal.
+    fn generate(&mut self) {
+        let (start, goal) = (self.maze.initial_position, self.maze.target_position);
+
+        self.maze = self.settings.generator.generate(self.settings, start, goal);
+        self.clear();
+    }
+
+    /// Drops the search and its trace.
+    fn clear(&mut self) {
+        self.finder = None;
+        self.maze.reset(None);
+        self.state = State::Editing;
+        self.elapsed = Duration::ZERO;
+    }
+
+    fn start(&mut self) {
+        self.clear();
+        self.finder = Some(self.settings.finder.build(&self.maze));
+        self.message.clear();
+    }
+
+    fn step(&mut self) {
+        let Some(finder) = self.finder.as_mut() else {
+            return;
+        };
+
+        if !finder.should_stop() {
+            let start = Instant::now();
+
+            if finder.act(&mut self.maze).is_err() {
+                self.message = "The finder failed to act".to_string();
+            }
+            self.elapsed += start.elapsed();
+        }
+
+        if finder.should_stop() {
+            self.state = State::Finished;
+            self.message = if self.maze.is_terminal() {
+                "Goal reached".to_string()
+            } else {
+                "The goal can't be reached".to_string()
+            };
+        }
+    }
+
+    fn move_cursor(&mut self, direction: Direction) {
+        let next = self.cursor.next(direction);
+
+        if self.maze.get(&next).is_some() {
+            self.cursor = next;
+        }
+    }
+
+    fn handle(&mut self, key: KeyEvent) -> Option<Exit> {
+        let on_endpoint =
+            self.cursor == self.maze.initial_position || self.cursor == self.maze.target_position;
+
+        // Rows are x and columns are y
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return Some(Exit::Quit)
+            }
+            KeyCode::Char('q') | KeyCode::Esc => return Some(Exit::Quit),
+            KeyCode::Up | KeyCode::Char('k') => self.move_cursor(Direction::West),
+            KeyCode::Down | KeyCode::Char('j') => self.move_cursor(Direction::East),
+            KeyCode::Left | KeyCode::Char('h') => self.move_cursor(Direction::North),
+            KeyCode::Right | KeyCode::Char('l') => self.move_cursor(Direction::South),
+            KeyCode::Char('s') if self.cursor != self.maze.target_position => {
+                self.maze.reset_to(self.cursor);
+                self.clear();
+            }
+            KeyCode::Char('g') if self.cursor != self.maze.initial_position => {
+                if let Some(ground) = self.maze.get_mut(&self.cursor) {
+                    *ground = Ground::Free;
+                }
+                self.maze.target_position = self.cursor;
+                self.clear();
+            }
+            KeyCode::Char('w') if !on_endpoint => {
+                if let Some(ground) = self.maze.get_mut(&self.cursor) {
+                    *ground = match ground {
+                        Ground::Blocked => Ground::Free,
+                        _ => Ground::Blocked,
+                    };
+                }
+                self.clear();
+            }
+            KeyCode::Char(' ') => match self.state {
+                State::Editing | State::Finished => {
+                    self.start();
+                    self.state = State::Running;
+                }
+                State::Running => self.state = State::Paused,
+                State::Paused => self.state = State::Running,
+            },
+            KeyCode::Char('n') => {
+                if matches!(self.state, State::Editing | State::Finished) {
+                    self.start();
+                }
+                self.state = State::Paused;
+                self.step();
+            }
+            KeyCode::Char('x') => self.clear(),
+            KeyCode::Char('f') => {
+                self.settings.finder = self.settings.finder.next();
+                self.clear();
+            }
+            KeyCode::Char('o') => {
+                self.settings.generator = self.settings.generator.next();
+                self.generate();
+            }
+            KeyCode::Char('m') => {
+                self.settings.seed = rand::thread_rng().gen();
+                self.generate();
+            }
+            KeyCode::Char('<') | KeyCode::Char(',') => {
+                self.settings.density = self.settings.density.saturating_sub(5);
+                self.generate();
+            }
+            KeyCode::Char('>') | KeyCode::Char('.') => {
+                self.settings.density = (self.settings.density + 5).min(100);
+                self.generate();
+            }
+            KeyCode::Char('[') | KeyCode::Char(']') => {
+                let index = SIZES.iter().position(|size| *size == N).unwrap_or(0);
+                let index = match key.code {
+                    KeyCode::Char('[') => index.saturating_sub(1),
+                    _ => (index + 1).min(SIZES.len() - 1),
+                };
+
+                if SIZES[index] != N {
+                    self.settings.size = SIZES[index];
+                    return Some(Exit::Resize);
+                }
+            }
+            KeyCode::Char('+') => {
+                self.settings.delay = (self.settings.delay / 2).max(DELAY_RANGE.0);
+            }
+            KeyCode::Char('-') => {
+                self.settings.delay = (self.settings.delay * 2).min(DELAY_RANGE.1);
+            }
+            _ => {}
+        }
+
+        None
+    }
+
+    fn panel(&self) -> Vec<String> {
+        let trace = self.maze.trace();
+        let row = |name: &str, value: String, keys: &str| format!("{name:<11}{value:<14}{keys}");
+
+        let mut lines = vec![
+            format!("{}", "Settings".bold()),
+            row("size", format!("{N}x{N}"), "[ ]"),
+            row("density", format!("{}%", self.settings.density), "< >"),
+            row("generator", self.settings.generator.name().to_string(), "o"),
+            row("finder", self.settings.finder.name().to_string(), "f"),
+            row("seed", self.settings.seed.to_string(), "m"),
+            row(
+                "delay",
+                format!("{} ms", self.settings.delay.as_millis()),
+                "+ -",
+            ),
+            String::new(),
+            format!("{}", "Search".bold()),
+            row("state", self.state.name().to_string(), ""),
+            row("steps", trace.steps.to_string(), ""),
+            row("expanded", trace.visited.len().to_string(), ""),
+            row("frontier", trace.frontier.len().to_string(), ""),
+            row(
+                "path length",
+                (self.maze.current_path.len() - 1).to_string(),
+                "",
+            ),
+            row("elapsed", format!("{:.2?}", self.elapsed), ""),
+            String::new(),
+            format!("{}", "Legend".bold()),
+        ];
+
+        lines.extend(
+            Cell::ALL
+                .iter()
+                .map(|cell| format!("{cell}{}", cell.label())),
+        );
+        lines.push("◼ wall".to_string());
+        lines.push(format!("{}cursor", "▣ ".with(Color::Magenta)));
+
+        lines.extend([
+            String::new(),
+            format!("{}", "Keys".bold()),
+            "arrows/hjkl  move the cursor".to_string(),
+            "s g          place start, goal".to_string(),
+            "w            draw or erase a wall".to_string(),
+            "space        run or pause".to_string(),
+            "n            single step".to_string(),
+            "x            clear the search".to_string(),
+            "q            quit".to_string(),
+        ]);
+
+        lines
+    }
+
+    fn draw(&self, stdout: &mut Stdout) -> io::Result<()> {
+        let cells = search_cells(&self.maze);
+        let clear = terminal::Clear(terminal::ClearType::UntilNewLine);
+
+        stdout.queue(cursor::MoveTo(0, 0))?;
+        write!(stdout, "{}", "Maze explorer".bold())?;
+
+        let Viewport {
+            origin,
+            rows,
+            columns,
+        } = self.view;
+
+        for (line_row, (x, row)) in cells
+            .iter()
+            .enumerate()
+            .skip(origin.x as usize)
+            .take(rows)
+            .enumerate()
+        {
+            let mut line = String::new();
+
+            for (y, cell) in row.iter().enumerate().skip(origin.y as usize).take(columns) {
+                let coords = Coordinates::new(x as i64, y as i64);
+
+                if coords == self.cursor {
+                    line.push_str(&format!("{}", "▣ ".with(Color::Magenta)));
+                    continue;
+                }
+
+                match (cell, self.maze.get(&coords)) {
+                    (Some(cell), _) => line.push_str(&cell.to_string()),
+                    (None, Some(Ground::Blocked)) => line.push_str("◼ "),
+                    (None, _) => line.push_str("  "),
+                }
+            }
+
+            stdout.queue(cursor::MoveTo(0, line_row as u16 + 2))?;
+            write!(stdout, "{line}")?;
+        }
+
+        let column = 2 * columns as u16 + 4;
+        for (row, line) in self.panel().iter().enumerate() {
+            stdout.queue(cursor::MoveTo(column, row as u16 + 2))?;
+            write!(stdout, "{line}")?;
+            stdout.queue(clear)?;
+        }
+
+        stdout.queue(cursor::MoveTo(0, rows as u16 + 3))?;
+        write!(stdout, "{}", self.message)?;
+        stdout.queue(clear)?;
+
+        stdout.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    use crate::maze::{coordinates::Coordinates, Ground};
+
+    use super::{Exit, FinderKind, GeneratorKind, Session, Settings, State, Viewport};
+
+    fn press(session: &mut Session<'_, 8>, keys: &str) -> Option<Exit> {
+        keys.chars()
+            .filter_map(|key| session.handle(KeyEvent::new(KeyCode::Char(key), KeyModifiers::NONE)))
+            .last()
+    }
+
+    #[test]
+    fn test_edit_and_solve() {
+        let mut settings = Settings {
+            size: 8,
+            density: 0,
+            generator: GeneratorKind::RandomWalk,
+            finder: FinderKind::Bfs,
+            seed: 0,
+            delay: Duration::ZERO,
+        };
+        let mut session = Session::<8>::new(&mut settings);
+
+        // The cursor stays inside the maze
+        press(&mut session, "kkhjjl");
+        assert_eq!(session.cursor, Coordinates::new(2, 1));
+
+        // Walls are toggled, but never on the start
+        press(&mut session, "w");
+        assert_eq!(
+            session.maze.get(&Coordinates::new(2, 1)),
+            Some(Ground::Blocked)
+        );
+        press(&mut session, "w");
+        assert_eq!(
+            session.maze.get(&Coordinates::new(2, 1)),
+            Some(Ground::Free)
+        );
+        press(&mut session, "kkhws");
+        assert_eq!(
+            session.maze.get(&Coordinates::new(0, 0)),
+            Some(Ground::Free)
+        );
+
+        press(&mut session, "jjjg");
+        assert_eq!(session.maze.target_position, Coordinates::new(3, 0));
+
+        // A single step pauses the search, space resumes it
+        press(&mut session, "n");
+        assert_eq!(session.state, State::Paused);
+        assert_eq!(session.maze.trace().steps, 1);
+        press(&mut session, " ");
+        assert_eq!(session.state, State::Running);
+        while session.state == State::Running {
+            session.step();
+        }
+        assert_eq!(session.maze.current_path.len(), 4);
+        press(&mut session, "x");
+        assert_eq!(session.state, State::Editing);
+
+        assert_eq!(press(&mut session, "]"), Some(Exit::Resize));
+        assert_eq!(session.settings.size, 10);
+        assert_eq!(press(&mut session, "q"), Some(Exit::Quit));
+    }
+
+    #[test]
+    fn test_viewport_follows_cursor() {
+        let view = Viewport {
+            origin: Coordinates::new(0, 0),
+            rows: 100,
+            columns: 100,
+        };
+
+        // 80x24 leaves 20 rows and 18 columns of cells next to the panel
+        let view = view.fit::<100>((80, 24), Coordinates::new(50, 5));
+        assert_eq!((view.rows, view.columns), (20, 18));
+        assert_eq!(view.origin, Coordinates::new(31, 0));
+
+        let view = view.fit::<100>((80, 24), Coordinates::new(40, 99));
+        assert_eq!(view.origin, Coordinates::new(31, 82));
+
+        // Small mazes are shown whole
+        let view = view.fit::<8>((200, 60), Coordinates::new(7, 7));
+        assert_eq!(
+            view,
+            Viewport {
+                origin: Coordinates::new(0, 0),
+                rows: 8,
+                columns: 8
+            }
+        );
+    }
+}
@@ -0,0 +1,368 @@
+//! Browser visualizer
+//!
+//! [`WebRenderer`] serves a canvas page on localhost and streams every frame
+//! of a run to it with Server-Sent Events. The page zooms with the mouse
+//! wheel, pans by dragging, and pauses, steps or aborts the simulation. Like
+//! the [`SearchRenderer`](super::render::SearchRenderer), it shows the
+//! frontier of mazes built [`with_frontier_trace`](Maze::with_frontier_trace).
+//!
+//! The server only needs the standard library and is enabled by the `web`
+//! feature.
+
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Condvar, Mutex, MutexGuard,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use super::{
+    coordinates::Coordinates,
+    render::{search_cells, Cell, DEFAULT_DELAY},
+    Ground, Maze,
+};
+
+use crate::render::{Control, Renderer};
+
+/// The page served at `/`.
+const PAGE: &str = include_str!("web.html");
+
+/// Time between two checks for new connections.
+const ACCEPT_INTERVAL: Duration = Duration::from_millis(50);
+
+/// State shared by the renderer and the connections.
+#[derive(Default)]
+struct Session {
+    /// Open event streams.
+    clients: Vec<TcpStream>,
+    /// Last frame sent, for late clients.
+    frame: Option<String>,
+    paused: bool,
+    /// Frames allowed while paused.
+    steps: usize,
+    aborted: bool,
+}
+
+impl Session {
+    /// Sends an event to every client, forgetting the disconnected ones.
+    fn broadcast(&mut self, event: &str, data: &str) {
+        let message = format!("event: {event}\ndata: {data}\n\n");
+
+        self.clients
+            .retain_mut(|client| client.write_all(message.as_bytes()).is_ok());
+    }
+
+    fn broadcast_status(&mut self) {
+        let status = format!(
+            "{{\"paused\":{},\"aborted\":{}}}",
+            self.paused, self.aborted
+        );
+        self.broadcast("status", &status);
+    }
+}
+
+#[derive(Default)]
+struct Shared {
+    session: Mutex<Session>,
+    changed: Condvar,
+    running: AtomicBool,
+}
+
+impl Shared {
+    fn session(&self) -> MutexGuard<'_, Session> {
+        self.session.lock().expect("Web session poisoned")
+    }
+}
+
+/// Renders a [`Maze`] search in the browser.
+///
+/// The server listens on `127.0.0.1` from [`WebRenderer::bind`] until the
+/// renderer is dropped.
+pub struct WebRenderer {
+    address: SocketAddr,
+    delay: Duration,
+    start_paused: bool,
+    shared: Arc<Shared>,
+    server: Option<JoinHandle<()>>,
+}
+
+impl WebRenderer {
+    /// Starts serving on the given port of localhost, `0` picking any free
+    /// port.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the port can't be bound.
+    pub fn bind(port: u16) -> io::Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+        listener.set_nonblocking(true)?;
+
+        let address = listener.local_addr()?;
+        let shared = Arc::new(Shared::default());
+        shared.running.store(true, Ordering::SeqCst);
+
+        let server = {
+            let shared = Arc::clone(&shared);
+            thread::spawn(move || serve(listener, shared))
+        };
+
+        Ok(Self {
+            address,
+            delay: DEFAULT_DELAY,
+            start_paused: false,
+            shared,
+            server: Some(server),
+        })
+    }
+
+    /// Sets the delay between frames.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Starts the simulation paused, waiting for the page to resume or step
+    /// it.
+    pub fn paused(mut self) -> Self {
+        self.start_paused = true;
+        self
+    }
+
+    /// Address of the page.
+    pub fn url(&self) -> String {
+        format!("http://{}/", self.address)
+    }
+}
+
+impl Drop for WebRenderer {
+    fn drop(&mut self) {
+        self.shared.running.store(false, Ordering::SeqCst);
+
+        if let Some(server) = self.server.take() {
+            let _ = server.join();
+        }
+    }
+}
+
+/// Accepts connections until the renderer is dropped.
+fn serve(listener: TcpListener, shared: Arc<Shared>) {
+    while shared.running.load(Ordering::SeqCst) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                let shared = Arc::clone(&shared);
+                thread::spawn(move || {
+                    let _ = handle(stream, &shared);
+                });
+            }
+            Err(error) if error.kind() == io::ErrorKind::WouldBlock => {
+                thread::sleep(ACCEPT_INTERVAL)
+            }
+            Err(_) => return,
+        }
+    }
+}
+
+/// Answers a single request.
+fn handle(mut stream: TcpStream, shared: &Shared) -> io::Result<()> {
+    stream.set_nonblocking(false)?;
+
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+
+    // Skip the headers
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let mut parts = request.split_whitespace();
+    let route = (
+        parts.next().unwrap_or_default(),
+        parts.next().unwrap_or_default(),
+    );
+
+    match route {
+        ("GET", "/") => {
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{PAGE}",
+                PAGE.len()
+            )
+        }
+        ("GET", "/events") => {
+            stream.write_all(
+                b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n",
+            )?;
+
+            let mut session = shared.session();
+            if let Some(frame) = &session.frame {
+                write!(stream, "event: frame\ndata: {frame}\n\n")?;
+            }
+            session.clients.push(stream);
+            session.broadcast_status();
+
+            Ok(())
+        }
+        ("POST", action @ ("/pause" | "/resume" | "/step" | "/abort")) => {
+            let mut session = shared.session();
+
+            match action {
+                "/pause" => session.paused = true,
+                "/resume" => session.paused = false,
+                "/step" => {
+                    session.paused = true;
+                    session.steps += 1;
+                }
+                _ => session.aborted = true,
+            }
+            session.broadcast_status();
+            shared.changed.notify_all();
+
+            stream.write_all(b"HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n")
+        }
+        _ => stream
+            .write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"),
+    }
+}
+
+/// Encodes a frame as JSON, the cells being a string of one character per
+/// cell, row after row.
+fn frame<const N: usize>(environment: &Maze<N>) -> String {
+    let trace = environment.trace();
+    let mut cells = String::with_capacity(N * N);
+
+    for (x, row) in search_cells(environment).iter().enumerate() {
+        for (y, cell) in row.iter().enumerate() {
+            cells.push(match cell {
+                Some(Cell::Visited) => 'v',
+                Some(Cell::Frontier) => 'f',
+                Some(Cell::Path) => 'p',
+                Some(Cell::Start) => 's',
+                Some(Cell::Goal) => 'g',
+                None => match environment.get(&Coordinates::new(x as i64, y as i64)) {
+                    Some(Ground::Blocked) => '#',
+                    Some(Ground::Door(_)) => 'd',
+                    Some(Ground::Key(_)) => 'k',
+                    Some(Ground::Item(_)) => 'i',
+                    _ => '.',
+                },
+            });
+        }
+    }
+
+    format!(
+        "{{\"size\":{N},\"cells\":\"{cells}\",\"step\":{},\"expanded\":{},\"frontier\":{},\"length\":{}}}",
+        trace.steps,
+        trace.visited.len(),
+        trace.frontier.len(),
+        environment.current_path.len() - 1
+    )
+}
+
+impl<const N: usize> Renderer<Maze<N>> for WebRenderer {
+    fn setup(&mut self) {
+        let mut session = self.shared.session();
+
+        session.paused = self.start_paused;
+        session.steps = 0;
+        session.aborted = false;
+        session.broadcast_status();
+    }
+
+    fn render(&mut self, environment: &Maze<N>) {
+        let frame = frame(environment);
+        let mut session = self.shared.session();
+
+        session.broadcast("frame", &frame);
+        session.frame = Some(frame);
+
+        while session.paused && session.steps == 0 && !session.aborted {
+            session = self
+                .shared
+                .changed
+                .wait(session)
+                .expect("Web session poisoned");
+        }
+        session.steps = session.steps.saturating_sub(1);
+        drop(session);
+
+        thread::sleep(self.delay);
+    }
+
+    fn teardown(&mut self) {
+        self.shared.session().broadcast("end", "{}");
+    }
+
+    fn control(&self) -> Control {
+        if self.shared.session().aborted {
+            Control::Abort
+        } else {
+            Control::Continue
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::{SocketAddr, TcpStream},
+        thread,
+        time::Duration,
+    };
+
+    use crate::{
+        agent::Agent,
+        maze::{
+            agent::{astar::AStarFinder, PathFinder},
+            coordinates::Coordinates,
+            Maze,
+        },
+        simulator::Simulator,
+    };
+
+    use super::WebRenderer;
+
+    fn request(address: SocketAddr, request: &str) -> String {
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn test_pause_and_abort() {
+        let renderer = WebRenderer::bind(0).unwrap().paused();
+        let address = renderer.address;
+
+        let page = request(address, "GET / HTTP/1.1\r\n\r\n");
+        assert!(page.starts_with("HTTP/1.1 200 OK"));
+        assert!(page.contains("<canvas"));
+
+        let maze = Maze::<8>::with_seed(Coordinates::new(0, 0), Coordinates::new(7, 7), 20, 3);
+        let finder = PathFinder::<8, AStarFinder>::new(&maze);
+        let mut simulator = Simulator::new(finder, renderer, maze);
+
+        let run = thread::spawn(move || {
+            simulator.simulate().unwrap();
+            simulator.aborted()
+        });
+
+        // The first frame waits for the page
+        thread::sleep(Duration::from_millis(200));
+        assert!(!run.is_finished());
+
+        let step = request(address, "POST /step HTTP/1.1\r\n\r\n");
+        assert!(step.starts_with("HTTP/1.1 204"));
+
+        request(address, "POST /abort HTTP/1.1\r\n\r\n");
+        assert!(run.join().unwrap());
+    }
+}
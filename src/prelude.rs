@@ -0,0 +1,32 @@
+//! Most used types, to import at once
+//!
+//! ```
+//! use maze::prelude::*;
+//!
+//! let maze = Maze::<16>::with_seed(Coordinates::new(0, 0), Coordinates::new(15, 15), 20, 7);
+//! let finder = PathFinder::<16, AStarFinder>::new(&maze);
+//!
+//! let mut simulator = Simulator::new(finder, Headless, maze);
+//! simulator.run_episodes(1).unwrap();
+//! ```
+
+pub use crate::{
+    agent::Agent,
+    environment::Environment,
+    maze::{
+        agent::{
+            astar::AStarFinder,
+            graph_based::{BFSFinder, DFSFinder},
+            greedy::GreedyFinder,
+            uniform_cost::UniformCostFinder,
+            FrontierManager, PathFinder, RandomFinder,
+        },
+        coordinates::{Coordinates, Direction},
+        render::{DefaultRenderer, InteractiveRenderer, SearchRenderer, TextRenderer},
+        text::TextStyle,
+        Connectivity, Ground, Maze, Path,
+    },
+    render::{Headless, Renderer},
+    search::{NodeId, Problem, Search, SearchArena, Solution},
+    simulator::Simulator,
+};
@@ -0,0 +1,512 @@
+//! Sliding-tile puzzle
+//!
+//! The N-puzzle: tiles numbered from `1` sit on a square board with a single
+//! blank, and slide into the blank one at a time until they are in order,
+//! the blank in the bottom right corner. The 8-puzzle is played on a 3x3
+//! board, the 15-puzzle on a 4x4 one.
+//!
+//! Only half of the arrangements of the tiles can be solved, see
+//! [`Board::is_solvable`]. A [`SlidingPuzzle`] is both an [`Environment`]
+//! and a [`Problem`], solved by the [`Planner`](crate::search::Planner)
+//! agent with any search strategy.
+
+use std::{error::Error, fmt};
+
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaChaRng;
+
+use crate::{environment::Environment, search::Problem};
+
+/// Errors of the sliding-tile puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PuzzleError {
+    /// Boards are between 2 and 16 tiles wide.
+    InvalidWidth { width: usize },
+    /// The board doesn't have `width * width` tiles.
+    WrongSize { width: usize, tiles: usize },
+    /// The tiles are not the numbers from `0`, the blank, to
+    /// `width * width - 1`, each once.
+    NotPermutation,
+    /// The blank can't move that way from the edge of the board.
+    InvalidMove,
+}
+
+impl fmt::Display for PuzzleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidWidth { width } => {
+                write!(f, "a board must be 2 to 16 tiles wide, not {width}")
+            }
+            Self::WrongSize { width, tiles } => write!(
+                f,
+                "a board of width {width} has {} tiles, not {tiles}",
+                width * width
+            ),
+            Self::NotPermutation => write!(f, "every tile must appear exactly once"),
+            Self::InvalidMove => write!(f, "invalid move"),
+        }
+    }
+}
+
+impl Error for PuzzleError {}
+
+/// Move of the blank, swapping it with the tile it moves to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Slide {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Slide {
+    pub const ALL: [Slide; 4] = [Slide::Up, Slide::Down, Slide::Left, Slide::Right];
+}
+
+/// Tiles of the puzzle, row after row, `0` being the blank.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Board {
+    width: usize,
+    tiles: Vec<u8>,
+}
+
+impl Board {
+    /// Board of the given tiles, row after row, `0` being the blank.
+    pub fn new(width: usize, tiles: Vec<u8>) -> Result<Self, PuzzleError> {
+        if !(2..=16).contains(&width) {
+            return Err(PuzzleError::InvalidWidth { width });
+        }
+        if tiles.len() != width * width {
+            return Err(PuzzleError::WrongSize {
+                width,
+                tiles: tiles.len(),
+            });
+        }
+
+        let mut seen = vec![false; tiles.len()];
+        for tile in &tiles {
+            match seen.get_mut(*tile as usize) {
+                Some(seen) if !*seen => *seen = true,
+                _ => return Err(PuzzleError::NotPermutation),
+            }
+        }
+
+        Ok(Self { width, tiles })
+    }
+
+    /// Board with every tile in order.
+    ///
+    /// # Panics
+    /// Panics if the board doesn't have between 2 and 256 cells.
+    pub fn solved(width: usize) -> Self {
+        assert!((2..=256).contains(&(width * width)));
+        let cells = width * width;
+
+        Self {
+            width,
+            tiles: (1..cells).chain([0]).map(|tile| tile as u8).collect(),
+        }
+    }
+
+    /// Random board that can be solved.
+    ///
+    /// # Panics
+    /// Panics if the board doesn't have between 2 and 256 cells.
+    pub fn random(width: usize, rng: &mut impl Rng) -> Self {
+        let mut board = Self::solved(width);
+        board.tiles.shuffle(rng);
+
+        // Swapping two tiles flips the parity of the board
+        if !board.is_solvable() {
+            let mut numbered = (0..board.tiles.len()).filter(|index| board.tiles[*index] != 0);
+            let (first, second) = (numbered.next().unwrap(), numbered.next().unwrap());
+            board.tiles.swap(first, second);
+        }
+
+        board
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn tiles(&self) -> &[u8] {
+        &self.tiles
+    }
+
+    fn blank(&self) -> usize {
+        self.tiles.iter().position(|tile| *tile == 0).unwrap()
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.tiles
+            .iter()
+            .enumerate()
+            .all(|(index, tile)| *tile as usize == (index + 1) % self.tiles.len())
+    }
+
+    /// Whether the tiles can be put in order.
+    ///
+    /// Counts the pairs of numbered tiles out of order. Every slide keeps
+    /// the parity of that count on boards of odd width, and flips it along
+    /// with the row of the blank when sliding vertically on boards of even
+    /// width.
+    pub fn is_solvable(&self) -> bool {
+        let numbered: Vec<_> = self.tiles.iter().filter(|tile| **tile != 0).collect();
+        let inversions: usize = (0..numbered.len())
+            .map(|i| {
+                numbered[i + 1..]
+                    .iter()
+                    .filter(|tile| **tile < numbered[i])
+                    .count()
+            })
+            .sum();
+
+        if self.width % 2 == 1 {
+            inversions.is_multiple_of(2)
+        } else {
+            // The solved board has no inversion and the blank on the last row
+            (inversions + self.blank() / self.width) % 2 == (self.width - 1) % 2
+        }
+    }
+
+    /// The board after the blank slides, `None` when it would leave the
+    /// board.
+    pub fn slide(&self, slide: Slide) -> Option<Self> {
+        let blank = self.blank();
+        let (row, column) = (blank / self.width, blank % self.width);
+
+        let target = match slide {
+            Slide::Up if row > 0 => blank - self.width,
+            Slide::Down if row + 1 < self.width => blank + self.width,
+            Slide::Left if column > 0 => blank - 1,
+            Slide::Right if column + 1 < self.width => blank + 1,
+            _ => return None,
+        };
+
+        let mut next = self.clone();
+        next.tiles.swap(blank, target);
+        Some(next)
+    }
+
+    /// Row and column where a tile belongs.
+    fn goal(&self, tile: u8) -> (usize, usize) {
+        let index = tile as usize - 1;
+        (index / self.width, index % self.width)
+    }
+
+    /// Sum of the distances of the tiles to where they belong, moving along
+    /// the rows and columns.
+    pub fn manhattan(&self) -> usize {
+        self.tiles
+            .iter()
+            .enumerate()
+            .filter(|(_, tile)| **tile != 0)
+            .map(|(index, tile)| {
+                let (row, column) = self.goal(*tile);
+                (index / self.width).abs_diff(row) + (index % self.width).abs_diff(column)
+            })
+            .sum()
+    }
+
+    /// Manhattan distance, plus two moves for every tile that has to leave
+    /// its row or column to let another tile of the same line pass.
+    ///
+    /// Both estimates never overestimate the number of moves left, this one
+    /// is closer.
+    pub fn linear_conflict(&self) -> usize {
+        let mut conflicts = 0;
+
+        for line in 0..self.width {
+            // Goal columns of the tiles of the row belonging to it, and goal
+            // rows of the tiles of the column belonging to it, in order
+            let row: Vec<_> = (0..self.width)
+                .map(|column| self.tiles[line * self.width + column])
+                .filter(|tile| *tile != 0 && self.goal(*tile).0 == line)
+                .map(|tile| self.goal(tile).1)
+                .collect();
+            let column: Vec<_> = (0..self.width)
+                .map(|row| self.tiles[row * self.width + line])
+                .filter(|tile| *tile != 0 && self.goal(*tile).1 == line)
+                .map(|tile| self.goal(tile).0)
+                .collect();
+
+            // Tiles left in the line are in order
+            conflicts += row.len() - longest_increasing(&row);
+            conflicts += column.len() - longest_increasing(&column);
+        }
+
+        self.manhattan() + 2 * conflicts
+    }
+}
+
+/// Length of the longest increasing subsequence.
+fn longest_increasing(values: &[usize]) -> usize {
+    let mut tails: Vec<usize> = Vec::new();
+
+    for value in values {
+        match tails.binary_search(value) {
+            Ok(_) => {}
+            Err(index) if index == tails.len() => tails.push(*value),
+            Err(index) => tails[index] = *value,
+        }
+    }
+
+    tails.len()
+}
+
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = (self.tiles.len() - 1).to_string().len();
+
+        for row in self.tiles.chunks(self.width) {
+            let row: Vec<_> = row
+                .iter()
+                .map(|tile| match tile {
+                    0 => format!("{:>digits$}", "."),
+                    tile => format!("{tile:>digits$}"),
+                })
+                .collect();
+
+            writeln!(f, "{}", row.join(" "))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Estimate of the moves left to solve a [`Board`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PuzzleHeuristic {
+    /// See [`Board::manhattan`].
+    #[default]
+    Manhattan,
+    /// See [`Board::linear_conflict`].
+    LinearConflict,
+}
+
+/// Stimuli produced by a [`SlidingPuzzle`] update.
+///
+/// The reward is `1.0` once the board is solved and `0.0` otherwise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PuzzleStimuli {
+    pub board: Board,
+    pub moves: usize,
+    pub reward: f64,
+    pub terminal: bool,
+}
+
+/// The sliding-tile puzzle, one slide at a time.
+#[derive(Debug, Clone)]
+pub struct SlidingPuzzle {
+    initial: Board,
+    board: Board,
+    moves: usize,
+    heuristic: PuzzleHeuristic,
+}
+
+impl SlidingPuzzle {
+    pub fn new(board: Board) -> Self {
+        Self {
+            initial: board.clone(),
+            board,
+            moves: 0,
+            heuristic: PuzzleHeuristic::default(),
+        }
+    }
+
+    /// Puzzle on a random solvable board, fully determined by `seed`.
+    pub fn with_seed(width: usize, seed: u64) -> Self {
+        Self::new(Board::random(width, &mut ChaChaRng::seed_from_u64(seed)))
+    }
+
+    /// Changes the estimate given to the informed searches.
+    pub fn with_heuristic(mut self, heuristic: PuzzleHeuristic) -> Self {
+        self.heuristic = heuristic;
+        self
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Slides played since the start of the episode.
+    pub fn moves(&self) -> usize {
+        self.moves
+    }
+
+    fn stimuli(&self) -> PuzzleStimuli {
+        let terminal = self.board.is_solved();
+
+        PuzzleStimuli {
+            board: self.board.clone(),
+            moves: self.moves,
+            reward: if terminal { 1.0 } else { 0.0 },
+            terminal,
+        }
+    }
+}
+
+impl Environment for SlidingPuzzle {
+    type Error = PuzzleError;
+
+    type Action = Slide;
+
+    type Stimuli = PuzzleStimuli;
+
+    fn initial_stimuli(&self) -> Self::Stimuli {
+        self.stimuli()
+    }
+
+    fn update(&mut self, action: Self::Action) -> Result<Self::Stimuli, Self::Error> {
+        self.board = self.board.slide(action).ok_or(PuzzleError::InvalidMove)?;
+        self.moves += 1;
+
+        Ok(self.stimuli())
+    }
+
+    /// Starts over from the initial board, or from a new random board drawn
+    /// from the seed.
+    fn reset(&mut self, seed: Option<u64>) -> Self::Stimuli {
+        if let Some(seed) = seed {
+            self.initial = Board::random(self.initial.width, &mut ChaChaRng::seed_from_u64(seed));
+        }
+        self.board = self.initial.clone();
+        self.moves = 0;
+
+        self.stimuli()
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.board.is_solved()
+    }
+}
+
+/// Solving the current board, every slide costing one move.
+impl Problem for SlidingPuzzle {
+    type State = Board;
+
+    type Action = Slide;
+
+    fn start(&self) -> Self::State {
+        self.board.clone()
+    }
+
+    fn is_goal(&self, state: &Self::State) -> bool {
+        state.is_solved()
+    }
+
+    fn successors(&self, state: &Self::State) -> Vec<(Self::Action, Self::State, f32)> {
+        Slide::ALL
+            .into_iter()
+            .filter_map(|slide| Some((slide, state.slide(slide)?, 1.0)))
+            .collect()
+    }
+
+    fn heuristic(&self, state: &Self::State) -> f32 {
+        match self.heuristic {
+            PuzzleHeuristic::Manhattan => state.manhattan() as f32,
+            PuzzleHeuristic::LinearConflict => state.linear_conflict() as f32,
+        }
+    }
+}
+
+impl fmt::Display for SlidingPuzzle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.board)?;
+        write!(
+            f,
+            "moves: {}{}",
+            self.moves,
+            if self.board.is_solved() {
+                ", solved!"
+            } else {
+                ""
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+
+    use crate::{
+        agent::Agent,
+        maze::agent::{astar::AStarFinder, graph_based::BFSFinder},
+        render::Headless,
+        search::{solve, Planner, Search},
+        simulator::Simulator,
+    };
+
+    use super::{Board, PuzzleError, PuzzleHeuristic, Slide, SlidingPuzzle};
+
+    #[test]
+    fn test_sliding_puzzle() {
+        assert_eq!(
+            Board::new(3, vec![1, 2, 3]),
+            Err(PuzzleError::WrongSize { width: 3, tiles: 3 })
+        );
+        assert_eq!(
+            Board::new(2, vec![1, 1, 2, 0]),
+            Err(PuzzleError::NotPermutation)
+        );
+        assert_eq!(
+            Board::new(0, vec![]),
+            Err(PuzzleError::InvalidWidth { width: 0 })
+        );
+        assert_eq!(
+            Board::new(1, vec![0]),
+            Err(PuzzleError::InvalidWidth { width: 1 })
+        );
+
+        // Random walks from the solved board stay solvable, swapping two
+        // tiles never is
+        let mut rng = ChaChaRng::seed_from_u64(3);
+        for width in [2, 3, 4] {
+            let mut board = Board::solved(width);
+            for _ in 0..100 {
+                let slide = Slide::ALL[rand::Rng::gen_range(&mut rng, 0..4)];
+                board = board.slide(slide).unwrap_or(board);
+                assert!(board.is_solvable());
+
+                let mut swapped = board.clone();
+                let numbered: Vec<_> = (0..width * width)
+                    .filter(|index| board.tiles()[*index] != 0)
+                    .collect();
+                swapped.tiles.swap(numbered[0], numbered[1]);
+                assert!(!swapped.is_solvable());
+            }
+            assert!(Board::random(width, &mut rng).is_solvable());
+        }
+
+        // 2 and 1 conflict in the first row
+        let board = Board::new(3, vec![2, 1, 3, 4, 5, 6, 7, 8, 0]).unwrap();
+        assert_eq!(board.manhattan(), 2);
+        assert_eq!(board.linear_conflict(), 4);
+        assert!(!board.is_solvable());
+
+        // Both heuristics find optimal solutions, linear conflict expanding
+        // fewer boards
+        let puzzle = SlidingPuzzle::with_seed(3, 11);
+        let manhattan = solve::<_, AStarFinder>(&puzzle).unwrap();
+        let conflict = puzzle
+            .clone()
+            .with_heuristic(PuzzleHeuristic::LinearConflict);
+        let mut search = Search::<_, AStarFinder>::new(&conflict);
+        let linear = search.run(&conflict).unwrap();
+        assert_eq!(manhattan.cost, linear.cost);
+        assert!(linear.expanded <= manhattan.expanded);
+
+        let easy = SlidingPuzzle::new(Board::solved(3).slide(Slide::Up).unwrap());
+        assert_eq!(solve::<_, BFSFinder>(&easy).unwrap().actions, [Slide::Down]);
+
+        // The planner plays the solution
+        let planner = Planner::<_, AStarFinder>::new(&puzzle);
+        let mut simulator = Simulator::new(planner, Headless, puzzle);
+        let stats = simulator.run_episodes(1).unwrap();
+        assert!(stats[0].terminated);
+        assert_eq!(simulator.environment().moves(), manhattan.actions.len());
+    }
+}
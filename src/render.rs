@@ -1,5 +1,26 @@
+//! Rendering
+//!
+//! Environments never draw themselves: a [`Renderer`] observes the
+//! environment after every action of a [`Simulator`](crate::simulator::Simulator)
+//! run. Several renderers can observe the same run by grouping them in a
+//! tuple or a `Vec<Box<dyn Renderer<E>>>`, and [`Headless`] observes nothing.
+//!
+//! Interactive renderers may ask the simulator to stop through
+//! [`Renderer::control`].
+
+use std::{fmt::Display, io::Write};
+
 use crate::environment::Environment;
 
+/// What a simulator should do after rendering a frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Control {
+    #[default]
+    Continue,
+    /// The user asked to stop the simulation.
+    Abort,
+}
+
 /// Renders the environment state to the screen.
 pub trait Renderer<E: Environment> {
     /// Setup renderer.
@@ -10,4 +31,201 @@ pub trait Renderer<E: Environment> {
 
     /// Teardown renderer.
     fn teardown(&mut self);
+
+    /// Checked by the simulator after every frame.
+    fn control(&self) -> Control {
+        Control::Continue
+    }
+}
+
+/// Renderer that draws nothing, for tests, benchmarks and other runs without
+/// a terminal.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Headless;
+
+impl<E: Environment> Renderer<E> for Headless {
+    fn setup(&mut self) {}
+
+    fn render(&mut self, _environment: &E) {}
+
+    fn teardown(&mut self) {}
+}
+
+impl<E: Environment, A: Renderer<E>, B: Renderer<E>> Renderer<E> for (A, B) {
+    fn setup(&mut self) {
+        self.0.setup();
+        self.1.setup();
+    }
+
+    fn render(&mut self, environment: &E) {
+        self.0.render(environment);
+        self.1.render(environment);
+    }
+
+    fn teardown(&mut self) {
+        self.0.teardown();
+        self.1.teardown();
+    }
+
+    fn control(&self) -> Control {
+        self.0.control().max(self.1.control())
+    }
+}
+
+impl<E: Environment> Renderer<E> for Vec<Box<dyn Renderer<E>>> {
+    fn setup(&mut self) {
+        self.iter_mut().for_each(|renderer| renderer.setup());
+    }
+
+    fn render(&mut self, environment: &E) {
+        self.iter_mut()
+            .for_each(|renderer| renderer.render(environment));
+    }
+
+    fn teardown(&mut self) {
+        self.iter_mut().for_each(|renderer| renderer.teardown());
+    }
+
+    fn control(&self) -> Control {
+        self.iter()
+            .map(|renderer| renderer.control())
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+/// Writes every frame, as formatted by the environment's [`Display`], to a
+/// writer such as a file. Frames are separated by a blank line.
+pub struct FrameWriter<W: Write> {
+    writer: W,
+    frames: usize,
+}
+
+impl<W: Write> FrameWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer, frames: 0 }
+    }
+
+    /// Number of frames written so far.
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    /// Gives the writer back.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<E: Environment + Display, W: Write> Renderer<E> for FrameWriter<W> {
+    fn setup(&mut self) {
+        self.frames = 0;
+    }
+
+    fn render(&mut self, environment: &E) {
+        writeln!(self.writer, "{environment}\n").expect("Failed to write frame");
+        self.frames += 1;
+    }
+
+    fn teardown(&mut self) {
+        self.writer.flush().expect("Failed to flush frames");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::environment::Environment;
+
+    use super::{Control, Renderer};
+
+    struct Counter;
+
+    impl Environment for Counter {
+        type Error = ();
+        type Action = ();
+        type Stimuli = ();
+
+        fn initial_stimuli(&self) {}
+
+        fn update(&mut self, _action: ()) -> Result<(), ()> {
+            Ok(())
+        }
+
+        fn reset(&mut self, _seed: Option<u64>) {}
+
+        fn is_terminal(&self) -> bool {
+            false
+        }
+    }
+
+    /// Logs every call it gets, tagged with its name.
+    struct Recorder {
+        name: char,
+        log: Rc<RefCell<String>>,
+        control: Control,
+    }
+
+    impl Recorder {
+        fn new(name: char, log: &Rc<RefCell<String>>, control: Control) -> Self {
+            Self {
+                name,
+                log: Rc::clone(log),
+                control,
+            }
+        }
+    }
+
+    impl Renderer<Counter> for Recorder {
+        fn setup(&mut self) {
+            self.log.borrow_mut().extend(['s', self.name]);
+        }
+
+        fn render(&mut self, _environment: &Counter) {
+            self.log.borrow_mut().extend(['r', self.name]);
+        }
+
+        fn teardown(&mut self) {
+            self.log.borrow_mut().extend(['t', self.name]);
+        }
+
+        fn control(&self) -> Control {
+            self.control
+        }
+    }
+
+    #[test]
+    fn test_renderers_forward_to_every_child() {
+        let log = Rc::new(RefCell::new(String::new()));
+        let mut pair = (
+            Recorder::new('a', &log, Control::Continue),
+            Recorder::new('b', &log, Control::Continue),
+        );
+        pair.setup();
+        pair.render(&Counter);
+        pair.teardown();
+        assert_eq!(*log.borrow(), "sasbrarbtatb");
+        assert_eq!(pair.control(), Control::Continue);
+        pair.1.control = Control::Abort;
+        assert_eq!(pair.control(), Control::Abort);
+
+        log.borrow_mut().clear();
+        let mut list: Vec<Box<dyn Renderer<Counter>>> = vec![
+            Box::new(Recorder::new('a', &log, Control::Continue)),
+            Box::new(Recorder::new('b', &log, Control::Abort)),
+            Box::new(Recorder::new('c', &log, Control::Continue)),
+        ];
+        list.setup();
+        list.render(&Counter);
+        list.teardown();
+        assert_eq!(*log.borrow(), "sasbscrarbrctatbtc");
+        assert_eq!(list.control(), Control::Abort);
+        list.remove(1);
+        assert_eq!(list.control(), Control::Continue);
+        assert_eq!(
+            Vec::<Box<dyn Renderer<Counter>>>::new().control(),
+            Control::Continue
+        );
+    }
 }
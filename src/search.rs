@@ -0,0 +1,587 @@
+//! Graph search
+//!
+//! Many problems are solved by searching for a sequence of actions leading
+//! from a start state to a goal state. A [`Problem`] describes the state
+//! space: the start state, the goal test, the successors of a state with the
+//! cost of reaching them and an estimate of the cost left to a goal.
+//!
+//! A [`Search`] explores the state space from the start state, keeping every
+//! node it reaches in a [`SearchArena`], each node pointing to the node it
+//! was reached from. Which node is expanded next is decided by a
+//! [`FrontierManager`], the same ones the maze
+//! [`PathFinder`](crate::maze::agent::PathFinder) uses:
+//! [`BFSFinder`](crate::maze::agent::graph_based::BFSFinder),
+//! [`DFSFinder`](crate::maze::agent::graph_based::DFSFinder),
+//! [`GreedyFinder`](crate::maze::agent::greedy::GreedyFinder),
+//! [`UniformCostFinder`](crate::maze::agent::uniform_cost::UniformCostFinder)
+//! and [`AStarFinder`](crate::maze::agent::astar::AStarFinder). The frontiers
+//! only hold [`NodeId`]s, and order them from the costs and estimates kept
+//! in the arena.
+//!
+//! ```
+//! use maze::{prelude::*, search};
+//!
+//! let maze = Maze::<8>::with_seed(Coordinates::new(0, 0), Coordinates::new(7, 7), 0, 1);
+//! let solution = search::solve::<_, AStarFinder>(&maze).unwrap();
+//!
+//! assert_eq!(solution.states.len(), 15);
+//! ```
+
+use std::{
+    collections::{HashSet, VecDeque},
+    fmt,
+    hash::Hash,
+    iter,
+    marker::PhantomData,
+    ops::Index,
+};
+
+use crate::{agent::Agent, environment::Environment};
+
+/// State space to search.
+pub trait Problem {
+    type State: Clone + Eq + Hash;
+
+    type Action: Clone;
+
+    fn start(&self) -> Self::State;
+
+    fn is_goal(&self, state: &Self::State) -> bool;
+
+    /// Actions available in `state`, with the state each one leads to and
+    /// its cost.
+    fn successors(&self, state: &Self::State) -> Vec<(Self::Action, Self::State, f32)>;
+
+    /// Estimate of the cost from `state` to the closest goal, which
+    /// [`AStarFinder`](crate::maze::agent::astar::AStarFinder) only finds
+    /// optimal paths with if it never overestimates. No estimate by default.
+    fn heuristic(&self, _state: &Self::State) -> f32 {
+        0.0
+    }
+}
+
+/// How a search chooses the next node to expand.
+pub trait FrontierManager {
+    fn init() -> Self;
+
+    fn is_empty(&self) -> bool;
+
+    /// Removes the next node to expand, `None` when the frontier is empty.
+    fn pop(&mut self) -> Option<NodeId>;
+
+    fn choose<S, A>(&mut self, candidates: Vec<NodeId>, arena: &SearchArena<S, A>);
+
+    /// Every node waiting in the frontier.
+    fn frontier(&self) -> impl Iterator<Item = NodeId> + '_;
+}
+
+/// Index of a node in a [`SearchArena`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(usize);
+
+/// Node of a search: a state and how it was reached.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchNode<S, A> {
+    state: S,
+    parent: Option<(NodeId, A)>,
+    cost: f32,
+    heuristic: f32,
+    depth: usize,
+}
+
+impl<S, A> SearchNode<S, A> {
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    /// Node this one was reached from, `None` for the root.
+    pub fn parent(&self) -> Option<NodeId> {
+        self.parent.as_ref().map(|(parent, _)| *parent)
+    }
+
+    /// Action reaching this node from its parent.
+    pub fn action(&self) -> Option<&A> {
+        self.parent.as_ref().map(|(_, action)| action)
+    }
+
+    /// Cost of the actions from the root, the g-cost.
+    pub fn cost(&self) -> f32 {
+        self.cost
+    }
+
+    /// Estimate of the cost left to a goal, the h-cost.
+    pub fn heuristic(&self) -> f32 {
+        self.heuristic
+    }
+
+    /// Number of actions from the root.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+}
+
+/// Nodes reached by a search, in the order they were reached.
+#[derive(Debug, Clone)]
+pub struct SearchArena<S, A> {
+    nodes: Vec<SearchNode<S, A>>,
+}
+
+impl<S, A> Default for SearchArena<S, A> {
+    fn default() -> Self {
+        Self { nodes: Vec::new() }
+    }
+}
+
+impl<S, A> SearchArena<S, A> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the node a search starts from.
+    pub fn root(&mut self, state: S, heuristic: f32) -> NodeId {
+        self.insert(SearchNode {
+            state,
+            parent: None,
+            cost: 0.0,
+            heuristic,
+            depth: 0,
+        })
+    }
+
+    /// Adds a node reached from `parent` by an action of cost `step`.
+    pub fn child(
+        &mut self,
+        parent: NodeId,
+        action: A,
+        state: S,
+        step: f32,
+        heuristic: f32,
+    ) -> NodeId {
+        let from = &self[parent];
+        let (cost, depth) = (from.cost + step, from.depth + 1);
+
+        self.insert(SearchNode {
+            state,
+            parent: Some((parent, action)),
+            cost,
+            heuristic,
+            depth,
+        })
+    }
+
+    fn insert(&mut self, node: SearchNode<S, A>) -> NodeId {
+        self.nodes.push(node);
+        NodeId(self.nodes.len() - 1)
+    }
+
+    /// Number of nodes reached.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// The node and the nodes it was reached through, up to the root.
+    pub fn ancestors(&self, id: NodeId) -> impl Iterator<Item = &SearchNode<S, A>> {
+        iter::successors(Some(&self[id]), |node| {
+            node.parent().map(|parent| &self[parent])
+        })
+    }
+}
+
+impl<S: Clone, A: Clone> SearchArena<S, A> {
+    /// States from the root to the node.
+    pub fn states(&self, id: NodeId) -> Vec<S> {
+        let mut states: Vec<_> = self.ancestors(id).map(|node| node.state.clone()).collect();
+        states.reverse();
+        states
+    }
+
+    /// Actions from the root to the node.
+    pub fn actions(&self, id: NodeId) -> Vec<A> {
+        let mut actions: Vec<_> = self
+            .ancestors(id)
+            .filter_map(|node| node.action().cloned())
+            .collect();
+        actions.reverse();
+        actions
+    }
+}
+
+impl<S, A> Index<NodeId> for SearchArena<S, A> {
+    type Output = SearchNode<S, A>;
+
+    fn index(&self, index: NodeId) -> &Self::Output {
+        &self.nodes[index.0]
+    }
+}
+
+/// Actions leading from the start state to a goal state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solution<S, A> {
+    /// States from the start to the goal, both included.
+    pub states: Vec<S>,
+    pub actions: Vec<A>,
+    pub cost: f32,
+    /// Number of nodes expanded to find the solution.
+    pub expanded: usize,
+}
+
+/// Search of a [`Problem`], expanding the nodes in the order of `F`.
+///
+/// The goal test is run on the nodes being expanded, and states are only
+/// expanded once, so that [`AStarFinder`](crate::maze::agent::astar::AStarFinder)
+/// finds optimal solutions with consistent heuristics.
+pub struct Search<P: Problem, F: FrontierManager> {
+    arena: SearchArena<P::State, P::Action>,
+    frontier: F,
+    expanded: HashSet<P::State>,
+    goal: Option<NodeId>,
+    /// Node expanded by the last step, if any.
+    last: Option<NodeId>,
+}
+
+impl<P: Problem, F: FrontierManager + Clone> Clone for Search<P, F> {
+    fn clone(&self) -> Self {
+        Self {
+            arena: self.arena.clone(),
+            frontier: self.frontier.clone(),
+            expanded: self.expanded.clone(),
+            goal: self.goal,
+            last: self.last,
+        }
+    }
+}
+
+impl<P, F> fmt::Debug for Search<P, F>
+where
+    P: Problem,
+    P::State: fmt::Debug,
+    P::Action: fmt::Debug,
+    F: FrontierManager + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Search")
+            .field("arena", &self.arena)
+            .field("frontier", &self.frontier)
+            .field("expanded", &self.expanded)
+            .field("goal", &self.goal)
+            .field("last", &self.last)
+            .finish()
+    }
+}
+
+impl<P: Problem, F: FrontierManager> Search<P, F> {
+    pub fn new(problem: &P) -> Self {
+        let start = problem.start();
+
+        let mut arena = SearchArena::new();
+        let heuristic = problem.heuristic(&start);
+        let root = arena.root(start, heuristic);
+        let mut frontier = F::init();
+        frontier.choose(vec![root], &arena);
+
+        Self {
+            arena,
+            frontier,
+            expanded: HashSet::new(),
+            goal: None,
+            last: None,
+        }
+    }
+
+    /// Expands the next node of the frontier. Returns whether the search is
+    /// over, having found a goal or emptied the frontier.
+    pub fn step(&mut self, problem: &P) -> bool {
+        self.step_with(problem, |state| problem.heuristic(state))
+    }
+
+    /// Like [`step`](Self::step), estimating the cost left with `heuristic`
+    /// instead of [`Problem::heuristic`].
+    pub fn step_with(&mut self, problem: &P, heuristic: impl Fn(&P::State) -> f32) -> bool {
+        self.last = None;
+        if self.is_over() {
+            return true;
+        }
+
+        // Skips the states reached again before being expanded
+        let Some(node) = iter::from_fn(|| self.frontier.pop())
+            .find(|node| !self.expanded.contains(self.arena[*node].state()))
+        else {
+            return true;
+        };
+
+        let state = self.arena[node].state().clone();
+        self.expanded.insert(state.clone());
+        self.last = Some(node);
+
+        if problem.is_goal(&state) {
+            self.goal = Some(node);
+            return true;
+        }
+
+        let mut candidates = Vec::new();
+        for (action, next, step) in problem.successors(&state) {
+            if !self.expanded.contains(&next) {
+                let heuristic = heuristic(&next);
+                candidates.push(self.arena.child(node, action, next, step, heuristic));
+            }
+        }
+        self.frontier.choose(candidates, &self.arena);
+
+        self.is_over()
+    }
+
+    /// Expands nodes until the search is over.
+    pub fn run(&mut self, problem: &P) -> Option<Solution<P::State, P::Action>> {
+        while !self.step(problem) {}
+
+        self.solution()
+    }
+
+    /// Whether a goal was found or the frontier emptied.
+    pub fn is_over(&self) -> bool {
+        self.goal.is_some() || self.frontier.is_empty()
+    }
+
+    /// Solution found, if any.
+    pub fn solution(&self) -> Option<Solution<P::State, P::Action>> {
+        self.goal.map(|goal| Solution {
+            states: self.arena.states(goal),
+            actions: self.arena.actions(goal),
+            cost: self.arena[goal].cost(),
+            expanded: self.expanded.len(),
+        })
+    }
+
+    /// Node expanded by the last step, `None` if it expanded nothing.
+    pub fn last_expanded(&self) -> Option<NodeId> {
+        self.last
+    }
+
+    /// Nodes reached so far.
+    pub fn arena(&self) -> &SearchArena<P::State, P::Action> {
+        &self.arena
+    }
+
+    /// States already expanded.
+    pub fn expanded(&self) -> &HashSet<P::State> {
+        &self.expanded
+    }
+
+    /// Nodes waiting to be expanded.
+    pub fn frontier(&self) -> impl Iterator<Item = &SearchNode<P::State, P::Action>> + '_ {
+        self.frontier.frontier().map(|node| &self.arena[node])
+    }
+}
+
+/// Searches the problem until it finds a goal, expanding the nodes in the
+/// order of `F`.
+pub fn solve<P: Problem, F: FrontierManager>(problem: &P) -> Option<Solution<P::State, P::Action>> {
+    Search::<P, F>::new(problem).run(problem)
+}
+
+/// Agent solving its environment as a [`Problem`] from the current state,
+/// then playing the actions of the solution one per step.
+///
+/// The agent stops right away when the problem has no solution.
+pub struct Planner<E: Problem, F: FrontierManager> {
+    plan: VecDeque<E::Action>,
+    solution: Option<Solution<E::State, E::Action>>,
+    frontier: PhantomData<F>,
+}
+
+impl<E: Problem, F: FrontierManager> Planner<E, F> {
+    /// Solution found when the agent was created, if any.
+    pub fn solution(&self) -> Option<&Solution<E::State, E::Action>> {
+        self.solution.as_ref()
+    }
+
+    /// Actions left to play.
+    pub fn plan(&self) -> impl Iterator<Item = &E::Action> {
+        self.plan.iter()
+    }
+}
+
+impl<E: Problem, F: FrontierManager> fmt::Debug for Planner<E, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Planner")
+            .field("solved", &self.solution.is_some())
+            .field("actions_left", &self.plan.len())
+            .finish()
+    }
+}
+
+impl<E, F> Agent for Planner<E, F>
+where
+    E: Environment + Problem<Action = <E as Environment>::Action>,
+    <E as Environment>::Action: Clone,
+    F: FrontierManager,
+{
+    type Error = <E as Environment>::Error;
+
+    type Action = <E as Environment>::Action;
+
+    type Stimuli = E::Stimuli;
+
+    type Environment = E;
+
+    fn new(environment: &Self::Environment) -> Self {
+        let solution = solve::<E, F>(environment);
+
+        Self {
+            plan: solution
+                .as_ref()
+                .map(|solution| solution.actions.iter().cloned().collect())
+                .unwrap_or_default(),
+            solution,
+            frontier: PhantomData,
+        }
+    }
+
+    fn act(&mut self, environment: &mut Self::Environment) -> Result<(), Self::Error> {
+        if let Some(action) = self.plan.pop_front() {
+            environment.update(action)?;
+        }
+
+        Ok(())
+    }
+
+    fn should_stop(&self) -> bool {
+        self.plan.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crate::{
+        agent::Agent,
+        maze::{
+            agent::{
+                astar::AStarFinder,
+                graph_based::{BFSFinder, DFSFinder},
+                greedy::GreedyFinder,
+                PathFinder,
+            },
+            coordinates::Coordinates,
+            Ground, Maze,
+        },
+        render::Headless,
+        simulator::Simulator,
+    };
+
+    use super::{solve, Problem, SearchArena};
+
+    /// Reaching a number from 1 by adding one or doubling.
+    struct Doubling(u32);
+
+    impl Problem for Doubling {
+        type State = u32;
+
+        type Action = &'static str;
+
+        fn start(&self) -> u32 {
+            1
+        }
+
+        fn is_goal(&self, state: &u32) -> bool {
+            *state == self.0
+        }
+
+        fn successors(&self, state: &u32) -> Vec<(&'static str, u32, f32)> {
+            [("+1", state + 1, 1.0), ("x2", state * 2, 1.0)]
+                .into_iter()
+                .filter(|(_, next, _)| *next <= self.0)
+                .collect()
+        }
+
+        fn heuristic(&self, state: &u32) -> f32 {
+            self.0.saturating_sub(*state) as f32 / self.0.max(1) as f32
+        }
+    }
+
+    #[test]
+    fn test_generic_search() {
+        let mut arena = SearchArena::new();
+        let root = arena.root('a', 2.0);
+        let b = arena.child(root, 1, 'b', 0.5, 1.0);
+        let c = arena.child(b, 2, 'c', 1.5, 0.0);
+        assert_eq!(arena.len(), 3);
+        assert_eq!(arena[c].parent(), Some(b));
+        assert_eq!((arena[c].cost(), arena[c].depth()), (2.0, 2));
+        assert_eq!(arena.states(c), ['a', 'b', 'c']);
+        assert_eq!(arena.actions(c), [1, 2]);
+
+        let problem = Doubling(10);
+        let bfs = solve::<_, BFSFinder>(&problem).unwrap();
+        assert_eq!(bfs.actions, ["+1", "x2", "+1", "x2"]);
+        assert_eq!(bfs.states, [1, 2, 4, 5, 10]);
+        assert_eq!(bfs.cost, 4.0);
+        assert_eq!(solve::<_, AStarFinder>(&problem).unwrap().cost, 4.0);
+        assert_eq!(
+            solve::<_, DFSFinder>(&problem).unwrap().states.last(),
+            Some(&10)
+        );
+        assert!(solve::<_, GreedyFinder>(&problem).is_some());
+        assert_eq!(solve::<_, BFSFinder>(&Doubling(0)), None);
+
+        // The maze is a problem too, solved as the path finders do
+        let maze = || {
+            Maze::<16>::from_cells(
+                Coordinates::new(0, 0),
+                Coordinates::new(15, 15),
+                [[Ground::Free; 16]; 16],
+            )
+        };
+        let solution = solve::<_, AStarFinder>(&maze()).unwrap();
+        assert_eq!(solution.states.len(), 31);
+
+        let bfs = PathFinder::<16, BFSFinder>::new(&maze());
+        let mut simulator = Simulator::new(bfs, Headless, maze());
+        simulator.run_episodes(1).unwrap();
+        let path = simulator.agent().solution().unwrap();
+        assert_eq!(path.len(), 31);
+        assert_eq!(simulator.environment().trace().steps, 16 * 16);
+    }
+
+    #[test]
+    fn test_path_finder_steps_independent_of_depth() {
+        const N: usize = 100;
+        const WINDOW: usize = 100;
+
+        // Depth-first search of an open maze goes one cell deeper at every
+        // step, and doesn't reach the goal in 9000 steps
+        let mut maze = Maze::<N>::from_cells(
+            Coordinates::new(0, 0),
+            Coordinates::new(99, 99),
+            [[Ground::Free; N]; N],
+        );
+        let mut finder = PathFinder::<N, DFSFinder>::new(&maze);
+
+        // Only the fastest windows of steps are compared, leaving out the
+        // rehashes and the scheduler
+        let mut windows = Vec::new();
+        while windows.len() < 90 {
+            let start = Instant::now();
+            for _ in 0..WINDOW {
+                finder.act(&mut maze).unwrap();
+            }
+            windows.push(start.elapsed());
+        }
+        assert!(finder.current_solution.len() > 8000);
+
+        let fastest = |windows: &[Duration]| windows.iter().min().copied().unwrap();
+        let (shallow, deep) = (
+            fastest(&windows[..10]),
+            fastest(&windows[windows.len() - 10..]),
+        );
+        assert!(
+            deep < shallow * 4,
+            "steps {shallow:?} near the root, {deep:?} deep down"
+        );
+    }
+}
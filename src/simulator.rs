@@ -1,7 +1,22 @@
 use std::fmt::Debug;
+use std::time::{Duration, Instant};
 
 use crate::agent::Agent;
-use crate::render::Renderer;
+use crate::environment::{Environment, Resolution, SharedEnvironment};
+use crate::render::{Control, Renderer};
+
+/// Statistics of a single episode run by a [`Simulator`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EpisodeStats {
+    pub episode: usize,
+    /// Number of times the agent acted.
+    pub steps: usize,
+    /// The environment reached a terminal state.
+    pub terminated: bool,
+    /// The episode was cut by the step limit.
+    pub truncated: bool,
+    pub elapsed: Duration,
+}
 
 pub struct Simulator<A, R>
 where
@@ -11,6 +26,9 @@ where
     agent: A,
     renderer: R,
     environment: A::Environment,
+    max_steps: Option<usize>,
+    seed: Option<u64>,
+    aborted: bool,
 }
 
 impl<A, R> Simulator<A, R>
@@ -23,24 +41,353 @@ where
             environment,
             agent,
             renderer,
+            max_steps: None,
+            seed: None,
+            aborted: false,
         }
     }
 
+    /// Cuts every episode after a number of steps.
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = Some(max_steps);
+        self
+    }
+
+    /// Reseeds the environment before each episode, using `seed + episode`.
+    ///
+    /// Without a seed the environment keeps its layout across episodes.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn agent(&self) -> &A {
+        &self.agent
+    }
+
+    pub fn environment(&self) -> &A::Environment {
+        &self.environment
+    }
+
+    /// Whether the last run was stopped by the renderer.
+    pub fn aborted(&self) -> bool {
+        self.aborted
+    }
+
+    fn render(&mut self) -> bool {
+        self.renderer.render(&self.environment);
+        self.aborted = self.renderer.control() == Control::Abort;
+        self.aborted
+    }
+
     pub fn simulate(&mut self) -> Result<(), A::Error> {
         self.renderer.setup();
 
         eprintln!("Starting acting with agent: {:?}", self.agent);
-        self.renderer.render(&self.environment);
+        let mut aborted = self.render();
 
-        while !self.agent.should_stop() {
-            self.agent.act(&mut self.environment)?;
+        while !aborted && !self.agent.should_stop() {
+            if let Err(error) = self.agent.act(&mut self.environment) {
+                self.renderer.teardown();
+                return Err(error);
+            }
+            aborted = self.render();
+        }
+
+        if aborted {
+            eprintln!("Simulation aborted");
+        } else {
+            eprintln!("Solution found:");
             self.renderer.render(&self.environment);
         }
 
-        eprintln!("Solution found:");
+        self.renderer.teardown();
+        Ok(())
+    }
+
+    /// Makes the agent act once and renders the result, unless the agent
+    /// already stopped. Returns whether the agent stopped.
+    ///
+    /// Setting up and tearing down the renderer is left to the caller.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the agent fails to act.
+    pub fn step(&mut self) -> Result<bool, A::Error> {
+        if !self.agent.should_stop() {
+            self.agent.act(&mut self.environment)?;
+            self.render();
+        }
+
+        Ok(self.agent.should_stop())
+    }
+
+    /// Runs several episodes, resetting the environment and the agent before
+    /// each one.
+    ///
+    /// An episode ends when the agent stops, the environment reaches a
+    /// terminal state or the step limit is hit. If the renderer aborts, the
+    /// current episode is recorded as truncated and no more episodes are run.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the agent fails to act.
+    pub fn run_episodes(&mut self, episodes: usize) -> Result<Vec<EpisodeStats>, A::Error> {
+        let mut stats = Vec::with_capacity(episodes);
+
+        self.renderer.setup();
+        self.aborted = false;
+
+        for episode in 0..episodes {
+            if self.aborted {
+                break;
+            }
+
+            let start = Instant::now();
+
+            self.environment
+                .reset(self.seed.map(|seed| seed.wrapping_add(episode as u64)));
+            self.agent.reset(&self.environment);
+
+            let mut steps = 0;
+            let mut truncated = self.render();
+
+            while !truncated && !self.agent.should_stop() && !self.environment.is_terminal() {
+                if self.max_steps.is_some_and(|max_steps| steps >= max_steps) {
+                    truncated = true;
+                    break;
+                }
+
+                if let Err(error) = self.agent.act(&mut self.environment) {
+                    self.renderer.teardown();
+                    return Err(error);
+                }
+                truncated = self.render();
+                steps += 1;
+            }
+
+            stats.push(EpisodeStats {
+                episode,
+                steps,
+                terminated: self.environment.is_terminal(),
+                truncated,
+                elapsed: start.elapsed(),
+            });
+        }
+
+        self.renderer.teardown();
+        Ok(stats)
+    }
+}
+
+/// An agent acting in an environment shared with agents of other types.
+pub type SharedAgent<E, Error> = Box<
+    dyn Agent<
+        Error = Error,
+        Action = <E as Environment>::Action,
+        Stimuli = <E as Environment>::Stimuli,
+        Environment = E,
+    >,
+>;
+
+/// Simulates several agents acting in the same environment.
+///
+/// Every tick each agent that hasn't stopped acts once, in the order the
+/// agents were added. With [`Resolution::TurnBased`] the environment is
+/// rendered after every single action, with [`Resolution::Simultaneous`]
+/// once per tick.
+pub struct MultiSimulator<E, Error, R>
+where
+    E: SharedEnvironment,
+    R: Renderer<E>,
+{
+    agents: Vec<SharedAgent<E, Error>>,
+    renderer: R,
+    environment: E,
+    resolution: Resolution,
+    max_ticks: Option<usize>,
+    aborted: bool,
+}
+
+impl<E, Error, R> MultiSimulator<E, Error, R>
+where
+    E: SharedEnvironment,
+    R: Renderer<E>,
+{
+    pub fn new(renderer: R, environment: E) -> Self {
+        Self {
+            agents: Vec::new(),
+            renderer,
+            environment,
+            resolution: Resolution::default(),
+            max_ticks: None,
+            aborted: false,
+        }
+    }
+
+    /// Adds an agent, which gets the next agent index of the environment.
+    pub fn with_agent<A>(mut self, agent: A) -> Self
+    where
+        A: Agent<Error = Error, Action = E::Action, Stimuli = E::Stimuli, Environment = E>
+            + 'static,
+    {
+        self.agents.push(Box::new(agent));
+        self
+    }
+
+    pub fn with_resolution(mut self, resolution: Resolution) -> Self {
+        self.resolution = resolution;
+        self
+    }
+
+    /// Stops the simulation after a number of ticks.
+    pub fn with_max_ticks(mut self, max_ticks: usize) -> Self {
+        self.max_ticks = Some(max_ticks);
+        self
+    }
+
+    pub fn environment(&self) -> &E {
+        &self.environment
+    }
+
+    /// Whether the last run was stopped by the renderer.
+    pub fn aborted(&self) -> bool {
+        self.aborted
+    }
+
+    fn render(&mut self) -> bool {
         self.renderer.render(&self.environment);
+        self.aborted = self.renderer.control() == Control::Abort;
+        self.aborted
+    }
+
+    /// Runs until every agent stops.
+    ///
+    /// Returns the tick at which each agent stopped, or `None` for agents
+    /// still running when the tick limit was hit or the renderer aborted.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if any agent fails to act.
+    pub fn simulate(&mut self) -> Result<Vec<Option<usize>>, Error> {
+        let mut finished = vec![None; self.agents.len()];
+        let mut tick = 0;
+
+        self.renderer.setup();
+        let mut aborted = self.render();
+
+        while !aborted
+            && finished.iter().any(Option::is_none)
+            && self.max_ticks.is_none_or(|max_ticks| tick < max_ticks)
+        {
+            self.environment.begin_tick(self.resolution);
+
+            for (index, agent) in self.agents.iter_mut().enumerate() {
+                if aborted || finished[index].is_some() {
+                    continue;
+                }
+
+                if agent.should_stop() {
+                    finished[index] = Some(tick);
+                    continue;
+                }
+
+                self.environment.select_agent(index);
+                if let Err(error) = agent.act(&mut self.environment) {
+                    self.renderer.teardown();
+                    return Err(error);
+                }
+
+                if self.resolution == Resolution::TurnBased {
+                    self.renderer.render(&self.environment);
+                    aborted = self.renderer.control() == Control::Abort;
+                }
+            }
+
+            self.aborted = aborted;
+
+            self.environment.end_tick();
+
+            if self.resolution == Resolution::Simultaneous {
+                aborted = self.render();
+            }
+
+            tick += 1;
+        }
 
         self.renderer.teardown();
-        Ok(())
+        Ok(finished)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        agent::Agent,
+        environment::{Environment, Resolution, SharedEnvironment},
+        maze::{
+            agent::{astar::AStarFinder, graph_based::BFSFinder, PathFinder},
+            coordinates::{Coordinates, Direction},
+            Maze, Path,
+        },
+        render::Headless,
+    };
+
+    use super::MultiSimulator;
+
+    #[test]
+    fn test_race_finders() {
+        let goal = Coordinates::new(7, 7);
+        let maze = Maze::<8>::with_seed(Coordinates::new(0, 0), goal, 20, 3);
+
+        let astar = PathFinder::<8, AStarFinder>::new(&maze);
+        let bfs = PathFinder::<8, BFSFinder>::new(&maze);
+
+        let mut simulator = MultiSimulator::new(Headless, maze)
+            .with_agent(astar)
+            .with_agent(bfs)
+            .with_resolution(Resolution::Simultaneous);
+
+        let finished = simulator.simulate().unwrap();
+        assert!(finished.iter().all(Option::is_some));
+
+        let paths = simulator.environment().agent_paths();
+        assert_eq!(paths.len(), 2);
+        assert!(paths.iter().all(|path| path.last() == goal));
+    }
+
+    #[test]
+    fn test_simultaneous_moves_show_together() {
+        let start = Coordinates::new(0, 0);
+        let mut maze = Maze::<8>::new(start, Coordinates::new(7, 7), 0);
+        let path = Path::new(start);
+
+        for resolution in [Resolution::TurnBased, Resolution::Simultaneous] {
+            maze.reset(None);
+            maze.begin_tick(resolution);
+
+            // Both agents move in the same tick, the second after the first
+            maze.select_agent(0);
+            maze.update(path.walk(Direction::East)).unwrap();
+            maze.select_agent(1);
+            let seen = maze.agent_paths()[0].last();
+            maze.update(path.walk(Direction::South)).unwrap();
+
+            let shown = maze.agent_paths()[1].last();
+
+            match resolution {
+                Resolution::TurnBased => {
+                    assert_eq!(
+                        (seen, shown),
+                        (Coordinates::new(1, 0), Coordinates::new(0, 1))
+                    )
+                }
+                Resolution::Simultaneous => assert_eq!((seen, shown), (start, start)),
+            }
+
+            maze.end_tick();
+            let ends: Vec<_> = maze.agent_paths().iter().map(|path| path.last()).collect();
+            assert_eq!(ends, [Coordinates::new(1, 0), Coordinates::new(0, 1)]);
+        }
     }
 }
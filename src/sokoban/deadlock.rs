@@ -0,0 +1,74 @@
+//! Deadlocks
+//!
+//! Boxes can only be pushed, so some pushes leave a level unsolvable however
+//! the player moves afterwards. The searches skip the pushes leading to the
+//! two simplest kinds of deadlocks:
+//!
+//! - dead squares, from where a box can never reach a goal, such as the
+//!   corners without a goal;
+//! - frozen squares, four boxes or walls in a square, one of the boxes not
+//!   on a goal: none of them can move any more.
+
+use std::collections::VecDeque;
+
+use super::{level::Level, Step};
+
+/// Cells from where a box can't be pushed to any goal, whatever the other
+/// boxes.
+///
+/// Pulls boxes back from the goals: a box reaches a goal from every cell it
+/// can be pulled to.
+pub fn dead_squares(level: &Level) -> Vec<bool> {
+    let cells = level.width() * level.height();
+    let mut live = vec![false; cells];
+    let mut queue: VecDeque<_> = level.goals().collect();
+    queue.iter().for_each(|goal| live[*goal] = true);
+
+    while let Some(cell) = queue.pop_front() {
+        for step in Step::ALL {
+            // Pulling the box from `from` to `cell` needs the player on the
+            // other side of `cell`
+            let pulled = level
+                .free_neighbor(cell, step)
+                .filter(|from| level.free_neighbor(*from, step).is_some());
+
+            if let Some(from) = pulled {
+                if !live[from] {
+                    live[from] = true;
+                    queue.push_back(from);
+                }
+            }
+        }
+    }
+
+    (0..cells)
+        .map(|cell| !level.is_wall(cell) && !live[cell])
+        .collect()
+}
+
+/// Whether the box on `cell` is frozen in a square of boxes and walls.
+///
+/// `boxes` must be sorted.
+pub fn is_frozen(level: &Level, boxes: &[usize], cell: usize) -> bool {
+    let blocked = |cell: Option<usize>| {
+        cell.is_none_or(|cell| level.is_wall(cell) || boxes.binary_search(&cell).is_ok())
+    };
+    let stuck = |cell: Option<usize>| {
+        cell.is_some_and(|cell| boxes.binary_search(&cell).is_ok() && !level.is_goal(cell))
+    };
+
+    // The four squares holding the cell
+    [Step::Up, Step::Down].into_iter().any(|vertical| {
+        [Step::Left, Step::Right].into_iter().any(|horizontal| {
+            let beside = level.neighbor(cell, horizontal);
+            let square = [
+                Some(cell),
+                beside,
+                level.neighbor(cell, vertical),
+                beside.and_then(|beside| level.neighbor(beside, vertical)),
+            ];
+
+            square.into_iter().all(blocked) && square.into_iter().any(stuck)
+        })
+    })
+}
@@ -0,0 +1,253 @@
+//! Levels in the XSB format
+//!
+//! One line per row of the level and one character per cell:
+//!
+//! | cell | meaning |
+//! |------|---------|
+//! | `#` | wall |
+//! | ` `, `-`, `_` | floor |
+//! | `.` | goal |
+//! | `$` | box |
+//! | `*` | box on a goal |
+//! | `@` | player |
+//! | `+` | player on a goal |
+//!
+//! Collections hold several levels, separated by blank lines, comments
+//! starting with `;` or lines such as `Title: ...`.
+//!
+//! ```text
+//! ; 1
+//! #######
+//! #     #
+//! # $ . #
+//! #  @  #
+//! #######
+//! ```
+
+use std::{error::Error, fmt, str::FromStr};
+
+use super::{SokobanState, Step};
+
+/// Error of parsing a [`Level`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseLevelError {
+    Empty,
+    UnknownCell {
+        row: usize,
+        column: usize,
+        cell: char,
+    },
+    MissingPlayer,
+    DuplicatePlayer,
+    /// There must be as many boxes as goals.
+    BoxesGoals {
+        boxes: usize,
+        goals: usize,
+    },
+}
+
+impl fmt::Display for ParseLevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "the level is empty"),
+            Self::UnknownCell { row, column, cell } => write!(
+                f,
+                "unknown cell `{cell}` at row {row}, column {column}, expected one of `# -_.$*@+`"
+            ),
+            Self::MissingPlayer => write!(f, "the level has no player `@`"),
+            Self::DuplicatePlayer => write!(f, "the level has more than one player"),
+            Self::BoxesGoals { boxes, goals } => {
+                write!(f, "the level has {boxes} boxes and {goals} goals")
+            }
+        }
+    }
+}
+
+impl Error for ParseLevelError {}
+
+/// Layout of a Sokoban level and where the player and the boxes start.
+///
+/// Cells are indexed row after row. The rows shorter than the longest one
+/// are padded with floor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Level {
+    width: usize,
+    height: usize,
+    walls: Vec<bool>,
+    goals: Vec<bool>,
+    start: SokobanState,
+}
+
+impl Level {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn is_wall(&self, cell: usize) -> bool {
+        self.walls[cell]
+    }
+
+    pub fn is_goal(&self, cell: usize) -> bool {
+        self.goals[cell]
+    }
+
+    /// Cells of the goals.
+    pub fn goals(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.goals.len()).filter(|cell| self.goals[*cell])
+    }
+
+    /// Where the player and the boxes start.
+    pub fn start(&self) -> &SokobanState {
+        &self.start
+    }
+
+    /// Row and column of a cell.
+    pub fn position(&self, cell: usize) -> (usize, usize) {
+        (cell / self.width, cell % self.width)
+    }
+
+    /// Cell next to `cell`, `None` past the edges.
+    pub fn neighbor(&self, cell: usize, step: Step) -> Option<usize> {
+        let (row, column) = self.position(cell);
+
+        match step {
+            Step::Up if row > 0 => Some(cell - self.width),
+            Step::Down if row + 1 < self.height => Some(cell + self.width),
+            Step::Left if column > 0 => Some(cell - 1),
+            Step::Right if column + 1 < self.width => Some(cell + 1),
+            _ => None,
+        }
+    }
+
+    /// Cell next to `cell` the player or a box can stand on.
+    pub fn free_neighbor(&self, cell: usize, step: Step) -> Option<usize> {
+        self.neighbor(cell, step).filter(|next| !self.walls[*next])
+    }
+
+    /// Parses every level of a collection.
+    pub fn parse_collection(collection: &str) -> Result<Vec<Self>, ParseLevelError> {
+        let mut levels = Vec::new();
+        let mut rows = Vec::new();
+
+        for line in collection.lines().chain([""]) {
+            let line = line.trim_end();
+
+            if is_row(line) {
+                rows.push(line);
+            } else if !rows.is_empty() {
+                levels.push(rows.join("\n").parse()?);
+                rows.clear();
+            }
+        }
+
+        Ok(levels)
+    }
+
+    /// The level, with the player and the boxes of `state`, in the XSB
+    /// format.
+    pub fn draw(&self, state: &SokobanState) -> String {
+        let mut text = String::new();
+
+        for row in 0..self.height {
+            let line: String = (row * self.width..(row + 1) * self.width)
+                .map(|cell| {
+                    let boxed = state.boxes.binary_search(&cell).is_ok();
+
+                    match (self.walls[cell], self.goals[cell]) {
+                        (true, _) => '#',
+                        (_, goal) if cell == state.player => ['@', '+'][goal as usize],
+                        (_, goal) if boxed => ['$', '*'][goal as usize],
+                        (_, true) => '.',
+                        (_, false) => ' ',
+                    }
+                })
+                .collect();
+
+            text.push_str(line.trim_end());
+            text.push('\n');
+        }
+
+        text
+    }
+}
+
+/// Rows of a level are made of cells and hold at least a wall.
+fn is_row(line: &str) -> bool {
+    line.contains('#') && line.chars().all(|cell| " -_#.$*@+".contains(cell))
+}
+
+impl FromStr for Level {
+    type Err = ParseLevelError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rows: Vec<&str> = s
+            .lines()
+            .map(str::trim_end)
+            .filter(|row| !row.is_empty())
+            .collect();
+
+        let height = rows.len();
+        let width = rows
+            .iter()
+            .map(|row| row.chars().count())
+            .max()
+            .unwrap_or(0);
+        if height == 0 {
+            return Err(ParseLevelError::Empty);
+        }
+
+        let mut walls = vec![false; width * height];
+        let mut goals = vec![false; width * height];
+        let mut boxes = Vec::new();
+        let mut player = None;
+
+        for (row, line) in rows.iter().enumerate() {
+            for (column, cell) in line.chars().enumerate() {
+                let index = row * width + column;
+
+                match cell {
+                    '#' => walls[index] = true,
+                    ' ' | '-' | '_' => {}
+                    '.' | '*' | '+' => goals[index] = true,
+                    '$' | '@' => {}
+                    _ => return Err(ParseLevelError::UnknownCell { row, column, cell }),
+                }
+                if matches!(cell, '$' | '*') {
+                    boxes.push(index);
+                }
+                if matches!(cell, '@' | '+') && player.replace(index).is_some() {
+                    return Err(ParseLevelError::DuplicatePlayer);
+                }
+            }
+        }
+
+        let goal_count = goals.iter().filter(|goal| **goal).count();
+        if boxes.len() != goal_count {
+            return Err(ParseLevelError::BoxesGoals {
+                boxes: boxes.len(),
+                goals: goal_count,
+            });
+        }
+
+        Ok(Self {
+            width,
+            height,
+            walls,
+            goals,
+            start: SokobanState {
+                player: player.ok_or(ParseLevelError::MissingPlayer)?,
+                boxes,
+            },
+        })
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.draw(&self.start))
+    }
+}
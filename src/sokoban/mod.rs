@@ -0,0 +1,372 @@
+//! Sokoban
+//!
+//! The player walks around a warehouse pushing boxes, one at a time, until
+//! every box sits on a goal. Boxes can't be pulled, so a careless push can
+//! leave the level unsolvable, see [`deadlock`].
+//!
+//! Levels are read from the XSB format, see [`level`]. A [`Sokoban`] is
+//! both an [`Environment`] and a [`Problem`], solved by the
+//! [`Planner`](crate::search::Planner) agent with any search strategy.
+
+use std::{error::Error, fmt};
+
+use crate::{environment::Environment, search::Problem};
+
+use self::{
+    deadlock::{dead_squares, is_frozen},
+    level::Level,
+};
+
+pub mod deadlock;
+pub mod level;
+
+pub use level::ParseLevelError;
+
+/// Errors of the Sokoban environment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SokobanError {
+    /// The player walks into a wall, or pushes a box into a wall or another
+    /// box.
+    Blocked,
+}
+
+impl fmt::Display for SokobanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Blocked => write!(f, "the move is blocked"),
+        }
+    }
+}
+
+impl Error for SokobanError {}
+
+/// Move of the player, pushing the box in front of it if any.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Step {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Step {
+    pub const ALL: [Step; 4] = [Step::Up, Step::Down, Step::Left, Step::Right];
+}
+
+/// Cells of the player and of the boxes, indexed as in the [`Level`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SokobanState {
+    pub player: usize,
+    /// Sorted, so that states with the same boxes are equal.
+    pub boxes: Vec<usize>,
+}
+
+/// Stimuli produced by a [`Sokoban`] update.
+///
+/// The reward is `1.0` once every box is on a goal and `0.0` otherwise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SokobanStimuli {
+    pub state: SokobanState,
+    pub moves: usize,
+    pub pushes: usize,
+    pub reward: f64,
+    pub terminal: bool,
+    /// Some box can't reach a goal any more.
+    pub deadlocked: bool,
+}
+
+/// A Sokoban level, played one step at a time.
+#[derive(Debug, Clone)]
+pub struct Sokoban {
+    level: Level,
+    dead: Vec<bool>,
+    state: SokobanState,
+    moves: usize,
+    pushes: usize,
+}
+
+impl Sokoban {
+    pub fn new(level: Level) -> Self {
+        Self {
+            dead: dead_squares(&level),
+            state: level.start().clone(),
+            level,
+            moves: 0,
+            pushes: 0,
+        }
+    }
+
+    pub fn level(&self) -> &Level {
+        &self.level
+    }
+
+    pub fn state(&self) -> &SokobanState {
+        &self.state
+    }
+
+    /// Steps played since the start of the episode.
+    pub fn moves(&self) -> usize {
+        self.moves
+    }
+
+    /// Steps that pushed a box since the start of the episode.
+    pub fn pushes(&self) -> usize {
+        self.pushes
+    }
+
+    pub fn is_solved(&self, state: &SokobanState) -> bool {
+        state.boxes.iter().all(|cell| self.level.is_goal(*cell))
+    }
+
+    /// Whether a box is on a dead square, or frozen away from the goals.
+    pub fn is_deadlocked(&self, state: &SokobanState) -> bool {
+        state
+            .boxes
+            .iter()
+            .any(|cell| self.dead[*cell] || is_frozen(&self.level, &state.boxes, *cell))
+    }
+
+    /// The state after the player steps, and whether it pushed a box.
+    /// `None` when the step is blocked.
+    pub fn step(&self, state: &SokobanState, step: Step) -> Option<(SokobanState, bool)> {
+        let player = self.level.free_neighbor(state.player, step)?;
+
+        let Ok(pushed) = state.boxes.binary_search(&player) else {
+            let boxes = state.boxes.clone();
+            return Some((SokobanState { player, boxes }, false));
+        };
+
+        let target = self.level.free_neighbor(player, step)?;
+        if state.boxes.binary_search(&target).is_ok() {
+            return None;
+        }
+
+        let mut boxes = state.boxes.clone();
+        boxes.remove(pushed);
+        let index = boxes.binary_search(&target).unwrap_err();
+        boxes.insert(index, target);
+
+        Some((SokobanState { player, boxes }, true))
+    }
+
+    fn stimuli(&self) -> SokobanStimuli {
+        let terminal = self.is_solved(&self.state);
+
+        SokobanStimuli {
+            state: self.state.clone(),
+            moves: self.moves,
+            pushes: self.pushes,
+            reward: if terminal { 1.0 } else { 0.0 },
+            terminal,
+            deadlocked: self.is_deadlocked(&self.state),
+        }
+    }
+}
+
+impl Environment for Sokoban {
+    type Error = SokobanError;
+
+    type Action = Step;
+
+    type Stimuli = SokobanStimuli;
+
+    fn initial_stimuli(&self) -> Self::Stimuli {
+        self.stimuli()
+    }
+
+    fn update(&mut self, action: Self::Action) -> Result<Self::Stimuli, Self::Error> {
+        let (state, pushed) = self
+            .step(&self.state, action)
+            .ok_or(SokobanError::Blocked)?;
+
+        self.state = state;
+        self.moves += 1;
+        self.pushes += usize::from(pushed);
+
+        Ok(self.stimuli())
+    }
+
+    /// Starts the level over, the seed is ignored.
+    fn reset(&mut self, _seed: Option<u64>) -> Self::Stimuli {
+        self.state = self.level.start().clone();
+        self.moves = 0;
+        self.pushes = 0;
+
+        self.stimuli()
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.is_solved(&self.state)
+    }
+}
+
+/// Solving the level from the current state with as few steps as possible.
+/// Pushes into deadlocks are left out.
+impl Problem for Sokoban {
+    type State = SokobanState;
+
+    type Action = Step;
+
+    fn start(&self) -> Self::State {
+        self.state.clone()
+    }
+
+    fn is_goal(&self, state: &Self::State) -> bool {
+        self.is_solved(state)
+    }
+
+    fn successors(&self, state: &Self::State) -> Vec<(Self::Action, Self::State, f32)> {
+        Step::ALL
+            .into_iter()
+            .filter_map(|step| {
+                let (next, pushed) = self.step(state, step)?;
+
+                if pushed {
+                    let moved = self.level.free_neighbor(next.player, step)?;
+                    if self.dead[moved] || is_frozen(&self.level, &next.boxes, moved) {
+                        return None;
+                    }
+                }
+
+                Some((step, next, 1.0))
+            })
+            .collect()
+    }
+
+    /// Sum of the distances of the boxes to their closest goal, each box
+    /// being pushed at least that many times.
+    fn heuristic(&self, state: &Self::State) -> f32 {
+        state
+            .boxes
+            .iter()
+            .map(|cell| {
+                let (row, column) = self.level.position(*cell);
+
+                self.level
+                    .goals()
+                    .map(|goal| {
+                        let (goal_row, goal_column) = self.level.position(goal);
+                        row.abs_diff(goal_row) + column.abs_diff(goal_column)
+                    })
+                    .min()
+                    .unwrap_or(0)
+            })
+            .sum::<usize>() as f32
+    }
+}
+
+impl fmt::Display for Sokoban {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.level.draw(&self.state))?;
+        write!(
+            f,
+            "moves: {} | pushes: {}{}",
+            self.moves,
+            self.pushes,
+            if self.is_solved(&self.state) {
+                ", solved!"
+            } else if self.is_deadlocked(&self.state) {
+                ", deadlocked"
+            } else {
+                ""
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        agent::Agent,
+        maze::agent::{astar::AStarFinder, graph_based::BFSFinder},
+        render::FrameWriter,
+        search::{solve, Planner},
+        simulator::Simulator,
+    };
+
+    use super::{
+        deadlock::{dead_squares, is_frozen},
+        level::Level,
+        ParseLevelError, Sokoban, SokobanState, Step,
+    };
+
+    const LEVELS: &str = "\
+; 1
+#######
+#     #
+# $ . #
+#  @  #
+#######
+
+Title: two boxes
+########
+#.  @  #
+# $$   #
+#.     #
+########
+";
+
+    #[test]
+    fn test_sokoban() {
+        let levels = Level::parse_collection(LEVELS).unwrap();
+        assert_eq!(levels.len(), 2);
+        assert_eq!(
+            levels[0].to_string(),
+            LEVELS
+                .lines()
+                .skip(1)
+                .take(5)
+                .collect::<Vec<_>>()
+                .join("\n")
+                + "\n"
+        );
+        assert_eq!(
+            "#@$#".parse::<Level>(),
+            Err(ParseLevelError::BoxesGoals { boxes: 1, goals: 0 })
+        );
+        assert_eq!("# #".parse::<Level>(), Err(ParseLevelError::MissingPlayer));
+        assert!(matches!(
+            "#@x#".parse::<Level>(),
+            Err(ParseLevelError::UnknownCell { cell: 'x', .. })
+        ));
+
+        // Only the cells between the box and the goal are live
+        let level = &levels[0];
+        let dead = dead_squares(level);
+        let live: Vec<_> = (0..dead.len())
+            .filter(|cell| !level.is_wall(*cell) && !dead[*cell])
+            .map(|cell| level.position(cell))
+            .collect();
+        assert_eq!(live, [(2, 2), (2, 3), (2, 4)]);
+
+        let sokoban = Sokoban::new(level.clone());
+        let bfs = solve::<_, BFSFinder>(&sokoban).unwrap();
+        assert_eq!(
+            bfs.actions,
+            [Step::Left, Step::Left, Step::Up, Step::Right, Step::Right]
+        );
+
+        // Boxes side by side against the wall, away from the goals
+        let two = &levels[1];
+        let frozen = [9, 10];
+        assert!(is_frozen(two, &frozen, 9));
+        assert!(!is_frozen(two, &[9, 11], 9));
+        let sokoban = Sokoban::new(two.clone());
+        let stuck = SokobanState {
+            player: 12,
+            boxes: vec![9, 10],
+        };
+        assert!(sokoban.is_deadlocked(&stuck));
+        assert!(!sokoban.is_deadlocked(two.start()));
+
+        // The planner plays the solution, frame after frame
+        let astar = solve::<_, AStarFinder>(&sokoban).unwrap();
+        let planner = Planner::<_, AStarFinder>::new(&sokoban);
+        let mut simulator = Simulator::new(planner, FrameWriter::new(Vec::new()), sokoban);
+        let stats = simulator.run_episodes(1).unwrap();
+        assert!(stats[0].terminated);
+        assert_eq!(simulator.environment().moves(), astar.actions.len());
+        // As many pushes as the heuristic bound of the start
+        assert_eq!(simulator.environment().pushes(), 5);
+    }
+}
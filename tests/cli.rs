@@ -0,0 +1,157 @@
+//! Runs the `path_finder` binary headless, against the library it is built
+//! with.
+
+use std::{
+    fs,
+    path::PathBuf,
+    process::{Command, Output},
+};
+
+use maze::{maze::file::MazeFile, prelude::*};
+
+fn path_finder(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_path_finder"))
+        .args(args)
+        .output()
+        .expect("failed to run path_finder")
+}
+
+/// File in the temporary directory, unique to this test run.
+fn temp_file(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("path_finder-{}-{name}", std::process::id()))
+}
+
+#[test]
+fn test_generate_then_solve() {
+    let maze_path = temp_file("maze.txt");
+    let maze_file = maze_path.to_str().unwrap();
+
+    let generate = path_finder(&[
+        "generate",
+        "--size",
+        "16",
+        "--seed",
+        "5",
+        "--generator",
+        "walk",
+        "-o",
+        maze_file,
+    ]);
+    assert!(generate.status.success());
+
+    let file: MazeFile = fs::read_to_string(&maze_path).unwrap().parse().unwrap();
+    assert_eq!(file.size(), 16);
+    assert_eq!(file.goal(), Coordinates::new(15, 15));
+
+    let solve = path_finder(&["solve", maze_file, "--finder", "astar", "--render", "none"]);
+    assert!(solve.status.success());
+
+    let path = String::from_utf8(solve.stdout).unwrap();
+    let cells: Vec<&str> = path.lines().collect();
+    assert_eq!(cells.first(), Some(&"0,0"));
+    assert_eq!(cells.last(), Some(&"15,15"));
+
+    // The binary finds the same path as the library
+    let maze = file.to_maze::<16>();
+    let finder = PathFinder::<16, AStarFinder>::new(&maze);
+    let mut simulator = Simulator::new(finder, Headless, maze);
+    simulator.run_episodes(1).unwrap();
+    assert_eq!(simulator.environment().current_path.len(), cells.len());
+
+    let image_path = temp_file("maze.svg");
+    let render = path_finder(&[
+        "render",
+        maze_file,
+        "-o",
+        image_path.to_str().unwrap(),
+        "--finder",
+        "dfs",
+    ]);
+    assert!(render.status.success());
+    assert!(fs::read_to_string(&image_path).unwrap().starts_with("<svg"));
+
+    let _ = fs::remove_file(maze_path);
+    let _ = fs::remove_file(image_path);
+}
+
+#[test]
+fn test_bench_report() {
+    let bench = path_finder(&[
+        "bench",
+        "--sizes",
+        "8",
+        "--finders",
+        "astar,dfs",
+        "--mazes",
+        "2",
+        "--generator",
+        "walk",
+        "--format",
+        "csv",
+    ]);
+    assert!(bench.status.success());
+
+    let report = String::from_utf8(bench.stdout).unwrap();
+    let lines: Vec<&str> = report.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("size,finder"));
+    assert!(lines[1].starts_with("8,astar (euclidean),2,2,"));
+
+    // Seeds wrap around past the last one
+    let last_seed = path_finder(&[
+        "bench",
+        "--seed",
+        "18446744073709551615",
+        "--sizes",
+        "8",
+        "--finders",
+        "bfs",
+        "--mazes",
+        "2",
+    ]);
+    assert!(last_seed.status.success());
+}
+
+#[test]
+fn test_invalid_combinations() {
+    let heuristic = path_finder(&["bench", "--finders", "bfs", "--heuristic", "manhattan"]);
+    assert_eq!(heuristic.status.code(), Some(2));
+    assert!(String::from_utf8(heuristic.stderr)
+        .unwrap()
+        .contains("--heuristic only applies"));
+
+    let size = path_finder(&["generate", "--size", "12"]);
+    assert_eq!(size.status.code(), Some(2));
+
+    let cell_size = path_finder(&["render", "maze.txt", "-o", "maze.png", "--cell-size", "0"]);
+    assert_eq!(cell_size.status.code(), Some(2));
+
+    let missing = path_finder(&["solve", "no-such-maze.txt"]);
+    assert!(!missing.status.success());
+}
+
+#[test]
+fn test_route() {
+    let graph_path = temp_file("graph.dot");
+    let graph_file = graph_path.to_str().unwrap();
+    fs::write(
+        &graph_path,
+        "digraph {\n  a -> b [weight=1]; b -> d [weight=1]\n  a -> d [weight=5]\n}\n",
+    )
+    .unwrap();
+
+    let route = |finder| {
+        let output = path_finder(&[
+            "route", graph_file, "--from", "a", "--to", "d", "--finder", finder,
+        ]);
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+    assert_eq!(route("bfs"), "a\nd\n");
+    assert_eq!(route("ucs"), "a\nb\nd\n");
+
+    let unknown = path_finder(&["route", graph_file, "--from", "a", "--to", "z"]);
+    assert!(!unknown.status.success());
+
+    let _ = fs::remove_file(graph_path);
+}